@@ -0,0 +1,455 @@
+use crate::*;
+use glam::EulerRot;
+
+const FRAME_TIME: i32 = 33; // ms, ~30fps, stretched to fit the sequence
+const CHANNELS: &str = "CHANNELS 6 Xposition Yposition Zposition Zrotation Xrotation Yrotation";
+
+//#region write_bvh
+
+impl MdlxData {
+    /// Write the hierarchy of bones and helpers with the motion of a sequence as BVH,
+    /// in model coordinates. The sequence is `eopts.pose` (its frame is ignored) or the first one,
+    /// offsets are the differences of pivots. Scaling, DontInherit and billboard flags are not
+    /// represented.
+    pub fn write_bvh(
+        &self,
+        path: &Path,
+        opts: &MdlWriteOptions,
+        eopts: &ExportOptions,
+    ) -> Result<(), MyError> {
+        fs::write(path, self.to_bvh(opts, eopts)?)?;
+        return Ok(());
+    }
+
+    pub fn to_bvh(&self, opts: &MdlWriteOptions, eopts: &ExportOptions) -> Result<String, MyError> {
+        let sequence = match &eopts.pose {
+            Some(spec) => Some(self.find_frame(spec)?.0),
+            None => self.sequences.first(),
+        };
+        let joints: Vec<&Node> = self
+            .bones
+            .iter()
+            .map(|b| &b.base)
+            .chain(self.helpers.iter().map(|h| &h.base))
+            .collect();
+        yes!(joints.is_empty(), return ERR!("No bones or helpers to export"));
+
+        // parent of each joint: its nearest ancestor which is a joint
+        let nodes: HashMap<i32, &Node> =
+            self.nodes().into_iter().map(|(_, n)| (n.object_id, n)).collect();
+        let is_joint = |id: i32| joints.iter().any(|j| j.object_id == id);
+        let parent_of = |joint: &Node| {
+            let mut id = joint.parent_id;
+            for _ in 0..nodes.len() {
+                yes!(id == -1 || is_joint(id), break);
+                id = nodes.get(&id).map_or(-1, |n| n.parent_id);
+            }
+            return yesno!(is_joint(id) && id != joint.object_id, Some(id), None);
+        };
+        let mut children: HashMap<Option<i32>, Vec<&Node>> = HashMap::new();
+        for j in joints.iter() {
+            children.entry(parent_of(j)).or_default().push(j);
+        }
+        children.values_mut().for_each(|list| list.sort_by_key(|j| j.object_id));
+
+        let pivot = |id: i32| {
+            usize::try_from(id)
+                .ok()
+                .and_then(|i| self.pivot_points.get(i))
+                .map_or(Vec3::ZERO, |p| p.position)
+        };
+        let num = |v: f32| v.fmt_with(opts);
+        let vec3 = |v: Vec3| F!("{} {} {}", num(v.x), num(v.y), num(v.z));
+        let roots = children.get(&None).cloned().unwrap_or_default();
+        let single_root = roots.len() == 1;
+
+        // hierarchy, in the order of channels in the motion
+        let mut lines = vec!["HIERARCHY".s()];
+        let mut order: Vec<(&Node, Vec3)> = vec![]; // joint, offset
+        let mut stack: Vec<(&Node, usize, bool)> =
+            roots.iter().rev().map(|j| (*j, yesno!(single_root, 0, 1), true)).collect();
+        if !single_root {
+            lines.extend([
+                "ROOT Root".s(),
+                "{".s(),
+                F!("{}OFFSET 0 0 0", opts.indent(1)),
+                F!("{}{CHANNELS}", opts.indent(1)),
+            ]);
+        }
+        let mut visited: HashSet<i32> = HashSet::new();
+        while let Some((joint, depth, enter)) = stack.pop() {
+            let (indent, indent1) = (opts.indent(depth as u8), opts.indent(depth as u8 + 1));
+            if !enter {
+                lines.push(F!("{indent}}}"));
+                continue;
+            }
+            no!(visited.insert(joint.object_id), continue);
+            let offset = pivot(joint.object_id) - parent_of(joint).map_or(Vec3::ZERO, pivot);
+            let keyword = yesno!(depth == 0, "ROOT", "JOINT");
+            lines.extend([F!("{indent}{keyword} {}", joint.name), F!("{indent}{{")]);
+            lines.extend([F!("{indent1}OFFSET {}", vec3(offset)), F!("{indent1}{CHANNELS}")]);
+            order.push((joint, offset));
+            stack.push((joint, depth, false));
+            let kids = children.get(&Some(joint.object_id)).cloned().unwrap_or_default();
+            if kids.is_empty() {
+                let indent2 = opts.indent(depth as u8 + 2);
+                lines.extend([
+                    F!("{indent1}End Site"),
+                    F!("{indent1}{{"),
+                    F!("{indent2}OFFSET 0 0 0"),
+                    F!("{indent1}}}"),
+                ]);
+            }
+            stack.extend(kids.iter().rev().map(|k| (*k, depth + 1, true)));
+        }
+        yes!(!single_root, lines.push("}".s()));
+
+        // motion
+        let (start, duration) =
+            sequence.map_or((0, 0), |s| (s.start_frame, (s.end_frame - s.start_frame).max(0)));
+        let count = ((duration as f32 / FRAME_TIME as f32).round() as i32).max(1);
+        let frame_time = duration as f32 / count as f32;
+        let frames: Vec<i32> = match sequence {
+            Some(_) => {
+                (0..=count).map(|k| start + (k as f32 * frame_time).round() as i32).collect()
+            },
+            None => vec![0],
+        };
+        let frame_time =
+            fmt_float_lossless(&(yesno!(frame_time > 0.0, frame_time, FRAME_TIME as f32) / 1000.0));
+        lines.extend([
+            "MOTION".s(),
+            F!("Frames: {}", frames.len()),
+            F!("Frame Time: {frame_time}"),
+        ]);
+        for &frame in frames.iter() {
+            let mut values: Vec<String> = yesno!(single_root, vec![], vec!["0".s(); 6]);
+            for (joint, offset) in order.iter() {
+                let (t, r) = match sequence {
+                    Some(s) => {
+                        let time = frame - s.start_frame;
+                        let t = self.sample(&joint.translation, frame, s, time, Vec3::ZERO);
+                        (t, to_quat(self.sample(&joint.rotation, frame, s, time, Vec4::W)))
+                    },
+                    None => (Vec3::ZERO, Quat::IDENTITY),
+                };
+                let (z, x, y) = r.to_euler(EulerRot::ZXY);
+                values.push(vec3(*offset + t));
+                values.push(vec3(Vec3::new(z, x, y).map(f32::to_degrees)));
+            }
+            lines.push(values.join(" "));
+        }
+
+        let eol = &opts.line_ending;
+        return Ok(lines.join(eol) + eol);
+    }
+}
+
+//#endregion
+//#region import_bvh
+
+/// A joint of a BVH hierarchy.
+#[derive(Debug, Default)]
+struct BvhJoint {
+    name: String,
+    offset: Vec3,
+    channels: Vec<String>,
+}
+
+/// A parsed BVH file: joints (in the order of their channels), frame time (seconds)
+/// and the channel values of each frame.
+#[derive(Debug, Default)]
+struct BvhClip {
+    joints: Vec<BvhJoint>,
+    frame_time: f32,
+    motion: Vec<Vec<f32>>,
+}
+
+/// Translation and rotation of a node.
+type NodeTracks = (Option<Animation<Vec3>>, Option<Animation<Vec4>>);
+
+impl MdlxData {
+    /// Add the motion of a BVH file as a new sequence (named after the file) after the last one.
+    /// Joints drive the bones and helpers of the same names (case-insensitive) with linear keys,
+    /// one per frame. Nothing changes if any joint fails.
+    pub fn import_bvh(&mut self, path: &Path) -> Result<(), MyError> {
+        let parsed = fs::read(path)
+            .map_err(MyError::from)
+            .and_then(|bytes| parse_bvh(&String::from_utf8_lossy(&bytes)));
+        let BvhClip { joints, frame_time, motion } =
+            parsed.map_err(|e| e.within(F!("Failed to read BVH {:?}", path)))?;
+        yes!(motion.is_empty(), return ERR!("No frames in BVH {:?}", path));
+
+        let start = self.sequences.iter().map(|s| s.end_frame + 1).max().unwrap_or(0);
+        let frames: Vec<i32> = (0..motion.len())
+            .map(|k| start + (k as f32 * frame_time * 1000.0).round() as i32)
+            .collect();
+        let mut seq = Sequence {
+            name: path.file_stem().map_or("".s(), |s| s.to_string_lossy().into_owned()),
+            start_frame: start,
+            end_frame: *frames.last().unwrap_or(&start),
+            ..Default::default()
+        };
+        (seq.bounds_radius, seq.min_extent, seq.max_extent) = (
+            self.model.extent.bound_radius,
+            self.model.extent.min_extent,
+            self.model.extent.max_extent,
+        );
+
+        // new tracks are built aside, they replace the old ones once every joint is added
+        let nodes: Vec<&Node> = self
+            .bones
+            .iter()
+            .map(|b| &b.base)
+            .chain(self.helpers.iter().map(|h| &h.base))
+            .collect();
+        let mut tracks: Vec<(usize, NodeTracks)> = vec![];
+        let mut column = 0;
+        let mut matched = 0;
+        for joint in joints.iter() {
+            let (first, count) = (column, joint.channels.len());
+            column += count;
+            let Some(n) = nodes.iter().position(|n| n.name.eq_icase(&joint.name)) else {
+                vlog!("BVH joint {:?} matches no bone or helper, skipped.", joint.name);
+                continue;
+            };
+            let has_position = joint.channels.iter().any(|c| c.ends_with("position"));
+            let (mut translations, mut rotations) = (vec![], vec![]);
+            for (&frame, row) in frames.iter().zip(motion.iter()) {
+                let (mut t, mut r) = (Vec3::ZERO, Quat::IDENTITY);
+                for (c, v) in joint.channels.iter().zip(row[first..first + count].iter()) {
+                    match c.as_str() {
+                        "Xposition" => t.x = *v,
+                        "Yposition" => t.y = *v,
+                        "Zposition" => t.z = *v,
+                        "Xrotation" => r *= Quat::from_rotation_x(v.to_radians()),
+                        "Yrotation" => r *= Quat::from_rotation_y(v.to_radians()),
+                        "Zrotation" => r *= Quat::from_rotation_z(v.to_radians()),
+                        _ => {},
+                    }
+                }
+                translations.push((frame, t - joint.offset));
+                rotations.push((frame, from_quat(r.normalize())));
+            }
+            let k = match tracks.iter().position(|t| t.0 == n) {
+                Some(k) => k,
+                None => {
+                    tracks.push((n, (nodes[n].translation.clone(), nodes[n].rotation.clone())));
+                    tracks.len() - 1
+                },
+            };
+            let (owner, (translation, rotation)) = (F!("{:?}", nodes[n].name), &mut tracks[k].1);
+            yes!(
+                has_position,
+                append_keys(translation, translations).map_err(|e| e.within(owner.clone()))?
+            );
+            append_keys(rotation, rotations).map_err(|e| e.within(owner))?;
+            matched += 1;
+        }
+        yes!(matched == 0, return ERR!("No BVH joint in {:?} matches a bone or helper", path));
+        vlog!(
+            "Added sequence {:?} ({} frames) onto {matched} of {} BVH joints.",
+            seq.name,
+            frames.len(),
+            joints.len()
+        );
+        let mut nodes: Vec<&mut Node> = self
+            .bones
+            .iter_mut()
+            .map(|b| &mut b.base)
+            .chain(self.helpers.iter_mut().map(|h| &mut h.base))
+            .collect();
+        for (n, (translation, rotation)) in tracks {
+            (nodes[n].translation, nodes[n].rotation) = (translation, rotation);
+        }
+        self.sequences.push(seq);
+        return Ok(());
+    }
+}
+
+/// Append linear keys to a track (which is created if missing),
+/// keys of an existing Hermite or Bezier track get tangents which make them linear.
+fn append_keys<T: Interpolate>(
+    track: &mut Option<Animation<T>>,
+    keys: Vec<(i32, T)>,
+) -> Result<(), MyError> {
+    let anim = track.get_or_insert_with(|| Animation {
+        interp_type: InterpolationType::Linear,
+        ..Default::default()
+    });
+    yes!(anim.global_seq_id >= 0, return ERR!("its track is in a global sequence"));
+    let typ = anim.interp_type;
+    let cubic = matches!(typ, InterpolationType::Hermite | InterpolationType::Bezier);
+    for (i, &(frame, value)) in keys.iter().enumerate() {
+        let (prev, next) = (keys[i.saturating_sub(1)].1, keys.get(i + 1).map_or(value, |k| k.1));
+        let (itan, otan) =
+            yesno!(cubic, T::linear_tangents(typ, prev, value, next), (value, value));
+        anim.key_frames.push(KeyFrame { frame, value, itan, otan, has_tans: cubic });
+    }
+    return Ok(());
+}
+
+fn parse_bvh(text: &str) -> Result<BvhClip, MyError> {
+    let mut tokens = text.split_whitespace().peekable();
+    let mut next = |what: &str| {
+        tokens.next().ok_or_else(|| MyError::String(F!("unexpected end, expect {what}")))
+    };
+    let number = |s: &str| s.parse::<f32>().or_else(|_| ERR!("bad number {s:?}"));
+
+    yes!(next("HIERARCHY")? != "HIERARCHY", return ERR!("expect HIERARCHY"));
+    let mut joints: Vec<BvhJoint> = vec![];
+    let mut depth = 0; // of open braces
+    let mut end_site = None; // depth of an open End Site
+    loop {
+        let token = next("a joint")?;
+        match token {
+            "ROOT" | "JOINT" => {
+                let mut name: Vec<&str> = vec![];
+                loop {
+                    let t = next("{")?;
+                    yes!(t == "{", break);
+                    name.push(t);
+                }
+                depth += 1;
+                joints.push(BvhJoint { name: name.join(" "), ..Default::default() });
+            },
+            "End" => {
+                yes!(
+                    next("Site")? != "Site" || next("{")? != "{",
+                    return ERR!("expect End Site {{")
+                );
+                depth += 1;
+                end_site = Some(depth);
+            },
+            "OFFSET" => {
+                let v = Vec3::new(number(next("x")?)?, number(next("y")?)?, number(next("z")?)?);
+                match (end_site, joints.last_mut()) {
+                    (None, Some(j)) => j.offset = v,
+                    (Some(_), _) => {},
+                    (None, None) => return ERR!("OFFSET out of a joint"),
+                }
+            },
+            "CHANNELS" => {
+                let n = number(next("channel count")?)? as usize;
+                let channels = (0..n)
+                    .map(|_| next("channel").map(|s| s.s()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let Some(j) = joints.last_mut() else { return ERR!("CHANNELS out of a joint") };
+                j.channels = channels;
+            },
+            "}" => {
+                yes!(depth == 0, return ERR!("unexpected }}"));
+                yes!(end_site == Some(depth), end_site = None);
+                depth -= 1;
+            },
+            "MOTION" if depth == 0 => break,
+            other => return ERR!("unexpected {other:?}"),
+        }
+    }
+
+    yes!(next("Frames:")? != "Frames:", return ERR!("expect Frames:"));
+    let count = number(next("frame count")?)? as usize;
+    yes!(next("Frame")? != "Frame" || next("Time:")? != "Time:", return ERR!("expect Frame Time:"));
+    let frame_time = number(next("frame time")?)?;
+    yes!(!frame_time.is_finite() || frame_time <= 0.0, return ERR!("bad frame time {frame_time}"));
+    let width: usize = joints.iter().map(|j| j.channels.len()).sum();
+    let mut motion = Vec::with_capacity(count);
+    for _ in 0..count {
+        motion.push(
+            (0..width)
+                .map(|_| number(next("a channel value")?))
+                .collect::<Result<Vec<f32>, _>>()?,
+        );
+    }
+    return Ok(BvhClip { joints, frame_time, motion });
+}
+
+//#endregion
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track<T: TAnimation>(a: T, b: T) -> Animation<T> {
+        let key_frames = vec![
+            KeyFrame { frame: 0, value: a, ..Default::default() },
+            KeyFrame { frame: 100, value: b, ..Default::default() },
+        ];
+        return Animation {
+            interp_type: InterpolationType::Linear,
+            key_frames,
+            ..Default::default()
+        };
+    }
+
+    fn model(animated: bool) -> MdlxData {
+        let mut data = MdlxData::default();
+        for (id, name, pivot) in [(0, "Root", Vec3::ZERO), (1, "Arm", Vec3::new(0.0, 0.0, 10.0))] {
+            let mut bone = Bone::default();
+            (bone.base.name, bone.base.object_id, bone.base.parent_id) = (name.s(), id, id - 1);
+            data.bones.push(bone);
+            data.pivot_points.push(PivotPoint { position: pivot });
+        }
+        yes!(!animated, return data);
+        data.bones[0].base.translation = Some(track(Vec3::ZERO, Vec3::new(10.0, 0.0, 5.0)));
+        let turn = from_quat(Quat::from_rotation_z(1.0) * Quat::from_rotation_x(0.5));
+        data.bones[1].base.rotation = Some(track(from_quat(Quat::IDENTITY), turn));
+        data.sequences.push(Sequence { name: "Walk".s(), end_frame: 100, ..Default::default() });
+        return data;
+    }
+
+    fn write(data: &MdlxData, name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join("war3mdlx_bvh_test");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(F!("{name}.bvh"));
+        let opts = MdlWriteOptions::default();
+        data.write_bvh(&path, &opts, &ExportOptions::default()).unwrap();
+        return path;
+    }
+
+    #[test]
+    fn export_and_import() {
+        let source = model(true);
+        let mut data = model(false);
+        data.import_bvh(&write(&source, "walk")).unwrap();
+
+        assert_eq!(data.sequences.len(), 1);
+        let (a, b) = (&source.sequences[0], &data.sequences[0]);
+        assert_eq!((b.name.as_str(), b.start_frame, b.end_frame), ("walk", 0, 100));
+        for frame in [0, 33, 50, 67, 100] {
+            for (j, k) in source.bones.iter().zip(data.bones.iter()) {
+                let (j, k) = (&j.base, &k.base);
+                let t = source.sample(&j.translation, frame, a, frame, Vec3::ZERO);
+                let u = data.sample(&k.translation, frame, b, frame, Vec3::ZERO);
+                assert!(t.abs_diff_eq(u, 1e-3), "{} at {frame}: {t} != {u}", j.name);
+                let r = to_quat(source.sample(&j.rotation, frame, a, frame, Vec4::W));
+                let s = to_quat(data.sample(&k.rotation, frame, b, frame, Vec4::W));
+                assert!(r.dot(s).abs() > 1.0 - 1e-5, "{} at {frame}: {r} != {s}", j.name);
+            }
+        }
+    }
+
+    #[test]
+    fn import_all_or_nothing() {
+        let path = write(&model(true), "global");
+        let mut data = model(false);
+        data.bones[1].base.rotation = Some(Animation { global_seq_id: 0, ..Default::default() });
+        assert!(data.import_bvh(&path).is_err()); // on Arm, after Root
+
+        assert!(data.sequences.is_empty());
+        assert!(data.bones[0].base.translation.is_none() && data.bones[0].base.rotation.is_none());
+        assert!(data.bones[1].base.rotation.as_ref().is_some_and(|a| a.key_frames.is_empty()));
+    }
+
+    #[test]
+    fn frame_time() {
+        let clip = |time: &str| {
+            let text = "HIERARCHY ROOT a { OFFSET 0 0 0 CHANNELS 1 Zrotation } MOTION";
+            parse_bvh(&F!("{text} Frames: 2 Frame Time: {time} 1 2")).map(|c| c.frame_time)
+        };
+        assert_eq!(clip("0.5").ok(), Some(0.5));
+        for time in ["0", "-0.1", "NaN", "inf"] {
+            assert!(clip(time).is_err(), "{time}");
+        }
+    }
+}
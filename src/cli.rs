@@ -19,9 +19,92 @@ pub struct Args {
     pub mdl2x: bool,
     #[arg(long, short = '2', help = "Convert *.mdx to *.mdl")]
     pub mdx2l: bool,
+    #[arg(
+        long,
+        short = 't',
+        value_name = "mdl|mdx|gltf|glb|obj|bvh",
+        value_parser = validate_format,
+        help = "Convert to this format [default: mdl <-> mdx, gltf/glb/obj -> mdx, or by the output extension]",
+    )]
+    pub to: Option<String>,
+    #[arg(
+        long,
+        short = 'P',
+        value_name = "SEQUENCE[:FRAME]",
+        help = "Export obj geometry posed at FRAME ms into SEQUENCE (name or index) instead of the bind pose, \
+            or the motion of SEQUENCE to bvh [default: the first]"
+    )]
+    pub pose: Option<String>,
+    #[arg(
+        long,
+        short = 'A',
+        value_name = "BVH",
+        help = "Add the motion of a BVH file as a new sequence, onto the bones and helpers of the same names"
+    )]
+    pub add_bvh: Option<String>,
+    #[arg(
+        long,
+        short = 'c',
+        help = "Verify *.mdx files survive mdx -> mdl -> mdx byte-exactly (no output)"
+    )]
+    pub verify: bool,
+    #[arg(
+        long,
+        short = 'l',
+        conflicts_with_all = [
+            "output", "mdl2x", "mdx2l", "to", "pose", "add_bvh", "verify",
+            "fix", "recalc_extents", "recalc_normals", "flat", "overwrite",
+        ],
+        help = "Check *.mdl/*.mdx files for broken references and counts (no output)",
+    )]
+    pub lint: bool,
     #[arg(long, short = 'B', help = "Make sure colors are in RGB order in mdl files")]
     pub mdl_rgb: bool,
 
+    #[arg(
+        long,
+        short = 's',
+        help = "Salvage damaged *.mdx files as far as possible, and report repairs"
+    )]
+    pub salvage: bool,
+    #[arg(
+        long,
+        short = 'S',
+        help = "Split geosets over the engine limits (65536 vertices, 256 vertex groups) into parts"
+    )]
+    pub split_geosets: bool,
+    #[arg(
+        long,
+        short = 'x',
+        help = "Fix animations when converting: sort keys, remove duplicate keys, normalize rotations"
+    )]
+    pub fix: bool,
+    #[arg(
+        long,
+        short = 'E',
+        help = "Recompute bounding extents from geometry and animation when converting"
+    )]
+    pub recalc_extents: bool,
+    #[arg(
+        long,
+        short = 'N',
+        value_name = "CREASE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "180",
+        help = "Recompute normals when converting, split vertices at edges sharper than CREASE degrees [default: 180]",
+    )]
+    pub recalc_normals: Option<f32>,
+    #[arg(
+        long,
+        short = 'W',
+        value_name = "area|angle",
+        value_parser = validate_normal_weighting,
+        default_value = "area",
+        help = "How faces are weighted when recomputing normals",
+    )]
+    pub normal_weighting: NormalWeighting,
+
     #[arg(long, short = 'F', help = "Put output files in one directory and ignore hierarchy")]
     pub flat: bool,
     #[arg(long, short = 'f', help = "Overwrite existing output files [default: skip]")]
@@ -46,6 +129,12 @@ pub struct Args {
         help = "Max precision of decimal numbers when converted to text",
     )]
     pub precision: u8,
+    #[arg(
+        long,
+        short = 'L',
+        help = "Write decimal numbers exactly (shortest round-trip form), ignore precision"
+    )]
+    pub lossless: bool,
     #[arg(
         long,
         short = 'n',
@@ -71,6 +160,43 @@ pub struct Args {
     pub verbose: u8,
 }
 
+impl Args {
+    pub fn mdl_read_options(&self) -> MdlReadOptions {
+        let (rgb, salvage, split_geosets) = (self.mdl_rgb, self.salvage, self.split_geosets);
+        MdlReadOptions { rgb, salvage, split_geosets }
+    }
+    pub fn normal_options(&self) -> Option<NormalOptions> {
+        let weighting = self.normal_weighting;
+        self.recalc_normals.map(|crease_angle| NormalOptions { weighting, crease_angle })
+    }
+    pub fn mdl_write_options(&self) -> MdlWriteOptions {
+        MdlWriteOptions {
+            indent: self.indent.clone(),
+            line_ending: self.line_ending.clone(),
+            precision: self.precision,
+            lossless: self.lossless,
+            rgb: self.mdl_rgb,
+        }
+    }
+    pub fn export_options(&self) -> ExportOptions {
+        ExportOptions { pose: self.pose.clone() }
+    }
+    pub fn worker_options(&self) -> WorkerOptions {
+        WorkerOptions {
+            read: self.mdl_read_options(),
+            write: self.mdl_write_options(),
+            export: self.export_options(),
+            normals: self.normal_options(),
+            add_bvh: self.add_bvh.as_ref().map(PathBuf::from),
+            fix: self.fix,
+            recalc_extents: self.recalc_extents,
+            verify: self.verify,
+            lint: self.lint,
+            stop_on_error: self.stop_on_error,
+        }
+    }
+}
+
 fn validate_line_ending(s: &str) -> Result<String, String> {
     match_istr!(s,
         "CR" => Ok("\r".s()),
@@ -79,6 +205,26 @@ fn validate_line_ending(s: &str) -> Result<String, String> {
         _other => Err("must be CR, LF or CRLF".s())
     )
 }
+fn validate_normal_weighting(s: &str) -> Result<NormalWeighting, String> {
+    match_istr!(s,
+        "area" => Ok(NormalWeighting::Area),
+        "angle" => Ok(NormalWeighting::Angle),
+        _other => Err("must be area or angle".s())
+    )
+}
+/// Extensions of the formats which can be read.
+pub const INPUT_FORMATS: [&str; 5] = ["mdl", "mdx", "gltf", "glb", "obj"];
+/// Extensions of the formats which can be written.
+pub const OUTPUT_FORMATS: [&str; 6] = ["mdl", "mdx", "gltf", "glb", "obj", "bvh"];
+
+fn validate_format(s: &str) -> Result<String, String> {
+    let s = s.trim_start_matches('.').to_lowercase();
+    yesno!(
+        OUTPUT_FORMATS.contains(&s.as_str()),
+        Ok(s),
+        Err(F!("must be {}", OUTPUT_FORMATS.join(", ")))
+    )
+}
 fn validate_indent(s: &str) -> Result<String, String> {
     let re = Regex::new(r"^[0-9]{1,4}[st]$").unwrap();
     if re.is_match(s) {
@@ -95,13 +241,21 @@ fn validate_indent(s: &str) -> Result<String, String> {
 //#endregion
 //#region CLI
 
+/// Run the command line tool with the arguments of this process.
+pub fn run() -> Result<(), MyError> {
+    let cli = CLI::new();
+    let mut worker = Worker::init(ARGS.worker_options());
+    cli.execute(&mut worker)?;
+    return worker.join();
+}
+
 #[derive(PartialEq)]
 enum CheckResult {
     Ok,
     ExpectFileDir,
     ExpectMDL,
     ExpectMDX,
-    ExpectMDLX,
+    ExpectFormat,
 }
 
 #[derive(Debug)]
@@ -109,6 +263,7 @@ pub struct CLI;
 
 impl CLI {
     pub fn new() -> Self {
+        set_log_level(init_log_level());
         Self {}
     }
 
@@ -116,9 +271,13 @@ impl CLI {
         let input = PathBuf::from(&ARGS.input);
         match self.check_input(&input) {
             CheckResult::ExpectFileDir => EXIT1!("Not an existing file or directory: {:?}", input),
-            CheckResult::ExpectMDLX => EXIT1!("Invalid input: {:?}, expect *.mdl or *.mdx", input),
+            CheckResult::ExpectFormat => {
+                EXIT1!("Invalid input: {:?}, expect *.{}", input, INPUT_FORMATS.join(", *."))
+            },
             CheckResult::ExpectMDL => EXIT1!("Invalid input: {:?}, expect *.mdl", input),
             CheckResult::ExpectMDX => EXIT1!("Invalid input: {:?}, expect *.mdx", input),
+            CheckResult::Ok if ARGS.verify => self.handle_verify(worker, input),
+            CheckResult::Ok if ARGS.lint => self.handle_lint(worker, input),
             CheckResult::Ok => match input.is_dir() {
                 false => self.handle_file(worker, input),
                 true => self.handle_dir(worker, input),
@@ -126,15 +285,41 @@ impl CLI {
         }
     }
 
+    fn handle_verify(&self, worker: &mut Worker, input: PathBuf) -> Result<(), MyError> {
+        let max_depth = yesno!(input.is_dir(), ARGS.max_depth as usize + 1, 0);
+        for entry in WalkDir::new(&input).max_depth(max_depth).into_iter().filter_map(|e| e.ok()) {
+            let ifile = entry.into_path();
+            if ifile.is_file() && ifile.ext_lower() == "mdx" {
+                vlog!("Verifying {} ...", ifile.fmtx());
+                worker.add_job(ifile, PathBuf::new());
+            }
+        }
+        EXIT!();
+    }
+
+    fn handle_lint(&self, worker: &mut Worker, input: PathBuf) -> Result<(), MyError> {
+        let max_depth = yesno!(input.is_dir(), ARGS.max_depth as usize + 1, 0);
+        for entry in WalkDir::new(&input).max_depth(max_depth).into_iter().filter_map(|e| e.ok()) {
+            let ifile = entry.into_path();
+            if ifile.is_file() && self.check_input(&ifile) == CheckResult::Ok {
+                vlog!("Linting {} ...", ifile.fmtx());
+                worker.add_job(ifile, PathBuf::new());
+            }
+        }
+        EXIT!();
+    }
+
     fn guess_outext(&self, inext: &str) -> &str {
-        if ARGS.mdl2x {
+        if let Some(to) = &ARGS.to {
+            to
+        } else if ARGS.mdl2x {
             "mdx"
         } else if ARGS.mdx2l {
             "mdl"
-        } else if inext.eq_icase("mdl") {
-            "mdx"
-        } else {
+        } else if inext.eq_icase("mdx") {
             "mdl"
+        } else {
+            "mdx"
         }
     }
 
@@ -147,7 +332,10 @@ impl CLI {
         let opath = output.display().to_string();
         match self.check_output(&output) {
             CheckResult::ExpectFileDir => EXIT1!("Not a file or directory: {}", opath),
-            CheckResult::ExpectMDLX => EXIT1!("Invalid path: {}, expect *.mdl or *.mdx", opath),
+            CheckResult::ExpectFormat => match &ARGS.to {
+                Some(to) => EXIT1!("Invalid path: {}, expect *.{}", opath, to),
+                None => EXIT1!("Invalid path: {}, expect *.{}", opath, OUTPUT_FORMATS.join(", *.")),
+            },
             CheckResult::ExpectMDL => EXIT1!("Invalid path: {}, expect *.mdl", opath),
             CheckResult::ExpectMDX => EXIT1!("Invalid path: {}, expect *.mdx", opath),
             _ok => yes!(output.is_dir(), output = output.join(input.base_name())),
@@ -192,12 +380,14 @@ impl CLI {
         if path.is_dir() {
             CheckResult::Ok
         } else if path.is_file() {
-            if ARGS.mdl2x && ext != "mdl" {
+            if ARGS.verify && ext != "mdx" {
+                CheckResult::ExpectMDX
+            } else if ARGS.mdl2x && ext != "mdl" {
                 CheckResult::ExpectMDL
             } else if ARGS.mdx2l && ext != "mdx" {
                 CheckResult::ExpectMDX
-            } else if ext != "mdl" && ext != "mdx" {
-                CheckResult::ExpectMDLX
+            } else if !INPUT_FORMATS.contains(&ext.as_str()) {
+                CheckResult::ExpectFormat
             } else {
                 CheckResult::Ok
             }
@@ -215,8 +405,10 @@ impl CLI {
                 CheckResult::ExpectMDX
             } else if ARGS.mdx2l && ext != "mdl" {
                 CheckResult::ExpectMDL
-            } else if ext != "mdl" && ext != "mdx" {
-                CheckResult::ExpectMDLX
+            } else if ARGS.to.as_ref().is_some_and(|to| *to != ext)
+                || !OUTPUT_FORMATS.contains(&ext.as_str())
+            {
+                CheckResult::ExpectFormat
             } else {
                 CheckResult::Ok
             }
@@ -234,31 +426,9 @@ impl CLI {
     }
 }
 
-//#endregion
-//#region [global] args
-
-macro_rules! getter {
-    ($($field:ident),+) => {
-        $(
-            #[macro_export]
-            macro_rules! $field {
-                () => {
-                    &ARGS.$field
-                };
-            }
-        )+
-    };
-}
-
-getter!(line_ending, precision, stop_on_error, mdl_rgb);
-
 //#endregion
 //#region [global] log level
 
-lazy_static! {
-    pub static ref _LOG_LEVEL: LogLevel = init_log_level();
-}
-
 fn init_log_level() -> LogLevel {
     yesno!(
         ARGS.quiet,
@@ -272,42 +442,4 @@ fn init_log_level() -> LogLevel {
     )
 }
 
-pub fn log_level() -> &'static LogLevel {
-    &_LOG_LEVEL
-}
-
-//#endregion
-//#region [global] indent
-
-lazy_static! {
-    static ref INDENTS: HashMap<u8, String> = init_indents();
-}
-
-fn init_indents() -> HashMap<u8, String> {
-    let mut m = HashMap::new();
-    let s = &ARGS.indent;
-    for i in 0..10 {
-        m.insert(i, s.repeat(i as usize));
-    }
-    return m;
-}
-
-pub fn _indent(depth: u8) -> &'static str {
-    match &depth {
-        0 => "",
-        1 => ARGS.indent.as_str(),
-        d => INDENTS.get(d).expect(&F!("Invalid indent depth: {d}")),
-    }
-}
-
-#[macro_export]
-macro_rules! indent {
-    () => {
-        Args.indent
-    };
-    ($depth:expr) => {
-        _indent($depth)
-    };
-}
-
 //#endregion
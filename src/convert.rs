@@ -0,0 +1,16 @@
+//#region ExportOptions
+
+/// Options of the exports to other formats (obj, bvh), which have no animation or a single one.
+#[derive(Debug, Default, Clone)]
+pub struct ExportOptions {
+    /// "SEQUENCE[:FRAME]" to pose geometry at (obj), or the sequence to export (bvh).
+    pub pose: Option<String>,
+}
+
+//#endregion
+//#region Imports
+
+pub(crate) const FORMAT_VERSION: i32 = 800; // of models imported from other formats (gltf, obj)
+pub(crate) const WHITE_TEXTURE: &str = "Textures\\white.blp"; // for materials without a texture
+
+//#endregion
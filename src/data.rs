@@ -23,43 +23,140 @@ pub struct MdlxData {
     pub eventobjs: Vec<EventObject>,
     pub particle_emitters: Vec<ParticleEmitter>,
     pub particle_emitters2: Vec<ParticleEmitter2>,
+    pub popcorn_emitters: Vec<PopcornEmitter>,
     pub ribbon_emitters: Vec<RibbonEmitter>,
+    pub face_effects: Vec<FaceEffect>,
+    pub bind_pose: BindPose,
+    pub unknown_chunks: Vec<UnknownChunk>,
+    pub repairs: Vec<String>, // made in salvage mode
+
+    #[dbg(skip)]
+    pub(crate) mdl_lines: HashMap<String, u32>, // e.g. "Geoset[0]" => line, see validate()
+    #[dbg(skip)]
+    pub(crate) geoset_parts: Vec<usize>, // number of parts each mdl Geoset is split into
+}
+
+macro_rules! nodes_of {
+    ($this:expr, $out:ident, $( $var:ident => $name:literal ),+ $(,)?) => {
+        $(for (i, a) in $this.$var.iter().enumerate() {
+            $out.push((F!("{}[{i}]", $name), &a.base));
+        })+
+    };
+}
+macro_rules! VisitAllAnims {
+    ($this:expr, $v:expr, $visit:ident, $iter:ident) => {
+        VisitAllAnims!(@list $this, $v, $visit, $iter,
+            texanims => "TextureAnim",
+            geoanims => "GeosetAnim",
+            cameras => "Camera",
+            bones => "Bone",
+            lights => "Light",
+            helpers => "Helper",
+            attachments => "Attachment",
+            particle_emitters => "ParticleEmitter",
+            particle_emitters2 => "ParticleEmitter2",
+            popcorn_emitters => "PopcornEmitter",
+            ribbon_emitters => "RibbonEmitter",
+            eventobjs => "EventObject",
+            collisions => "CollisionShape",
+        );
+        for (i, m) in $this.materials.$iter().enumerate() {
+            for (j, a) in m.layers.$iter().enumerate() {
+                $v.enter(&F!("Material[{i}].Layer[{j}]"));
+                a.$visit($v);
+            }
+        }
+    };
+    (@list $this:expr, $v:expr, $visit:ident, $iter:ident, $( $var:ident => $name:literal ),+ $(,)?) => {
+        $(for (i, a) in $this.$var.$iter().enumerate() {
+            $v.enter(&F!("{}[{i}]", $name));
+            a.$visit($v);
+        })+
+    };
 }
 
 impl MdlxData {
-    pub fn read(path: &Path) -> Result<Self, MyError> {
+    pub fn read(path: &Path, opts: &MdlReadOptions) -> Result<Self, MyError> {
         let ret = match path.ext_lower().as_str() {
             "mdl" => match std::fs::read_to_string(path) {
                 Err(e) => Err(MyError::Io(e)),
-                Ok(s) => Self::read_mdl(&s).or_else(|e| Err(e)),
+                Ok(s) => Self::from_mdl_str(&s, opts),
             },
             "mdx" => match std::fs::read(path) {
                 Err(e) => Err(MyError::Io(e)),
-                Ok(s) => Self::read_mdx(&s).or_else(|e| Err(e)),
+                Ok(s) => yesno!(opts.salvage, Self::read_mdx_salvage(&s), Self::read_mdx(&s)),
             },
-            _ => EXIT1!("Invalid input path: {:?}, expecting *.mdl or *.mdx", path),
-        };
-        match ret {
-            Err(e) => EXIT1!("Failed to read file {:?}: {}.", path, e),
-            Ok(mut this) => {
-                for (i, a) in this.attachments.iter_mut().enumerate() {
-                    a.appear_order = i as i32;
-                }
-                return Ok(this);
+            "gltf" | "glb" => Self::read_gltf(path),
+            "obj" => Self::read_obj(path),
+            _ => {
+                EXIT1!("Invalid input path: {:?}, expecting *.{}", path, INPUT_FORMATS.join(", *."))
             },
-        }
+        };
+        return ret.map_err(|e| e.within(F!("Failed to read file {:?}", path)));
     }
 
-    pub fn write(&mut self, path: &Path) -> Result<(), MyError> {
+    pub fn write(
+        &self,
+        path: &Path,
+        opts: &MdlWriteOptions,
+        eopts: &ExportOptions,
+    ) -> Result<(), MyError> {
         if let Some(parent) = path.parent() {
             if let Err(e) = fs::create_dir_all(parent) {
                 EXIT1!("creating directory: {}", e);
             }
         }
         match path.ext_lower().as_ref() {
-            "mdl" => self.write_mdl(path).or_else(|e| ERR!("Failed to write file {:?}: {}", path, e)),
-            "mdx" => self.write_mdx(path).or_else(|e| ERR!("Failed to write file {:?}: {}", path, e)),
-            _ => ERR!("Invalid output path: {:?}, expected *.mdl or *.mdx", path),
+            "mdl" => self
+                .write_mdl(path, opts)
+                .map_err(|e| e.within(F!("Failed to write file {:?}", path))),
+            "mdx" => {
+                self.write_mdx(path).map_err(|e| e.within(F!("Failed to write file {:?}", path)))
+            },
+            "gltf" | "glb" => {
+                self.write_gltf(path).map_err(|e| e.within(F!("Failed to write file {:?}", path)))
+            },
+            "obj" => self
+                .write_obj(path, opts, eopts)
+                .map_err(|e| e.within(F!("Failed to write file {:?}", path))),
+            "bvh" => self
+                .write_bvh(path, opts, eopts)
+                .map_err(|e| e.within(F!("Failed to write file {:?}", path))),
+            _ => {
+                ERR!("Invalid output path: {:?}, expected *.{}", path, OUTPUT_FORMATS.join(", *."))
+            },
+        }
+    }
+
+    /// All nodes with their paths, e.g. ("Bone[0]", &bone.base).
+    pub fn nodes(&self) -> Vec<(String, &Node)> {
+        let mut nodes: Vec<(String, &Node)> = vec![];
+        nodes_of!(self, nodes,
+            bones => "Bone",
+            lights => "Light",
+            helpers => "Helper",
+            attachments => "Attachment",
+            particle_emitters => "ParticleEmitter",
+            particle_emitters2 => "ParticleEmitter2",
+            popcorn_emitters => "PopcornEmitter",
+            ribbon_emitters => "RibbonEmitter",
+            eventobjs => "EventObject",
+            collisions => "CollisionShape",
+        );
+        return nodes;
+    }
+
+    /// Visit animations of all objects, with `v.enter()` called for each object.
+    pub fn visit_anims<V: AnimVisitor>(&self, v: &mut V) {
+        VisitAllAnims!(self, v, visit_anims, iter);
+    }
+    pub fn visit_anims_mut<V: AnimVisitorMut>(&mut self, v: &mut V) {
+        VisitAllAnims!(self, v, visit_anims_mut, iter_mut);
+    }
+
+    pub(crate) fn after_read(&mut self) {
+        for (i, a) in self.attachments.iter_mut().enumerate() {
+            a.appear_order = i as i32;
         }
     }
 }
@@ -70,14 +70,16 @@ impl _ExtendCursorRead for Cursor<&Vec<u8>> {
     }
 
     fn read_array<T: ReadFromCursor>(&mut self, n: u32) -> Result<Vec<T>, MyError> {
-        let mut v = Vec::with_capacity(n as usize);
+        // a damaged count mustn't allocate more than is left
+        let mut v = Vec::with_capacity(n.min(self.left()) as usize);
         for _ in 0..n {
             v.push(T::read_from(self)?);
         }
         return Ok(v);
     }
     fn read_array_be<T: ReadFromCursor>(&mut self, n: u32) -> Result<Vec<T>, MyError> {
-        let mut v = Vec::with_capacity(n as usize);
+        // a damaged count mustn't allocate more than is left
+        let mut v = Vec::with_capacity(n.min(self.left()) as usize);
         for _ in 0..n {
             v.push(T::read_from_be(self)?);
         }
@@ -118,6 +120,7 @@ impl _ExtendCursorWrite for Cursor<Vec<u8>> {
         v.write_to_be(self)
     }
 
+    /// Longer strings are truncated, `MdlxData::check_limits` reports them before writing.
     fn write_string(&mut self, s: &str, n: u32) -> Result<(), MyError> {
         let n = n as usize;
         let bytes = s.as_bytes();
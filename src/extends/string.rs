@@ -28,7 +28,9 @@ impl _ExtendString for str {
 
     fn escape_path(&self) -> String {
         re_trailing_backslash
-            .replace_all(&self.replace("\"", "\\\""), |caps: &regex::Captures| "\\".repeat(caps[0].len() * 2))
+            .replace_all(&self.replace("\"", "\\\""), |caps: &regex::Captures| {
+                "\\".repeat(caps[0].len() * 2)
+            })
             .to_string()
     }
 
@@ -77,29 +79,29 @@ impl<T: CheckValue + Display> _ExtendStringArrayDisplay<T> for Vec<String> {
 
 #[allow(dead_code)]
 pub trait _ExtendStringArrayFMTX<T> {
-    fn pushx(&mut self, name: &str, v: &T);
+    fn pushx(&mut self, name: &str, v: &T, opts: &MdlWriteOptions);
 }
 impl<T: Formatter> _ExtendStringArrayFMTX<T> for Vec<String> {
-    fn pushx(&mut self, name: &str, v: &T) {
-        self.push(F!("{} {},", name, v.fmt()));
+    fn pushx(&mut self, name: &str, v: &T, opts: &MdlWriteOptions) {
+        self.push(F!("{} {},", name, v.fmt_with(opts)));
     }
 }
 
 #[allow(dead_code)]
 pub trait _ExtendStringArrayIfFMTX<T> {
-    fn pushx_if_n0(&mut self, name: &str, v: &T);
-    fn pushx_if_n1(&mut self, name: &str, v: &T);
-    fn pushx_if_nneg1(&mut self, name: &str, v: &T);
+    fn pushx_if_n0(&mut self, name: &str, v: &T, opts: &MdlWriteOptions);
+    fn pushx_if_n1(&mut self, name: &str, v: &T, opts: &MdlWriteOptions);
+    fn pushx_if_nneg1(&mut self, name: &str, v: &T, opts: &MdlWriteOptions);
 }
 impl<T: CheckValue + Formatter> _ExtendStringArrayIfFMTX<T> for Vec<String> {
-    fn pushx_if_n0(&mut self, name: &str, v: &T) {
-        yes!(!v.is0(), self.pushx(name, v));
+    fn pushx_if_n0(&mut self, name: &str, v: &T, opts: &MdlWriteOptions) {
+        yes!(!v.is0(), self.pushx(name, v, opts));
     }
-    fn pushx_if_n1(&mut self, name: &str, v: &T) {
-        yes!(!v.is1(), self.pushx(name, v));
+    fn pushx_if_n1(&mut self, name: &str, v: &T, opts: &MdlWriteOptions) {
+        yes!(!v.is1(), self.pushx(name, v, opts));
     }
-    fn pushx_if_nneg1(&mut self, name: &str, v: &T) {
-        yes!(!v.isneg1(), self.pushx(name, v));
+    fn pushx_if_nneg1(&mut self, name: &str, v: &T, opts: &MdlWriteOptions) {
+        yes!(!v.isneg1(), self.pushx(name, v, opts));
     }
 }
 
@@ -0,0 +1,109 @@
+use crate::*;
+
+const SAMPLE_STEP: usize = 33; // ms, ~30fps between keys
+
+//#region Bounds
+
+/// Axis-aligned bounding box, empty until the first point is added.
+#[derive(Debug, Default, Clone, Copy)]
+struct Bounds(Option<(Vec3, Vec3)>);
+
+impl Bounds {
+    fn add(&mut self, p: Vec3) {
+        self.0 = Some(match self.0 {
+            Some((min, max)) => (min.min(p), max.max(p)),
+            None => (p, p),
+        });
+    }
+    fn merge(&mut self, other: &Bounds) {
+        if let Some((min, max)) = other.0 {
+            self.add(min);
+            self.add(max);
+        }
+    }
+    fn of(points: &[Vec3]) -> Self {
+        let mut this = Self::default();
+        for p in points {
+            this.add(*p);
+        }
+        return this;
+    }
+
+    /// bound_radius is half the diagonal, i.e. the sphere around the box.
+    fn extent(&self) -> BoundExtent {
+        let (min, max) = self.0.unwrap_or_default();
+        BoundExtent { bound_radius: (max - min).length() / 2.0, min_extent: min, max_extent: max }
+    }
+}
+
+//#endregion
+//#region recalc_extents
+
+impl MdlxData {
+    /// Recompute all extents: Geoset::extent from vertices, Geoset::anim_extents
+    /// (one per sequence) and Sequence extents by posing the geosets over each sequence interval,
+    /// and Model::extent around all of them.
+    pub fn recalc_extents(&mut self) {
+        let statics: Vec<Bounds> = self.geosets.iter().map(|g| Bounds::of(&g.vertices)).collect();
+
+        let mut anims: Vec<Vec<Bounds>> = vec![vec![]; self.geosets.len()]; // [geoset][sequence]
+        for seq in self.sequences.iter() {
+            let mut bounds = vec![Bounds::default(); self.geosets.len()];
+            for frame in self.pose_frames(seq) {
+                let matrices = self.pose_at(seq, frame);
+                for (b, g) in bounds.iter_mut().zip(self.geosets.iter()) {
+                    b.merge(&Bounds::of(&g.skin(&matrices).0));
+                }
+            }
+            for (list, b) in anims.iter_mut().zip(bounds) {
+                list.push(b);
+            }
+        }
+
+        let mut model = Bounds::default();
+        for (i, g) in self.geosets.iter_mut().enumerate() {
+            g.extent = statics[i].extent();
+            g.anim_extents = anims[i].iter().map(|b| b.extent()).collect();
+            model.merge(&statics[i]);
+            anims[i].iter().for_each(|b| model.merge(b));
+        }
+        self.model.extent = model.extent();
+
+        for (k, seq) in self.sequences.iter_mut().enumerate() {
+            let mut b = Bounds::default();
+            for list in anims.iter() {
+                b.merge(&list[k]);
+            }
+            let e = b.extent();
+            (seq.bounds_radius, seq.min_extent, seq.max_extent) =
+                (e.bound_radius, e.min_extent, e.max_extent);
+        }
+    }
+
+    /// Frames to pose a sequence at: its start and end, every SAMPLE_STEP in between,
+    /// and every node key in between. Keys of global sequences are on their own timeline,
+    /// the steps cover them.
+    fn pose_frames(&self, seq: &Sequence) -> Vec<i32> {
+        let (start, end) = (seq.start_frame, seq.end_frame.max(seq.start_frame));
+        let mut frames = vec![start, end];
+        frames.extend((start..end).step_by(SAMPLE_STEP));
+        let global = |id: i32| usize::try_from(id).is_ok_and(|i| i < self.globalseqs.len());
+        let within = |f: &i32| start <= *f && *f <= end;
+        for (_, node) in self.nodes() {
+            if let Some(a) = node.translation.as_ref().filter(|a| !global(a.global_seq_id)) {
+                frames.extend(a.key_frames.iter().map(|k| k.frame).filter(within));
+            }
+            if let Some(a) = node.rotation.as_ref().filter(|a| !global(a.global_seq_id)) {
+                frames.extend(a.key_frames.iter().map(|k| k.frame).filter(within));
+            }
+            if let Some(a) = node.scaling.as_ref().filter(|a| !global(a.global_seq_id)) {
+                frames.extend(a.key_frames.iter().map(|k| k.frame).filter(within));
+            }
+        }
+        frames.sort();
+        frames.dedup();
+        return frames;
+    }
+}
+
+//#endregion
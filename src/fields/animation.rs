@@ -1,9 +1,15 @@
 use crate::*;
 
-pub trait TAnimation: ReadFromCursor + WriteToCursor + FromMdlValue + std::fmt::Debug + Default + Formatter {}
-impl<T: ReadFromCursor + WriteToCursor + FromMdlValue + std::fmt::Debug + Default + Formatter> TAnimation for T {}
+pub trait TAnimation:
+    ReadFromCursor + WriteToCursor + FromMdlValue + std::fmt::Debug + Default + Formatter
+{
+}
+impl<T: ReadFromCursor + WriteToCursor + FromMdlValue + std::fmt::Debug + Default + Formatter>
+    TAnimation for T
+{
+}
 
-#[derive(Dbg, Default)]
+#[derive(Dbg, Default, Clone)]
 pub struct KeyFrame<T: TAnimation> {
     pub frame: i32,
     pub value: T,
@@ -13,7 +19,7 @@ pub struct KeyFrame<T: TAnimation> {
     pub has_tans: bool,
 }
 
-#[derive(Dbg, SmartDefault)]
+#[derive(Dbg, SmartDefault, Clone)]
 pub struct Animation<T: TAnimation> {
     pub interp_type: InterpolationType,
     #[default(-1)]
@@ -86,36 +92,86 @@ impl<T: TAnimation> Animation<T> {
         }
         let has_tans = this.interp_type.has_tans();
         for f in &block.frames {
-            let mut kf = Build!(KeyFrame::<T>, frame:f.frame, has_tans:has_tans, value:f.value.to()?);
+            let mut kf =
+                Build!(KeyFrame::<T>, frame:f.frame, has_tans:has_tans, value:f.value.to()?);
             let (t, i, o) = (&block.typ, &f.intan, &f.outan);
             if has_tans {
-                yes!(i.is_empty(), EXIT1!("Missing {} (in {t}) at line {}.", i.name, i.line));
-                yes!(o.is_empty(), EXIT1!("Missing {} (in {t}) at line {}.", o.name, o.line));
+                yes!(
+                    i.is_empty(),
+                    return SYNTAX_ERR!(
+                        i.line,
+                        i.col,
+                        "Missing {} (in {t}) at line {}.",
+                        i.name,
+                        i.line
+                    )
+                );
+                yes!(
+                    o.is_empty(),
+                    return SYNTAX_ERR!(
+                        o.line,
+                        o.col,
+                        "Missing {} (in {t}) at line {}.",
+                        o.name,
+                        o.line
+                    )
+                );
                 (kf.itan, kf.otan) = (i.to()?, o.to()?);
             } else {
-                no!(i.is_empty(), EXIT1!("Unexpected {} (in {t}) at line {}.", i.name, i.line));
-                no!(o.is_empty(), EXIT1!("Unexpected {} (in {t}) at line {}.", o.name, o.line));
+                no!(
+                    i.is_empty(),
+                    return SYNTAX_ERR!(
+                        i.line,
+                        i.col,
+                        "Unexpected {} (in {t}) at line {}.",
+                        i.name,
+                        i.line
+                    )
+                );
+                no!(
+                    o.is_empty(),
+                    return SYNTAX_ERR!(
+                        o.line,
+                        o.col,
+                        "Unexpected {} (in {t}) at line {}.",
+                        o.name,
+                        o.line
+                    )
+                );
             }
             this.key_frames.push(kf);
         }
         return Ok(this);
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        let (indent, indent2) = (indent!(depth), indent!(depth + 1));
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        let (indent, indent2) = (opts.indent(depth), opts.indent(depth + 1));
         let mut lines: Vec<String> = vec![];
         lines.push(F!("{indent}{:?},", self.interp_type));
         lines.push_if_nneg1(&F!("{indent}GlobalSeqId"), &self.global_seq_id);
         for kf in &self.key_frames {
-            lines.pushx(&F!("{indent}{}:", kf.frame), &kf.value);
+            lines.pushx(&F!("{indent}{}:", kf.frame), &kf.value, opts);
             if kf.has_tans {
-                lines.pushx(&F!("{indent2}InTan"), &kf.itan);
-                lines.pushx(&F!("{indent2}OutTan"), &kf.otan);
+                lines.pushx(&F!("{indent2}InTan"), &kf.itan, opts);
+                lines.pushx(&F!("{indent2}OutTan"), &kf.otan, opts);
             }
         }
         return Ok(lines);
     }
 
+    /// Sort keys by frame (stable), return true if the order changed.
+    pub fn sort_keys(&mut self) -> bool {
+        yes!(self.key_frames.windows(2).all(|w| w[0].frame <= w[1].frame), return false);
+        self.key_frames.sort_by_key(|k| k.frame);
+        return true;
+    }
+    /// Remove keys at the same frame as the previous key, return the number of removed keys.
+    pub fn dedup_keys(&mut self) -> usize {
+        let n = self.key_frames.len();
+        self.key_frames.dedup_by_key(|k| k.frame);
+        return n - self.key_frames.len();
+    }
+
     pub fn convert<F: Fn(&T) -> T>(&self, f: F) -> Self {
         let mut this = Build! { interp_type: self.interp_type, global_seq_id: self.global_seq_id };
         for kf in &self.key_frames {
@@ -133,48 +189,48 @@ impl<T: TAnimation> Animation<T> {
 
 #[macro_export]
 macro_rules! MdlWriteAnim {
-    ($lines:ident, $depth:expr, $( $name:expr => $avar:expr ),+ $(,)?) => {
+    ($lines:ident, $depth:expr, $opts:expr, $( $name:expr => $avar:expr ),+ $(,)?) => {
         $(
             let anim = &$avar;
-            let indent = indent!($depth);
+            let indent = $opts.indent($depth);
             $lines.push(F!("{}{} {} {{", indent, $name, anim.key_frames.len()));
-            $lines.append(anim.write_mdl($depth + 1)?.as_mut());
+            $lines.append(anim.write_mdl($depth + 1, $opts)?.as_mut());
             $lines.push(F!("{}}}", indent));
         )+
     };
 }
 #[macro_export]
 macro_rules! MdlWriteAnimIfSome {
-    ($lines:ident, $depth:expr, $( $name:expr => $avar:expr ),+ $(,)?) => {
+    ($lines:ident, $depth:expr, $opts:expr, $( $name:expr => $avar:expr ),+ $(,)?) => {
         $(if let Some(item) = &$avar {
-            MdlWriteAnim!($lines, $depth, $name => item);
+            MdlWriteAnim!($lines, $depth, $opts, $name => item);
         })+
     };
 }
 
 #[macro_export]
 macro_rules! MdlWriteAnimStatic {
-    ($lines:ident, $depth:expr, $( $name:expr => $svar:expr ),+ $(,)?) => {
+    ($lines:ident, $depth:expr, $opts:expr, $( $name:expr => $svar:expr ),+ $(,)?) => {
         $(
-            let indent = indent!($depth);
-            $lines.push(F!("{}static {} {},", indent, $name, fmtx(&$svar)));
+            let indent = $opts.indent($depth);
+            $lines.push(F!("{}static {} {},", indent, $name, fmtw(&$svar, $opts)));
         )+
     };
 }
 #[macro_export]
 macro_rules! MdlWriteAnimStaticIfNot {
-    ($lines:ident, $depth:expr, $( $name:expr => $def:expr => $svar:expr ),+ $(,)?) => {
+    ($lines:ident, $depth:expr, $opts:expr, $( $name:expr => $def:expr => $svar:expr ),+ $(,)?) => {
         $(if $svar != $def {
-            MdlWriteAnimStatic!($lines, $depth, $name => $svar);
+            MdlWriteAnimStatic!($lines, $depth, $opts, $name => $svar);
         })+
     };
 }
 #[macro_export]
 macro_rules! MdlWriteAnimBoth {
-    ($lines:ident, $depth:expr, $( $name:expr => $avar:expr => $def:expr => $svar:expr ),+ $(,)?) => {
+    ($lines:ident, $depth:expr, $opts:expr, $( $name:expr => $avar:expr => $def:expr => $svar:expr ),+ $(,)?) => {
         $(
-            MdlWriteAnimStaticIfNot!($lines, $depth, $name => $def => $svar);
-            MdlWriteAnimIfSome!($lines, $depth, $name => $avar);
+            MdlWriteAnimStaticIfNot!($lines, $depth, $opts, $name => $def => $svar);
+            MdlWriteAnimIfSome!($lines, $depth, $opts, $name => $avar);
         )+
     };
 }
@@ -186,6 +242,14 @@ macro_rules! MdxWriteAnim {
             $avar.write_mdx($chunk, &$id)?;
         )+
     };
+    // unknown tracks go back after the known track they followed
+    ($chunk:ident, $unknown:expr; $( $id:expr => $avar:expr ),+ $(,)?) => {
+        $unknown.write_after(0, $chunk)?;
+        $(
+            $avar.write_mdx($chunk, &$id)?;
+            $unknown.write_after($id, $chunk)?;
+        )+
+    };
 }
 
 //#region InterpolationType
@@ -241,20 +305,20 @@ impl InterpolationType {
 //#region formatter
 
 impl<T: TAnimation> Formatter for KeyFrame<T> {
-    fn fmt(&self) -> String {
+    fn fmt_with(&self, opts: &MdlWriteOptions) -> String {
+        let (value, itan, otan) =
+            (fmtw(&self.value, opts), fmtw(&self.itan, opts), fmtw(&self.otan, opts));
         match self.has_tans {
-            true => {
-                F!("{}: {}, InTan={}, OutTan={},", self.frame, fmtx(&self.value), fmtx(&self.itan), fmtx(&self.otan))
-            },
-            false => F!("{}: {},", self.frame, fmtx(&self.value)),
+            true => F!("{}: {}, InTan={}, OutTan={},", self.frame, value, itan, otan),
+            false => F!("{}: {},", self.frame, value),
         }
     }
 }
 impl<T: TAnimation> Formatter for Vec<KeyFrame<T>> {
-    fn fmt(&self) -> String {
+    fn fmt_with(&self, opts: &MdlWriteOptions) -> String {
         let mut list: Vec<String> = Vec::with_capacity(self.len());
         for kf in self {
-            list.push(fmtx(kf));
+            list.push(fmtw(kf, opts));
         }
         return F!("[\n    {}\n]", list.join("\n    "));
     }
@@ -285,3 +349,37 @@ impl<T: TAnimation> _ExtendSomeAnimation for Option<Animation<T>> {
 }
 
 //#endregion
+//#region AnimVisitor
+
+/// Visits all animations of an object (with their mdl names), see `impl_VisitAnims!`.
+pub trait AnimVisitor {
+    /// Called before the animations of each object, see `MdlxData::visit_anims`.
+    fn enter(&mut self, _path: &str) {}
+    fn visit<T: Interpolate>(&mut self, name: &str, anim: &Animation<T>);
+}
+pub trait AnimVisitorMut {
+    fn enter(&mut self, _path: &str) {}
+    fn visit_mut<T: Interpolate>(&mut self, name: &str, anim: &mut Animation<T>);
+}
+
+#[macro_export]
+macro_rules! impl_VisitAnims {
+    ($ty:ty $(: $base:ident)? $(, $name:literal => $var:ident)* $(,)?) => {
+        impl $ty {
+            pub fn visit_anims<V: AnimVisitor>(&self, v: &mut V) {
+                $( self.$base.visit_anims(v); )?
+                $(if let Some(a) = &self.$var {
+                    v.visit($name, a);
+                })*
+            }
+            pub fn visit_anims_mut<V: AnimVisitorMut>(&mut self, v: &mut V) {
+                $( self.$base.visit_anims_mut(v); )?
+                $(if let Some(a) = &mut self.$var {
+                    v.visit_mut($name, a);
+                })*
+            }
+        }
+    };
+}
+
+//#endregion
@@ -13,12 +13,13 @@ pub struct Attachment {
 
     #[dbg(formatter = "fmtxx")]
     pub visibility: Option<Animation<f32>>,
+    pub unknown_tracks: Vec<UnknownTrack>,
 }
 
 impl Attachment {
     pub const ID: u32 = MdlxMagic::ATCH;
     const ID_V: u32 = MdlxMagic::KATV; /* Visibility */
-    const PATH_SIZE: u32 = 256;
+    pub const PATH_SIZE: u32 = 256;
 
     pub fn read_mdx(cur: &mut Cursor<&Vec<u8>>) -> Result<Self, MyError> {
         let mut this = Build! {
@@ -27,11 +28,17 @@ impl Attachment {
             _unknown: cur.readx()?,
             attachment_id: Some(cur.readx()?),
         };
+        let mut after = 0; // the last known track, for unknown tracks
         while cur.left() >= 16 {
-            match cur.read_be()? {
+            let id = cur.read_be()?;
+            match id {
                 Self::ID_V => this.visibility = Some(Animation::read_mdx(cur)?),
-                id => return ERR!("Unknown animation in {}: {} (0x{:08X})", TNAME!(), u32_to_ascii(id), id),
+                _ => {
+                    this.unknown_tracks.push(UnknownTrack::read_mdx(cur, id, after, TNAME!())?);
+                    continue;
+                },
             }
+            after = id;
         }
         return Ok(this);
     }
@@ -42,17 +49,18 @@ impl Attachment {
         chunk.write_string(&self.child_path, Self::PATH_SIZE)?;
         chunk.write(&self._unknown)?;
         chunk.write(&self.attachment_id.unwrap_or(self.appear_order))?;
-        MdxWriteAnim!(chunk, Self::ID_V => self.visibility);
+        MdxWriteAnim!(chunk, self.unknown_tracks; Self::ID_V => self.visibility);
         return Ok(());
     }
     pub fn calc_mdx_size(&self) -> u32 {
         let mut sz: u32 = 12 + Self::PATH_SIZE; // sz + path + unknown + attachment_id
         sz += self.base.calc_mdx_size();
         sz += self.visibility.calc_mdx_size();
+        sz += self.unknown_tracks.calc_mdx_size();
         return sz;
     }
 
-    pub fn read_mdl(block: &MdlBlock) -> Result<Self, MyError> {
+    pub fn read_mdl(block: &MdlBlock, _: &MdlReadOptions) -> Result<Self, MyError> {
         let mut this = Build! { base: Node::read_mdl(block)? };
         this.base.flags.insert(NodeFlags::Attachment);
         for f in &block.fields {
@@ -65,21 +73,27 @@ impl Attachment {
         for f in &block.blocks {
             match_istr!(f.typ.as_str(),
                 "Visibility" => this.visibility = Some(Animation::read_mdl(f)?),
+                "UnknownTrack" => this.unknown_tracks.push(UnknownTrack::from_mdl(f)?),
                 _other => this.base.unexpect_mdl_block(f)?,
             );
         }
         return Ok(this);
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        let indent = indent!(depth);
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        let indent = opts.indent(depth);
         let mut lines: Vec<String> = vec![];
-        lines.append(&mut self.base.write_mdl(depth)?);
+        lines.append(&mut self.base.write_mdl(depth, opts)?);
         if let Some(aid) = self.attachment_id {
             lines.push_if(aid != self.appear_order, F!("{indent}AttachmentID {},", aid));
         }
-        lines.pushx_if_n0(&F!("{indent}Path"), &self.child_path.escape_path());
-        MdlWriteAnimIfSome!(lines, depth, "Visibility" => self.visibility);
+        lines.pushx_if_n0(&F!("{indent}Path"), &self.child_path.escape_path(), opts);
+        MdlWriteAnimIfSome!(lines, depth, opts, "Visibility" => self.visibility);
+        lines.append(&mut self.unknown_tracks.write_mdl("UnknownTrack", depth, opts)?);
         return Ok(lines);
     }
 }
+
+impl_VisitAnims!(Attachment: base,
+    "Visibility" => visibility,
+);
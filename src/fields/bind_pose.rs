@@ -0,0 +1,79 @@
+use crate::*;
+
+#[derive(Dbg, Default)]
+pub struct BindPose {
+    #[dbg(fmt = "{:?}")]
+    pub matrices: Vec<[f32; 12]>, // 3x4 matrix per node, in object id order
+}
+
+impl BindPose {
+    pub const ID: u32 = MdlxMagic::BPOS;
+    const MATRIX_SIZE: u32 = 12;
+
+    pub fn is_empty(&self) -> bool {
+        self.matrices.is_empty()
+    }
+
+    /// `repairs`: `Some` in salvage mode, a truncated matrix drops the rest.
+    pub fn read_mdx(
+        cur: &mut Cursor<&Vec<u8>>,
+        mut repairs: Option<&mut Vec<String>>,
+    ) -> Result<Self, MyError> {
+        let mut this = Self::default();
+        let n: u32 = cur.readx()?;
+        for i in 0..n {
+            if let Some(repairs) = repairs.as_deref_mut()
+                && cur.left() < Self::MATRIX_SIZE * 4
+            {
+                REPAIR!(repairs, "Truncated BindPose matrix {i} of {n}, dropped the rest");
+                break;
+            }
+            let m = cur.read_array::<f32>(Self::MATRIX_SIZE)?;
+            this.matrices.push(m.try_into().unwrap());
+        }
+        return Ok(this);
+    }
+
+    pub fn write_mdx(&self, chunk: &mut MdxChunk) -> Result<(), MyError> {
+        chunk.write(&self.matrices.len())?;
+        for m in &self.matrices {
+            chunk.write(&m.to_vec())?;
+        }
+        return Ok(());
+    }
+
+    pub fn read_mdl(block: &MdlBlock, _: &MdlReadOptions) -> Result<Self, MyError> {
+        block.unexpect_fields()?;
+        block.unexpect_frames()?;
+        let mut this = Self::default();
+        for a in &block.blocks {
+            match_istr!(a.typ.as_str(),
+                "Matrices" => {
+                    a.unexpect_frames()?;
+                    a.unexpect_blocks()?;
+                    for f in &a.fields {
+                        no!(f.name.is_empty(), f.unexpect()?);
+                        let m = f.value.to_fvec(Self::MATRIX_SIZE)?;
+                        this.matrices.push(m.try_into().unwrap());
+                    }
+                },
+                _other => a.unexpect()?,
+            );
+        }
+        return Ok(this);
+    }
+
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        let (indent, indent2, indent3) =
+            (opts.indent(depth), opts.indent(depth + 1), opts.indent(depth + 2));
+        let mut lines: Vec<String> = vec![];
+        lines.push(F!("{indent}BindPose {{"));
+        lines.push(F!("{indent2}Matrices {} {{", self.matrices.len()));
+        for m in &self.matrices {
+            lines.push(F!("{indent3}{},", fmtw(&m.to_vec(), opts)));
+        }
+        lines.push(F!("{indent2}}}"));
+        lines.push(F!("{indent}}}"));
+        return Ok(lines);
+    }
+}
@@ -23,7 +23,7 @@ impl Bone {
         return Ok(());
     }
 
-    pub fn read_mdl(block: &MdlBlock) -> Result<Self, MyError> {
+    pub fn read_mdl(block: &MdlBlock, _: &MdlReadOptions) -> Result<Self, MyError> {
         let mut this = Build! { base: Node::read_mdl(block)? };
         this.base.flags.insert(NodeFlags::Bone);
         this.base.unexpect_mdl_blocks()?;
@@ -37,12 +37,14 @@ impl Bone {
         return Ok(this);
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        let indent = indent!(depth);
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        let indent = opts.indent(depth);
         let mut lines: Vec<String> = vec![];
-        lines.append(&mut self.base.write_mdl(depth)?);
+        lines.append(&mut self.base.write_mdl(depth, opts)?);
         lines.push_if_nneg1(&F!("{indent}GeosetId"), &self.geoset_id);
         lines.push_if_nneg1(&F!("{indent}GeosetAnimId"), &self.geoanim_id);
         return Ok(lines);
     }
 }
+
+impl_VisitAnims!(Bone: base);
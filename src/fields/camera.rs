@@ -17,6 +17,7 @@ pub struct Camera {
     pub rotation: Option<Animation<f32>>,
     #[dbg(formatter = "fmtxx")]
     pub target_translation: Option<Animation<Vec3>>,
+    pub unknown_tracks: Vec<UnknownTrack>,
 }
 
 impl Camera {
@@ -24,7 +25,7 @@ impl Camera {
     const ID_T: u32 = MdlxMagic::KCTR; /* Translation */
     const ID_R: u32 = MdlxMagic::KCRL; /* Rotation (radians) */
     const ID_TT: u32 = MdlxMagic::KTTR; /* Target translation */
-    const NAME_SIZE: u32 = 80;
+    pub const NAME_SIZE: u32 = 80;
 
     pub fn read_mdx(cur: &mut Cursor<&Vec<u8>>) -> Result<Self, MyError> {
         let mut this = Build! {
@@ -36,13 +37,19 @@ impl Camera {
             target: cur.readx()?,
         };
 
+        let mut after = 0; // the last known track, for unknown tracks
         while cur.left() >= 16 {
-            match cur.read_be()? {
+            let id = cur.read_be()?;
+            match id {
                 Self::ID_T => this.translation = Some(Animation::read_mdx(cur)?),
                 Self::ID_R => this.rotation = Some(Animation::read_mdx(cur)?),
                 Self::ID_TT => this.target_translation = Some(Animation::read_mdx(cur)?),
-                id => return ERR!("Unknown animation in {}: {} (0x{:08X})", TNAME!(), u32_to_ascii(id), id),
+                _ => {
+                    this.unknown_tracks.push(UnknownTrack::read_mdx(cur, id, after, TNAME!())?);
+                    continue;
+                },
             }
+            after = id;
         }
 
         return Ok(this);
@@ -56,7 +63,7 @@ impl Camera {
         chunk.write(&self.far_clip)?;
         chunk.write(&self.near_clip)?;
         chunk.write(&self.target)?;
-        MdxWriteAnim!(chunk,
+        MdxWriteAnim!(chunk, self.unknown_tracks;
             Self::ID_T  => self.translation,
             Self::ID_R  => self.rotation,
             Self::ID_TT => self.target_translation,
@@ -68,10 +75,11 @@ impl Camera {
         sz += self.translation.calc_mdx_size();
         sz += self.rotation.calc_mdx_size();
         sz += self.target_translation.calc_mdx_size();
+        sz += self.unknown_tracks.calc_mdx_size();
         return sz;
     }
 
-    pub fn read_mdl(block: &MdlBlock) -> Result<Self, MyError> {
+    pub fn read_mdl(block: &MdlBlock, _: &MdlReadOptions) -> Result<Self, MyError> {
         block.unexpect_frames()?;
         let mut this = Build! { name: block.name.clone() };
         for f in &block.fields {
@@ -88,6 +96,7 @@ impl Camera {
                 "Target" => this.read_mdl_target(f)?,
                 "Translation" => this.translation = Some(Animation::read_mdl(f)?),
                 "Rotation" => this.rotation = Some(Animation::read_mdl(f)?),
+                "UnknownTrack" => this.unknown_tracks.push(UnknownTrack::from_mdl(f)?),
                 _other => f.unexpect()?,
             );
         }
@@ -110,30 +119,37 @@ impl Camera {
         return Ok(());
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        let (indent, indent2) = (indent!(depth), indent!(depth + 1));
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        let (indent, indent2) = (opts.indent(depth), opts.indent(depth + 1));
         let mut lines: Vec<String> = vec![];
 
-        lines.pushx_if_n0(&F!("{indent}Position"), &self.position);
-        lines.pushx_if_n0(&F!("{indent}FieldOfView"), &self.field_of_view);
-        lines.pushx_if_n0(&F!("{indent}FarClip"), &self.far_clip);
-        lines.pushx_if_n0(&F!("{indent}NearClip"), &self.near_clip);
-        MdlWriteAnimIfSome!(lines, depth,
+        lines.pushx_if_n0(&F!("{indent}Position"), &self.position, opts);
+        lines.pushx_if_n0(&F!("{indent}FieldOfView"), &self.field_of_view, opts);
+        lines.pushx_if_n0(&F!("{indent}FarClip"), &self.far_clip, opts);
+        lines.pushx_if_n0(&F!("{indent}NearClip"), &self.near_clip, opts);
+        MdlWriteAnimIfSome!(lines, depth, opts,
             "Translation" => self.translation,
             "Rotation" => self.rotation,
         );
 
         {
             let mut tines: Vec<String> = vec![];
-            tines.pushx_if_n0(&F!("{indent2}Position"), &self.target);
-            MdlWriteAnimIfSome!(tines, depth + 1, "Translation" => self.target_translation);
+            tines.pushx_if_n0(&F!("{indent2}Position"), &self.target, opts);
+            MdlWriteAnimIfSome!(tines, depth + 1, opts, "Translation" => self.target_translation);
             if !tines.is_empty() {
                 lines.push(F!("{indent}Target {{"));
                 lines.append(&mut tines);
                 lines.push(F!("{indent}}}"));
             }
         }
+        lines.append(&mut self.unknown_tracks.write_mdl("UnknownTrack", depth, opts)?);
 
         return Ok(lines);
     }
 }
+
+impl_VisitAnims!(Camera,
+    "Translation"       => translation,
+    "Rotation"          => rotation,
+    "TargetTranslation" => target_translation,
+);
@@ -43,7 +43,7 @@ impl CollisionShape {
         return Ok(());
     }
 
-    pub fn read_mdl(block: &MdlBlock) -> Result<Self, MyError> {
+    pub fn read_mdl(block: &MdlBlock, _: &MdlReadOptions) -> Result<Self, MyError> {
         let mut this = Build! { base: Node::read_mdl(block)? };
         this.base.flags.insert(NodeFlags::CollisionShape);
         for f in &block.fields {
@@ -62,17 +62,19 @@ impl CollisionShape {
         return Ok(this);
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        let indent = indent!(depth);
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        let indent = opts.indent(depth);
         let mut lines: Vec<String> = vec![];
-        lines.append(&mut self.base.write_mdl(depth)?);
+        lines.append(&mut self.base.write_mdl(depth, opts)?);
         lines.push(F!("{indent}{:?},", self.shape));
-        MdlWriteType2!(lines, depth, "Vertices" => self.vertices);
-        lines.pushx_if_n0(&F!("{indent}BoundsRadius"), &self.bounds_radius);
+        MdlWriteType2!(lines, depth, opts, "Vertices" => self.vertices);
+        lines.pushx_if_n0(&F!("{indent}BoundsRadius"), &self.bounds_radius, opts);
         return Ok(lines);
     }
 }
 
+impl_VisitAnims!(CollisionShape: base);
+
 //#region CollisionType
 
 #[derive(Debug, Default, PartialEq, Eq)]
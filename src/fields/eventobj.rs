@@ -14,7 +14,14 @@ impl EventObject {
         if cur.left() >= 8 {
             match cur.read_be()? {
                 EventTrack::ID => this.track = EventTrack::read_mdx(cur)?,
-                id => return ERR!("Unknown chunk in {}: {} (0x{:08X})", TNAME!(), u32_to_ascii(id), id),
+                id => {
+                    return ERR!(
+                        "Unknown chunk in {}: {} (0x{:08X})",
+                        TNAME!(),
+                        u32_to_ascii(id),
+                        id
+                    );
+                },
             }
         }
         return Ok(this);
@@ -26,7 +33,7 @@ impl EventObject {
         return Ok(());
     }
 
-    pub fn read_mdl(block: &MdlBlock) -> Result<Self, MyError> {
+    pub fn read_mdl(block: &MdlBlock, _: &MdlReadOptions) -> Result<Self, MyError> {
         let mut this = Build! { base: Node::read_mdl(block)? };
         this.base.flags.insert(NodeFlags::EventObject);
         this.base.unexpect_mdl_fields()?;
@@ -39,14 +46,16 @@ impl EventObject {
         return Ok(this);
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
         let mut lines: Vec<String> = vec![];
-        lines.append(&mut self.base.write_mdl(depth)?);
-        lines.append(&mut self.track.write_mdl(depth)?);
+        lines.append(&mut self.base.write_mdl(depth, opts)?);
+        lines.append(&mut self.track.write_mdl(depth, opts)?);
         return Ok(lines);
     }
 }
 
+impl_VisitAnims!(EventObject: base);
+
 #[derive(Dbg, SmartDefault)]
 pub struct EventTrack {
     #[dbg(skip)]
@@ -80,8 +89,8 @@ impl EventTrack {
         Ok(Build! { frames: block.to_array("")? })
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        let (indent, indent2) = (indent!(depth), indent!(depth + 1));
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        let (indent, indent2) = (opts.indent(depth), opts.indent(depth + 1));
         let mut lines: Vec<String> = vec![];
         if !self.frames.is_empty() {
             lines.push(F!("{indent}EventTrack {} {{", self.frames.len()));
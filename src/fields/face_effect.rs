@@ -0,0 +1,44 @@
+use crate::*;
+
+#[derive(Dbg, Default)]
+pub struct FaceEffect {
+    pub name: String, // facial animation target
+    pub path: String,
+}
+
+impl FaceEffect {
+    pub const ID: u32 = MdlxMagic::FAFX;
+    pub const NAME_SIZE: u32 = 80;
+    pub const PATH_SIZE: u32 = 260;
+
+    pub fn read_mdx(cur: &mut Cursor<&Vec<u8>>) -> Result<Self, MyError> {
+        Ok(Self {
+            name: cur.read_string(Self::NAME_SIZE)?,
+            path: cur.read_string(Self::PATH_SIZE)?,
+        })
+    }
+
+    pub fn write_mdx(&self, chunk: &mut MdxChunk) -> Result<(), MyError> {
+        chunk.write_string(&self.name, Self::NAME_SIZE)?;
+        chunk.write_string(&self.path, Self::PATH_SIZE)?;
+        return Ok(());
+    }
+
+    pub fn read_mdl(block: &MdlBlock, _: &MdlReadOptions) -> Result<Self, MyError> {
+        block.unexpect_frames()?;
+        block.unexpect_blocks()?;
+        let mut this = Build! { name: block.name.clone() };
+        for f in &block.fields {
+            match_istr!(f.name.as_str(),
+                "Path" => this.path = f.value.to()?,
+                _other => f.unexpect()?,
+            );
+        }
+        return Ok(this);
+    }
+
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        let indent = opts.indent(depth);
+        return Ok(vec![F!("{indent}Path \"{}\",", self.path.escape_path())]);
+    }
+}
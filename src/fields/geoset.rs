@@ -1,6 +1,6 @@
 use crate::*;
 
-#[derive(Dbg, Default)]
+#[derive(Dbg, Default, Clone)]
 pub struct Geoset {
     #[dbg(formatter = "fmtx")]
     pub vertices: Vec<Vec3>,
@@ -22,17 +22,23 @@ pub struct Geoset {
     pub mtxgrpcnts: Vec<i32>,
     #[dbg(formatter = "fmtx")]
     pub mtx_indices: Vec<i32>,
+    #[dbg(formatter = "fmtx")]
+    pub tangents: Vec<Vec4>, // v900+
+    #[dbg(formatter = "fmtx")]
+    pub skin_weights: Vec<u8>, // v900+, [bone0..bone3, weight0..weight3] per vertex
 
     pub material_id: i32,
     pub sel_group: i32,
-    pub sel_type: i32, // 0=None, 4=Unselectable
+    pub sel_type: i32,    // 0=None, 4=Unselectable
+    pub lod: i32,         // v900+
+    pub lod_name: String, // v900+
     pub extent: BoundExtent,
     pub anim_extents: Vec<BoundExtent>,
 }
 
 //#region BoundExtent
 
-#[derive(Dbg, Default)]
+#[derive(Dbg, Default, Clone)]
 pub struct BoundExtent {
     #[dbg(formatter = "fmtx")]
     pub bound_radius: f32,
@@ -72,12 +78,12 @@ impl BoundExtent {
         return Ok(this);
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
         let mut lines: Vec<String> = vec![];
-        let indent = indent!(depth);
-        lines.pushx_if_n0(&F!("{indent}BoundsRadius"), &self.bound_radius);
-        lines.pushx_if_n0(&F!("{indent}MinimumExtent"), &self.min_extent);
-        lines.pushx_if_n0(&F!("{indent}MaximumExtent"), &self.max_extent);
+        let indent = opts.indent(depth);
+        lines.pushx_if_n0(&F!("{indent}BoundsRadius"), &self.bound_radius, opts);
+        lines.pushx_if_n0(&F!("{indent}MinimumExtent"), &self.min_extent, opts);
+        lines.pushx_if_n0(&F!("{indent}MaximumExtent"), &self.max_extent, opts);
         return Ok(lines);
     }
 }
@@ -179,8 +185,12 @@ macro_rules! MdxWriteGeosetChunk_CalcSize {
 
 impl Geoset {
     pub const ID: u32 = MdlxMagic::GEOS;
+    pub const LOD_NAME_SIZE: u32 = 80;
+    pub const SKIN_WEIGHT_SIZE: usize = 8;
+    pub const MAX_VERTICES: usize = 65536; // face_vertices are u16
+    pub const MAX_VERTEX_GROUPS: usize = 256; // vtxgrps are u8
 
-    pub fn read_mdx(cur: &mut Cursor<&Vec<u8>>) -> Result<Self, MyError> {
+    pub fn read_mdx(cur: &mut Cursor<&Vec<u8>>, version: i32) -> Result<Self, MyError> {
         let mut this = Build!();
 
         while cur.left() >= 16 {
@@ -188,7 +198,9 @@ impl Geoset {
             match id {
                 MdlxMagic::VRTX => this.vertices = cur.read_array(n)?,
                 MdlxMagic::NRMS => this.normals = cur.read_array(n)?,
-                MdlxMagic::PTYP => this.face_types = cur.read_array::<i32>(n)?.convert(|a| FaceType::from(*a)),
+                MdlxMagic::PTYP => {
+                    this.face_types = cur.read_array::<i32>(n)?.convert(|a| FaceType::from(*a))
+                },
                 MdlxMagic::PCNT => this.face_vtxcnts = cur.read_array(n)?,
                 MdlxMagic::PVTX => this.face_vertices = cur.read_array(n)?,
                 MdlxMagic::GNDX => this.vtxgrps = cur.read_array(n)?,
@@ -196,10 +208,16 @@ impl Geoset {
                 MdlxMagic::MATS => this.mtx_indices = cur.read_array(n)?,
                 MdlxMagic::UVAS => this.nvs_count = n,
                 MdlxMagic::UVBS => this.uvss.push(cur.read_array(n)?),
+                MdlxMagic::TANG => this.tangents = cur.read_array(n)?,
+                MdlxMagic::SKIN => this.skin_weights = cur.read_array(n)?,
                 id => {
                     this.material_id = id.swap_bytes() as i32;
                     this.sel_group = n as i32;
                     this.sel_type = cur.readx()?;
+                    if version > 800 {
+                        this.lod = cur.readx()?;
+                        this.lod_name = cur.read_string(Self::LOD_NAME_SIZE)?;
+                    }
                     this.extent = BoundExtent::read_mdx(cur)?;
                     let en = cur.readx()?;
                     for _ in 0..en {
@@ -217,7 +235,23 @@ impl Geoset {
         let tn = TNAME!();
 
         let (nnorm, nvert) = (self.normals.len(), self.vertices.len());
-        yes!(nnorm > 0 && nnorm != nvert, wlog!("OMG! {tn} #[normals] {} != {} #[vertices] ?", nnorm, nvert));
+        yes!(
+            nnorm > 0 && nnorm != nvert,
+            wlog!("OMG! {tn} #[normals] {} != {} #[vertices] ?", nnorm, nvert)
+        );
+
+        let ntang = self.tangents.len();
+        yes!(
+            ntang > 0 && ntang != nvert,
+            wlog!("OMG! {tn} #[tangents] {} != {} #[vertices] ?", ntang, nvert)
+        );
+
+        let nskin = self.skin_weights.len();
+        let nskinv = nvert * Self::SKIN_WEIGHT_SIZE;
+        yes!(
+            nskin > 0 && nskin != nskinv,
+            wlog!("OMG! {tn} #[skin_weights] {} != {} (8 * #[vertices]) ?", nskin, nskinv)
+        );
 
         let n = self.uvss.len() as u32;
         yes!(self.nvs_count != n, wlog!("OMG! {tn} #[UVs] {} != {n} ?", self.nvs_count));
@@ -229,16 +263,24 @@ impl Geoset {
         for (t, n) in self.face_types.iter().zip(self.face_vtxcnts.iter()) {
             if *t == tri {
                 if n % trin != 0 {
-                    wlog!("Expecting length of {t:?} (in {}) to be multiple of {trin}, got {n}.", TNAME!());
+                    wlog!(
+                        "Expecting length of {t:?} (in {}) to be multiple of {trin}, got {n}.",
+                        TNAME!()
+                    );
                 }
             } else {
-                wlog!("OMG! {} other than {tri:?}({}): {:?}", TNAME!(&tri), tri.to(), self.face_types);
+                wlog!(
+                    "OMG! {} other than {tri:?}({}): {:?}",
+                    TNAME!(&tri),
+                    tri.to(),
+                    self.face_types
+                );
             }
         }
     }
 
-    pub fn write_mdx(&self, chunk: &mut MdxChunk) -> Result<(), MyError> {
-        chunk.write(&self.calc_mdx_size())?;
+    pub fn write_mdx(&self, chunk: &mut MdxChunk, version: i32) -> Result<(), MyError> {
+        chunk.write(&self.calc_mdx_size(version))?;
 
         MdxWriteGeosetChunk!(chunk,
             MdlxMagic::VRTX => self.vertices,
@@ -254,12 +296,27 @@ impl Geoset {
         chunk.write(&self.material_id)?;
         chunk.write(&self.sel_group)?;
         chunk.write(&self.sel_type)?;
+        if version > 800 {
+            chunk.write(&self.lod)?;
+            chunk.write_string(&self.lod_name, Self::LOD_NAME_SIZE)?;
+        }
         self.extent.write_mdx(chunk)?;
         chunk.write(&self.anim_extents.len())?;
         for a in self.anim_extents.iter() {
             a.write_mdx(chunk)?;
         }
 
+        if version > 800 {
+            if !self.tangents.is_empty() {
+                MdxWriteGeosetChunk!(chunk, MdlxMagic::TANG => self.tangents);
+            }
+            if !self.skin_weights.is_empty() {
+                MdxWriteGeosetChunk!(chunk, MdlxMagic::SKIN => self.skin_weights);
+            }
+        } else if !self.tangents.is_empty() || !self.skin_weights.is_empty() {
+            wlog!("{} tangents and skin weights require version > 800, dropped.", TNAME!());
+        }
+
         chunk.write_be(&MdlxMagic::UVAS)?;
         chunk.write(&self.uvss.len())?;
         for a in self.uvss.iter() {
@@ -270,7 +327,7 @@ impl Geoset {
 
         return Ok(());
     }
-    pub fn calc_mdx_size(&self) -> u32 {
+    pub fn calc_mdx_size(&self, version: i32) -> u32 {
         let mut sz: u32 = 4; // sz itself
 
         sz += MdxWriteGeosetChunk_CalcSize!(
@@ -285,8 +342,16 @@ impl Geoset {
         );
 
         sz += 12; // material_id + sel_group + sel_type
+        yes!(version > 800, sz += 4 + Self::LOD_NAME_SIZE); // lod + lod_name
         sz += BoundExtent::size(); // extent
         sz += 4 + BoundExtent::size() * self.anim_extents.len() as u32; // anim_extents
+        if version > 800 {
+            yes!(!self.tangents.is_empty(), sz += MdxWriteGeosetChunk_CalcSize!(self.tangents));
+            yes!(
+                !self.skin_weights.is_empty(),
+                sz += MdxWriteGeosetChunk_CalcSize!(self.skin_weights)
+            );
+        }
 
         sz += 8; // "UVAS" + len
         for a in self.uvss.iter() {
@@ -297,14 +362,23 @@ impl Geoset {
         return sz;
     }
 
-    pub fn read_mdl(block: &MdlBlock) -> Result<Self, MyError> {
-        let mut this = Build! { extent: BoundExtent::read_mdl(&block, false)? };
+    pub fn read_mdl(block: &MdlBlock, opts: &MdlReadOptions) -> Result<Self, MyError> {
+        let opts = MdlReadOptions { split_geosets: false, ..opts.clone() };
+        return Ok(Self::read_mdl_split(block, &opts)?.remove(0));
+    }
+
+    /// Read a geoset, and split it into parts within the engine limits if `opts.split_geosets`.
+    pub fn read_mdl_split(block: &MdlBlock, opts: &MdlReadOptions) -> Result<Vec<Self>, MyError> {
+        let mut this: Self = Build! { extent: BoundExtent::read_mdl(&block, false)? };
+        let (mut faces, mut groups): (Vec<i32>, Vec<i32>) = (vec![], vec![]); // not narrowed yet
         block.unexpect_frames()?;
         for f in &block.fields {
             match_istr!(f.name.as_str(),
                 "MaterialID" => this.material_id = f.value.to()?,
                 "SelectionGroup" => this.sel_group = f.value.to()?,
                 "Unselectable" => this.sel_type |= f.expect_flag(4)?,
+                "LevelOfDetail" => this.lod = f.value.to()?,
+                "Name" => this.lod_name = f.value.to()?,
                 "BoundsRadius" | "MinimumExtent" | "MaximumExtent" => (),
                 _other => f.unexpect()?,
             );
@@ -314,11 +388,13 @@ impl Geoset {
                 "Vertices" => this.vertices = a.to_array("")?,
                 "Normals" => this.normals = a.to_array("")?,
                 "TVertices" => this.uvss.push(a.to_array("")?),
-                "VertexGroup" => this.vtxgrps = a.to_array("")?,
+                "VertexGroup" => groups = a.to_array("")?,
+                "Tangents" => this.tangents = a.to_array("")?,
+                "SkinWeights" => this.skin_weights = a.to_array("")?,
                 "Faces" => {
                     a.unexpect_fields()?;
                     a.unexpect_frames()?;
-                    for b in &a.blocks { this.read_mdl_face(b)?; }
+                    for b in &a.blocks { this.read_mdl_face(b, &mut faces)?; }
                 },
                 "Groups" => {
                     a.unexpect_frames()?;
@@ -330,27 +406,63 @@ impl Geoset {
             );
         }
         this.nvs_count = this.uvss.len() as u32;
-        this.validate();
-        return Ok(this);
+
+        let parts = match opts.split_geosets && this.is_oversize(&faces, &groups) {
+            true => this.split(&faces, &groups)?,
+            false => vec![this.narrow(&faces, &groups)?],
+        };
+        for a in parts.iter() {
+            a.validate();
+        }
+        return Ok(parts);
+    }
+    /// Geosets of triangles (for importers), split into parts within the engine limits.
+    /// Vertex attributes and material_id are taken from `self`,
+    /// `groups` are the matrices (object ids) of each vertex group.
+    pub fn from_triangles(
+        mut self,
+        faces: &[i32],
+        vtxgrps: &[i32],
+        groups: &[Vec<i32>],
+    ) -> Result<Vec<Self>, MyError> {
+        self.face_types = vec![FaceType::Triangles];
+        self.face_vtxcnts = vec![faces.len() as i32];
+        self.mtxgrpcnts = groups.iter().map(|g| g.len() as i32).collect();
+        self.mtx_indices = groups.iter().flatten().copied().collect();
+        self.nvs_count = self.uvss.len() as u32;
+        return match self.is_oversize(faces, vtxgrps) {
+            true => self.split(faces, vtxgrps),
+            false => Ok(vec![self.narrow(faces, vtxgrps)?]),
+        };
     }
-    fn read_mdl_face(&mut self, block: &MdlBlock) -> Result<(), MyError> {
+
+    fn read_mdl_face(&mut self, block: &MdlBlock, faces: &mut Vec<i32>) -> Result<(), MyError> {
         block.unexpect_frames()?;
         block.unexpect_blocks()?;
 
         let (ts, line) = (block.typ.as_str(), block.line);
         let t = FaceType::from_str(ts);
         if let FaceType::Error(_) = t {
-            EXIT1!("Unknown {} {ts:?} at line {line}.", TNAME!(&t));
+            return SYNTAX_ERR!(line, block.col, "Unknown {} {ts:?} at line {line}.", TNAME!(&t));
         }
 
         no!(t == FaceType::Triangles, wlog!("OMG! Bad {} ({:?}) at line {}.", TNAME!(&t), t, line));
         for f in &block.fields {
-            no!(f.name.is_empty(), EXIT1!("Unexpected {:?} (in {}) at line {}.", f.name, ts, f.line));
-            let iv: Vec<i32> = f.value.to()?;
+            no!(
+                f.name.is_empty(),
+                return SYNTAX_ERR!(
+                    f.line,
+                    f.col,
+                    "Unexpected {:?} (in {}) at line {}.",
+                    f.name,
+                    ts,
+                    f.line
+                )
+            );
+            let mut iv: Vec<i32> = f.value.to()?;
             self.face_types.push(t);
             self.face_vtxcnts.push(iv.len() as i32);
-            let mut miv = iv.convert(|v| *v as u16);
-            self.face_vertices.append(&mut miv);
+            faces.append(&mut iv);
         }
 
         return Ok(());
@@ -358,7 +470,7 @@ impl Geoset {
     fn read_mdl_matrices(&mut self, field: &MdlField) -> Result<(), MyError> {
         let (n, v, l) = (&field.name, &field.value, field.line);
         if !n.eq_icase("Matrices") {
-            EXIT1!("Unknown GroupType {n:?} at line {l}.");
+            return SYNTAX_ERR!(l, field.col, "Unknown GroupType {n:?} at line {l}.");
         }
         let iv: Vec<i32> = v.to()?;
         self.mtxgrpcnts.push(iv.len() as i32);
@@ -367,46 +479,224 @@ impl Geoset {
         return Ok(());
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        let (indent, indent2, indent3) = (indent!(depth), indent!(depth + 1), indent!(depth + 2));
+    fn is_oversize(&self, faces: &[i32], groups: &[i32]) -> bool {
+        let max_face = faces.iter().max().map_or(0, |v| *v as i64);
+        let max_group = groups.iter().max().map_or(0, |v| *v as i64);
+        return self.vertices.len() > Self::MAX_VERTICES
+            || max_face >= Self::MAX_VERTICES as i64
+            || max_group >= Self::MAX_VERTEX_GROUPS as i64;
+    }
+
+    /// Store faces as u16 and vertex groups as u8, fail instead of wrapping around.
+    fn narrow(mut self, faces: &[i32], groups: &[i32]) -> Result<Self, MyError> {
+        let (owner, nvert, ngrp) = (TNAME!().s(), self.vertices.len(), self.mtxgrpcnts.len());
+        if nvert > Self::MAX_VERTICES {
+            let (field, limit) = ("vertex count".s(), Self::MAX_VERTICES);
+            return Err(MyError::LimitExceeded { owner, field, value: nvert, limit });
+        }
+        for (&v, field, count, limit) in faces
+            .iter()
+            .map(|v| (v, "face vertex", nvert, Self::MAX_VERTICES))
+            .chain(groups.iter().map(|v| (v, "vertex group", ngrp, Self::MAX_VERTEX_GROUPS)))
+        {
+            if v < 0 {
+                let index = v as i64;
+                return Err(MyError::BadReference { owner, field: field.s(), index, count });
+            } else if v as usize >= limit {
+                let (value, limit) = (v as usize, limit - 1);
+                return Err(MyError::LimitExceeded { owner, field: field.s(), value, limit });
+            }
+        }
+        self.face_vertices = faces.convert(|v| *v as u16);
+        self.vtxgrps = groups.convert(|v| *v as u8);
+        return Ok(self);
+    }
+
+    /// Split faces into parts within MAX_VERTICES and MAX_VERTEX_GROUPS.
+    /// A triangle (or a whole face group of other types) is never split,
+    /// vertex attributes follow their vertices.
+    fn split(&self, faces: &[i32], groups: &[i32]) -> Result<Vec<Self>, MyError> {
+        let (owner, nvert, ngrp) = (TNAME!(), self.vertices.len(), self.mtxgrpcnts.len());
+        let bad_ref = |field: &str, index: i64, count: usize| {
+            Err(MyError::BadReference { owner: owner.s(), field: field.s(), index, count })
+        };
+        if let Some(&v) = faces.iter().find(|&&v| v < 0 || v as usize >= nvert) {
+            return bad_ref("face_vertices", v as i64, nvert);
+        }
+        if let Some(&v) = groups.iter().find(|&&v| v < 0 || v as usize >= ngrp) {
+            return bad_ref("vtxgrps", v as i64, ngrp);
+        }
+        if !groups.is_empty() && groups.len() != nvert {
+            return bad_ref("vtxgrps", groups.len() as i64, nvert);
+        }
+
+        let mut units: Vec<(FaceType, &[i32])> = vec![];
+        let mut start = 0_usize;
+        for (&t, &n) in self.face_types.iter().zip(self.face_vtxcnts.iter()) {
+            let n = n.max(0) as usize;
+            let Some(list) = faces.get(start..start + n) else {
+                return bad_ref("face_vertices", (start + n) as i64 - 1, faces.len());
+            };
+            match t == FaceType::Triangles && n.is_multiple_of(3) {
+                true => units.extend(list.chunks(3).map(|a| (t, a))),
+                false => units.push((t, list)),
+            }
+            start += n;
+        }
+
+        let mut parts: Vec<Self> = vec![];
+        let mut part = GeosetPart::default();
+        for (t, list) in units {
+            if !part.fits(list, groups) {
+                parts.push(self.build_part(&part, groups));
+                part = GeosetPart::default();
+                if !part.fits(list, groups) {
+                    let (owner, field) = (owner.s(), F!("{t:?} vertex count"));
+                    let (value, limit) = (list.len(), Self::MAX_VERTICES);
+                    return Err(MyError::LimitExceeded { owner, field, value, limit });
+                }
+            }
+            part.add(t, list, groups);
+        }
+        parts.push(self.build_part(&part, groups));
+        let n = parts.len();
+        log!("Split an oversize {owner} ({nvert} vertices, {ngrp} matrix groups) into {n} parts.");
+        return Ok(parts);
+    }
+
+    fn build_part(&self, part: &GeosetPart, groups: &[i32]) -> Self {
+        fn pick<T: Copy>(list: &[T], indices: &[usize]) -> Vec<T> {
+            indices.iter().map(|&i| list[i]).collect()
+        }
+        let (nvert, verts) = (self.vertices.len(), part.verts.as_slice());
+        let mut this: Self = Build! {
+            vertices: pick(&self.vertices, verts),
+            material_id: self.material_id,
+            sel_group: self.sel_group,
+            sel_type: self.sel_type,
+            lod: self.lod,
+            lod_name: self.lod_name.clone(),
+            extent: self.extent.clone(),
+            anim_extents: self.anim_extents.clone(),
+        };
+        yes!(self.normals.len() == nvert, this.normals = pick(&self.normals, verts));
+        yes!(self.tangents.len() == nvert, this.tangents = pick(&self.tangents, verts));
+        for uvs in self.uvss.iter() {
+            this.uvss.push(yesno!(uvs.len() == nvert, pick(uvs, verts), vec![]));
+        }
+        this.nvs_count = this.uvss.len() as u32;
+        if self.skin_weights.len() == nvert * Self::SKIN_WEIGHT_SIZE {
+            let n = Self::SKIN_WEIGHT_SIZE;
+            let weights = |i: usize| self.skin_weights[i * n..i * n + n].to_vec();
+            this.skin_weights = verts.iter().flat_map(|&i| weights(i)).collect();
+        }
+
+        if groups.is_empty() {
+            this.mtxgrpcnts = self.mtxgrpcnts.clone();
+            this.mtx_indices = self.mtx_indices.clone();
+        } else {
+            this.vtxgrps = verts.iter().map(|&i| part.gmap[&groups[i]]).collect();
+            let mut offsets = vec![0_usize];
+            for n in self.mtxgrpcnts.iter() {
+                offsets.push(offsets[offsets.len() - 1] + (*n).max(0) as usize);
+            }
+            for &k in part.grps.iter() {
+                this.mtxgrpcnts.push(self.mtxgrpcnts[k]);
+                let list = self.mtx_indices.get(offsets[k]..offsets[k + 1]).unwrap_or(&[]);
+                this.mtx_indices.extend_from_slice(list);
+            }
+        }
+
+        for (t, list) in part.faces.iter() {
+            this.face_types.push(*t);
+            this.face_vtxcnts.push(list.len() as i32);
+            this.face_vertices.extend_from_slice(list);
+        }
+        return this;
+    }
+
+    pub fn write_mdl(
+        &self,
+        depth: u8,
+        opts: &MdlWriteOptions,
+        version: i32,
+    ) -> Result<Vec<String>, MyError> {
+        let (indent, indent2, indent3) =
+            (opts.indent(depth), opts.indent(depth + 1), opts.indent(depth + 2));
         let mut lines: Vec<String> = vec![];
 
-        MdlWriteType2!(lines, depth, "Vertices" => self.vertices);
-        MdlWriteType2!(lines, depth, "Normals" => self.normals);
+        MdlWriteType2!(lines, depth, opts, "Vertices" => self.vertices);
+        MdlWriteType2!(lines, depth, opts, "Normals" => self.normals);
         for uvs in self.uvss.iter() {
-            MdlWriteType2!(lines, depth, "TVertices" => *uvs);
+            MdlWriteType2!(lines, depth, opts, "TVertices" => *uvs);
         }
         {
             lines.push(F!("{indent}VertexGroup {{"));
-            lines.append(&mut self.vtxgrps.iter().map(|x| F!("{indent2}{},", fmtx(x))).collect::<Vec<String>>());
+            lines.append(
+                &mut self
+                    .vtxgrps
+                    .iter()
+                    .map(|x| F!("{indent2}{},", fmtw(x, opts)))
+                    .collect::<Vec<String>>(),
+            );
+            lines.push(F!("{indent}}}"));
+        }
+        MdlWriteType2!(lines, depth, opts, "Tangents" => self.tangents);
+        if !self.skin_weights.is_empty() {
+            let n = Self::SKIN_WEIGHT_SIZE;
+            lines.push(F!("{indent}SkinWeights {} {{", self.skin_weights.len() / n));
+            for w in self.skin_weights.chunks(n) {
+                lines.push(F!(
+                    "{indent2}{},",
+                    w.iter().map(|x| fmtw(x, opts)).collect::<Vec<String>>().join(", ")
+                ));
+            }
             lines.push(F!("{indent}}}"));
         }
         {
-            lines.push(F!("{indent}Faces {} {} {{", self.face_types.len(), self.face_vertices.len()));
+            lines.push(F!(
+                "{indent}Faces {} {} {{",
+                self.face_types.len(),
+                self.face_vertices.len()
+            ));
             let mut i = 0_usize;
             for (t, n) in self.face_types.iter().zip(self.face_vtxcnts.iter()) {
                 let n = *n as usize;
                 lines.push(F!("{indent2}{:?} {{", t));
-                if let Some(slice) = &self.face_vertices.get(i..i + n) {
-                    let s = slice.iter().map(|x| fmtx(x)).collect::<Vec<String>>().join(", ");
-                    lines.push(F!("{indent3}{{ {} }},", s));
-                }
+                let slice =
+                    self.face_vertices.get(i..i + n).ok_or_else(|| MyError::BadReference {
+                        owner: TNAME!().s(),
+                        field: "face_vertices".s(),
+                        index: (i + n) as i64 - 1,
+                        count: self.face_vertices.len(),
+                    })?;
+                let s = slice.iter().map(|x| fmtw(x, opts)).collect::<Vec<String>>().join(", ");
+                lines.push(F!("{indent3}{{ {} }},", s));
                 lines.push(F!("{indent2}}}"));
                 i += n;
             }
             lines.push(F!("{indent}}}"));
         }
         {
-            lines.push(F!("{indent}Groups {} {} {{", self.mtxgrpcnts.len(), self.mtx_indices.len()));
+            lines.push(F!(
+                "{indent}Groups {} {} {{",
+                self.mtxgrpcnts.len(),
+                self.mtx_indices.len()
+            ));
             let mut i = 0_usize;
             for n in &self.mtxgrpcnts {
                 let n = *n as usize;
                 let mut slist: Vec<String> = vec![];
                 slist.push(F!("Matrices {{"));
-                if let Some(slice) = &self.mtx_indices.get(i..i + n) {
-                    let s = slice.iter().map(|x| fmtx(x)).collect::<Vec<String>>().join(", ");
-                    slist.push(F!("{}", s));
-                }
+                let slice =
+                    self.mtx_indices.get(i..i + n).ok_or_else(|| MyError::BadReference {
+                        owner: TNAME!().s(),
+                        field: "mtx_indices".s(),
+                        index: (i + n) as i64 - 1,
+                        count: self.mtx_indices.len(),
+                    })?;
+                let s = slice.iter().map(|x| fmtw(x, opts)).collect::<Vec<String>>().join(", ");
+                slist.push(F!("{}", s));
                 slist.push(F!("}},"));
                 lines.push(F!("{indent2}{}", slist.join(" ")));
                 i += n;
@@ -414,24 +704,79 @@ impl Geoset {
             lines.push(F!("{indent}}}"));
         }
 
-        lines.append(&mut self.extent.write_mdl(depth)?);
+        lines.append(&mut self.extent.write_mdl(depth, opts)?);
         for en in &self.anim_extents {
             lines.push(F!("{indent}Anim {{"));
-            lines.append(&mut en.write_mdl(depth + 1)?);
+            lines.append(&mut en.write_mdl(depth + 1, opts)?);
             lines.push(F!("{indent}}}"));
         }
 
         lines.push_if_nneg1(&F!("{indent}MaterialID"), &self.material_id);
         lines.push_if_nneg1(&F!("{indent}SelectionGroup"), &self.sel_group);
         yes!(self.sel_type != 0, lines.push(F!("{indent}Unselectable,")));
+        if version > 800 {
+            lines.push(F!("{indent}LevelOfDetail {},", self.lod));
+            lines.push_if(
+                !self.lod_name.is_empty(),
+                F!("{indent}Name \"{}\",", self.lod_name.escape()),
+            );
+        }
 
         return Ok(lines);
     }
 }
 
+//#region GeosetPart
+
+/// Faces of a part of an oversize geoset, see Geoset::split().
+#[derive(Default)]
+struct GeosetPart {
+    vmap: HashMap<i32, u16>, // old vertex -> new vertex
+    verts: Vec<usize>,       // new vertex -> old vertex
+    gmap: HashMap<i32, u8>,  // old matrix group -> new matrix group
+    grps: Vec<usize>,        // new matrix group -> old matrix group
+    faces: Vec<(FaceType, Vec<u16>)>,
+}
+
+impl GeosetPart {
+    fn fits(&self, list: &[i32], groups: &[i32]) -> bool {
+        let new_verts: HashSet<i32> =
+            list.iter().filter(|v| !self.vmap.contains_key(v)).copied().collect();
+        let new_grps: HashSet<i32> = match groups.is_empty() {
+            true => HashSet::new(),
+            false => {
+                let grps = list.iter().map(|&v| groups[v as usize]);
+                grps.filter(|g| !self.gmap.contains_key(g)).collect()
+            },
+        };
+        return self.verts.len() + new_verts.len() <= Geoset::MAX_VERTICES
+            && self.grps.len() + new_grps.len() <= Geoset::MAX_VERTEX_GROUPS;
+    }
+
+    fn add(&mut self, t: FaceType, list: &[i32], groups: &[i32]) {
+        let mut face: Vec<u16> = vec![];
+        for &v in list {
+            let n = self.verts.len();
+            let nv = *self.vmap.entry(v).or_insert_with(|| n as u16);
+            yes!(nv as usize == n, self.verts.push(v as usize));
+            face.push(nv);
+            if !groups.is_empty() {
+                let (g, n) = (groups[v as usize], self.grps.len());
+                yes!(!self.gmap.contains_key(&g), self.grps.push(g as usize));
+                self.gmap.entry(g).or_insert(n as u8);
+            }
+        }
+        match self.faces.last_mut() {
+            Some((lt, last)) if *lt == t && t == FaceType::Triangles => last.append(&mut face),
+            _ => self.faces.push((t, face)),
+        }
+    }
+}
+
+//#endregion
 //#region GeosetAnim
 
-#[derive(Dbg, SmartDefault)]
+#[derive(Dbg, SmartDefault, Clone)]
 pub struct GeosetAnim {
     #[default = 1.0]
     pub alpha: f32,
@@ -446,6 +791,7 @@ pub struct GeosetAnim {
     pub alpha_anim: Option<Animation<f32>>,
     #[dbg(formatter = "fmtxx")]
     pub color_anim: Option<Animation<Vec3>>, // BGR
+    pub unknown_tracks: Vec<UnknownTrack>,
 }
 bitflags! {
     #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -466,12 +812,18 @@ impl GeosetAnim {
         this.flags = GeosetAnimFlags::from_bits_retain(cur.readx()?);
         this.color = cur.readx()?;
         this.geoset_id = cur.readx()?;
+        let mut after = 0; // the last known track, for unknown tracks
         while cur.left() >= 16 {
-            match cur.read_be()? {
+            let id = cur.read_be()?;
+            match id {
                 Self::ID_ALPHA => this.alpha_anim = Some(Animation::read_mdx(cur)?),
                 Self::ID_COLOR => this.color_anim = Some(Animation::read_mdx(cur)?),
-                id => return ERR!("Unknown animation in {}: {} (0x{:08X})", TNAME!(), u32_to_ascii(id), id),
+                _ => {
+                    this.unknown_tracks.push(UnknownTrack::read_mdx(cur, id, after, TNAME!())?);
+                    continue;
+                },
             }
+            after = id;
         }
         return Ok(this);
     }
@@ -482,7 +834,7 @@ impl GeosetAnim {
         chunk.write(&self.flags.bits())?;
         chunk.write(&self.color)?;
         chunk.write(&self.geoset_id)?;
-        MdxWriteAnim!(chunk,
+        MdxWriteAnim!(chunk, self.unknown_tracks;
             Self::ID_ALPHA => self.alpha_anim,
             Self::ID_COLOR => self.color_anim,
         );
@@ -492,10 +844,11 @@ impl GeosetAnim {
         let mut sz: u32 = 28; // sz + alpha + flags + color + geoset_id
         sz += self.alpha_anim.calc_mdx_size();
         sz += self.color_anim.calc_mdx_size();
+        sz += self.unknown_tracks.calc_mdx_size();
         return sz;
     }
 
-    pub fn read_mdl(block: &MdlBlock) -> Result<Self, MyError> {
+    pub fn read_mdl(block: &MdlBlock, opts: &MdlReadOptions) -> Result<Self, MyError> {
         block.unexpect_frames()?;
         let mut this = Build!();
         for f in &block.fields {
@@ -518,36 +871,43 @@ impl GeosetAnim {
                     this.color_anim = Some(Animation::read_mdl(f)?);
                     this.flags |= GeosetAnimFlags::UseColor;
                 },
+                "UnknownTrack" => this.unknown_tracks.push(UnknownTrack::from_mdl(f)?),
                 _other => (),
             );
         }
-        if *mdl_rgb!() {
+        if opts.rgb {
             this.color_anim = this.color_anim.map(|a| a.convert(|v| v.reverse()));
         }
         return Ok(this);
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        let indent = indent!(depth);
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        let indent = opts.indent(depth);
         let mut lines: Vec<String> = vec![];
         lines.push_if_nneg1(&F!("{indent}GeosetId"), &self.geoset_id);
         lines.push_if(self.flags.contains(GeosetAnimFlags::DropShadow), F!("{indent}DropShadow,"));
 
-        MdlWriteAnimBoth!(lines, depth, "Alpha" => self.alpha_anim => 1.0 => self.alpha);
+        MdlWriteAnimBoth!(lines, depth, opts, "Alpha" => self.alpha_anim => 1.0 => self.alpha);
         if self.flags.contains(GeosetAnimFlags::UseColor) {
             if let Some(anim) = &self.color_anim {
-                if *mdl_rgb!() {
-                    MdlWriteAnim!(lines, depth, "Color" => anim.convert(|v| v.reverse()));
+                if opts.rgb {
+                    MdlWriteAnim!(lines, depth, opts, "Color" => anim.convert(|v| v.reverse()));
                 } else {
-                    MdlWriteAnim!(lines, depth, "Color" => anim);
+                    MdlWriteAnim!(lines, depth, opts, "Color" => anim);
                 }
             } else {
-                MdlWriteAnimStatic!(lines, depth, "Color" => self.color);
+                MdlWriteAnimStatic!(lines, depth, opts, "Color" => self.color);
             }
         }
+        lines.append(&mut self.unknown_tracks.write_mdl("UnknownTrack", depth, opts)?);
 
         return Ok(lines);
     }
 }
 
+impl_VisitAnims!(GeosetAnim,
+    "Alpha" => alpha_anim,
+    "Color" => color_anim,
+);
+
 //#endregion
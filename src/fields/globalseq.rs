@@ -16,18 +16,18 @@ impl GlobalSequence {
         chunk.write(&self.duration)
     }
 
-    pub fn read_mdl(f: &MdlField) -> Result<Self, MyError> {
+    pub fn read_mdl(f: &MdlField, _: &MdlReadOptions) -> Result<Self, MyError> {
         yesno!(f.name.eq_icase("Duration"), Ok(Self { duration: f.value.to()? }), f.unexpect())
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        Ok(vec![F!("{}Duration {},", indent!(depth), self.duration)])
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        Ok(vec![F!("{}Duration {},", opts.indent(depth), self.duration)])
     }
 }
 
 impl Formatter for Vec<GlobalSequence> {
-    fn fmt(&self) -> String {
-        let s = self.iter().map(|x| fmtx(&x.duration)).collect::<Vec<_>>().join(", ");
+    fn fmt_with(&self, opts: &MdlWriteOptions) -> String {
+        let s = self.iter().map(|x| fmtw(&x.duration, opts)).collect::<Vec<_>>().join(", ");
         return F!("[{}]", s);
     }
 }
@@ -16,7 +16,7 @@ impl Helper {
         self.base.write_mdx(chunk)
     }
 
-    pub fn read_mdl(block: &MdlBlock) -> Result<Self, MyError> {
+    pub fn read_mdl(block: &MdlBlock, _: &MdlReadOptions) -> Result<Self, MyError> {
         let mut base: Node = Node::read_mdl(block)?;
         base.flags.insert(NodeFlags::Helper);
         base.unexpect_mdl_fields()?;
@@ -24,7 +24,9 @@ impl Helper {
         return Ok(Self { base });
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        self.base.write_mdl(depth)
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        self.base.write_mdl(depth, opts)
     }
 }
+
+impl_VisitAnims!(Helper: base);
@@ -29,6 +29,7 @@ pub struct Light {
     pub amb_intensity_anim: Option<Animation<f32>>,
     #[dbg(formatter = "fmtxx")]
     pub visibility: Option<Animation<f32>>,
+    pub unknown_tracks: Vec<UnknownTrack>,
 }
 
 impl Light {
@@ -56,8 +57,10 @@ impl Light {
         this.amb_color = cur.readx()?;
         this.amb_intensity = cur.readx()?;
 
+        let mut after = 0; // the last known track, for unknown tracks
         while cur.left() >= 16 {
-            match cur.read_be()? {
+            let id = cur.read_be()?;
+            match id {
                 Self::ID_AS => this.attenuate_start_anim = Some(Animation::read_mdx(cur)?),
                 Self::ID_AE => this.attenuate_end_anim = Some(Animation::read_mdx(cur)?),
                 Self::ID_C => this.color_anim = Some(Animation::read_mdx(cur)?),
@@ -65,8 +68,12 @@ impl Light {
                 Self::ID_AC => this.amb_color_anim = Some(Animation::read_mdx(cur)?),
                 Self::ID_AI => this.amb_intensity_anim = Some(Animation::read_mdx(cur)?),
                 Self::ID_V => this.visibility = Some(Animation::read_mdx(cur)?),
-                id => return ERR!("Unknown animation in {}: {} (0x{:08X})", TNAME!(), u32_to_ascii(id), id),
+                _ => {
+                    this.unknown_tracks.push(UnknownTrack::read_mdx(cur, id, after, TNAME!())?);
+                    continue;
+                },
             }
+            after = id;
         }
 
         return Ok(this);
@@ -84,7 +91,7 @@ impl Light {
         chunk.write(&self.amb_color)?;
         chunk.write(&self.amb_intensity)?;
 
-        MdxWriteAnim!(chunk,
+        MdxWriteAnim!(chunk, self.unknown_tracks;
             Self::ID_AS => self.attenuate_start_anim,
             Self::ID_AE => self.attenuate_end_anim,
             Self::ID_C  => self.color_anim,
@@ -105,10 +112,11 @@ impl Light {
         sz += self.amb_color_anim.calc_mdx_size();
         sz += self.amb_intensity_anim.calc_mdx_size();
         sz += self.visibility.calc_mdx_size();
+        sz += self.unknown_tracks.calc_mdx_size();
         return sz;
     }
 
-    pub fn read_mdl(block: &MdlBlock) -> Result<Self, MyError> {
+    pub fn read_mdl(block: &MdlBlock, opts: &MdlReadOptions) -> Result<Self, MyError> {
         let mut this = Build! { base: Node::read_mdl(block)? };
         this.base.flags.insert(NodeFlags::Light);
 
@@ -133,30 +141,31 @@ impl Light {
                 "AmbColor" => this.amb_color_anim = Some(Animation::read_mdl(f)?),
                 "AmbIntensity" => this.amb_intensity_anim = Some(Animation::read_mdl(f)?),
                 "Visibility" => this.visibility = Some(Animation::read_mdl(f)?),
+                "UnknownTrack" => this.unknown_tracks.push(UnknownTrack::from_mdl(f)?),
                 _other => this.base.unexpect_mdl_block(f)?,
             );
         }
 
-        if *mdl_rgb!() {
+        if opts.rgb {
             this.color_anim = this.color_anim.map(|a| a.convert(|v| v.reverse()));
             this.amb_color_anim = this.amb_color_anim.map(|a| a.convert(|v| v.reverse()));
         }
         return Ok(this);
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        let indent = indent!(depth);
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        let indent = opts.indent(depth);
         let mut lines: Vec<String> = vec![];
 
-        lines.append(&mut self.base.write_mdl(depth)?);
+        lines.append(&mut self.base.write_mdl(depth, opts)?);
         lines.push(F!("{indent}{:?},", self.typ));
 
         let bgr_anim = self.color_anim.as_ref().and_then(|a| Some(a.convert(|v| v.reverse())));
         let bgr2_anim = self.amb_color_anim.as_ref().and_then(|a| Some(a.convert(|v| v.reverse())));
-        let color_anim = yesno!(*mdl_rgb!(), &bgr_anim, &self.color_anim);
-        let amb_color_anim = yesno!(*mdl_rgb!(), &bgr2_anim, &self.amb_color_anim);
+        let color_anim = yesno!(opts.rgb, &bgr_anim, &self.color_anim);
+        let amb_color_anim = yesno!(opts.rgb, &bgr2_anim, &self.amb_color_anim);
 
-        MdlWriteAnimBoth!(lines, depth,
+        MdlWriteAnimBoth!(lines, depth, opts,
             "AttenuationStart" => self.attenuate_start_anim => 0.0 => self.attenuate_start,
             "AttenuationEnd" => self.attenuate_end_anim => 0.0 => self.attenuate_end,
             "Color" => color_anim => Vec3::ZERO => self.color,
@@ -164,12 +173,23 @@ impl Light {
             "AmbColor" => amb_color_anim => Vec3::ZERO => self.amb_color,
             "AmbIntensity" => self.amb_intensity_anim => 0.0 => self.amb_intensity,
         );
-        MdlWriteAnimIfSome!(lines, depth, "Visibility" => self.visibility);
+        MdlWriteAnimIfSome!(lines, depth, opts, "Visibility" => self.visibility);
+        lines.append(&mut self.unknown_tracks.write_mdl("UnknownTrack", depth, opts)?);
 
         return Ok(lines);
     }
 }
 
+impl_VisitAnims!(Light: base,
+    "AttenuationStart" => attenuate_start_anim,
+    "AttenuationEnd"   => attenuate_end_anim,
+    "Color"            => color_anim,
+    "Intensity"        => intensity_anim,
+    "AmbColor"         => amb_color_anim,
+    "AmbIntensity"     => amb_intensity_anim,
+    "Visibility"       => visibility,
+);
+
 //#region LightType
 
 #[derive(Debug, Default)]
@@ -13,6 +13,10 @@ impl MdlxMagic {
     pub const LAYS: u32 = 0x4C415953; /* - Layers */
     pub const KMTA: u32 = 0x4B4D5441; /* - - Alpha */
     pub const KMTF: u32 = 0x4B4D5446; /* - - Texture ID */
+    pub const KMTE: u32 = 0x4B4D5445; /* - - Emissive Gain (v900+) */
+    pub const KFC3: u32 = 0x4B464333; /* - - Fresnel Color (v1000+) */
+    pub const KFCA: u32 = 0x4B464341; /* - - Fresnel Opacity (v1000+) */
+    pub const KFTC: u32 = 0x4B465443; /* - - Fresnel Team Color (v1000+) */
 
     pub const TXAN: u32 = 0x5458414E; /* Texture Animations */
     pub const KTAT: u32 = 0x4B544154; /* - Translation */
@@ -30,6 +34,8 @@ impl MdlxMagic {
     pub const MATS: u32 = 0x4D415453; /* - Matrices Group Indices (flat of all matrices group) */
     pub const UVAS: u32 = 0x55564153; /* - UVs count */
     pub const UVBS: u32 = 0x55564253; /* - UVs */
+    pub const TANG: u32 = 0x54414E47; /* - Vertex Tangents (v900+) */
+    pub const SKIN: u32 = 0x534B494E; /* - Skin Weights (v900+, 4 bone indices + 4 weights per vertex) */
 
     pub const GEOA: u32 = 0x47454F41; /* Geoset Animations */
     pub const KGAO: u32 = 0x4B47414F; /* - Alpha */
@@ -83,6 +89,17 @@ impl MdlxMagic {
     pub const KP2R: u32 = 0x4B503252; /* - Variation */
     pub const KP2G: u32 = 0x4B503247; /* - Gravity */
 
+    pub const CORN: u32 = 0x434F524E; /* PopcornFX Emitters (v900+) */
+    pub const KPPA: u32 = 0x4B505041; /* - Alpha */
+    pub const KPPC: u32 = 0x4B505043; /* - Color */
+    pub const KPPE: u32 = 0x4B505045; /* - Emission Rate */
+    pub const KPPL: u32 = 0x4B50504C; /* - LifeSpan */
+    pub const KPPS: u32 = 0x4B505053; /* - Speed */
+    pub const KPPV: u32 = 0x4B505056; /* - Visibility */
+
+    pub const FAFX: u32 = 0x46414658; /* Face Effects (v900+) */
+    pub const BPOS: u32 = 0x42504F53; /* Bind Pose (v900+) */
+
     pub const RIBB: u32 = 0x52494242; /* Ribbon Emitters */
     pub const KRVS: u32 = 0x4B525653; /* - Visibility */
     pub const KRHA: u32 = 0x4B524841; /* - Height Above */
@@ -90,4 +107,26 @@ impl MdlxMagic {
     pub const KRAL: u32 = 0x4B52414C; /* - Alpha */
     pub const KRCO: u32 = 0x4B52434F; /* - Color */
     pub const KRTX: u32 = 0x4B525458; /* - TextureSlot */
+
+    /// Top-level chunks, used to resync when reading damaged files.
+    #[rustfmt::skip]
+    pub const CHUNKS: [u32; 23] = [
+        Self::VERS, Self::MODL, Self::SEQS, Self::GLBS, Self::TEXS, Self::PIVT, Self::MTLS, Self::TXAN,
+        Self::GEOS, Self::GEOA, Self::CAMS, Self::BONE, Self::HELP, Self::CLID, Self::ATCH, Self::EVTS,
+        Self::LITE, Self::PREM, Self::PRE2, Self::CORN, Self::FAFX, Self::BPOS, Self::RIBB,
+    ];
+    /// Tags inside chunks (sub-chunks and tracks).
+    #[rustfmt::skip]
+    pub const TAGS: [u32; 66] = [
+        Self::LAYS, Self::KMTA, Self::KMTF, Self::KMTE, Self::KFC3, Self::KFCA, Self::KFTC,
+        Self::KTAT, Self::KTAR, Self::KTAS, Self::VRTX, Self::NRMS, Self::PTYP, Self::PCNT,
+        Self::PVTX, Self::GNDX, Self::MTGC, Self::MATS, Self::UVAS, Self::UVBS, Self::TANG,
+        Self::SKIN, Self::KGAO, Self::KGAC, Self::KCTR, Self::KCRL, Self::KTTR, Self::KGTR,
+        Self::KGRT, Self::KGSC, Self::KATV, Self::KEVT, Self::KLAV, Self::KLAS, Self::KLAE,
+        Self::KLAC, Self::KLAI, Self::KLBC, Self::KLBI, Self::KPEV, Self::KPEE, Self::KPEG,
+        Self::KPLN, Self::KPLT, Self::KPEL, Self::KPES, Self::KP2V, Self::KP2E, Self::KP2W,
+        Self::KP2N, Self::KP2S, Self::KP2L, Self::KP2R, Self::KP2G, Self::KPPA, Self::KPPC,
+        Self::KPPE, Self::KPPL, Self::KPPS, Self::KPPV, Self::KRVS, Self::KRHA, Self::KRHB,
+        Self::KRAL, Self::KRCO, Self::KRTX,
+    ];
 }
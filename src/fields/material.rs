@@ -7,6 +7,7 @@ pub struct Material {
     pub priority_plane: i32,
     #[dbg(fmt = "{:?}")]
     pub flags: MaterialFlags,
+    pub shader: String, // v900+
     pub layers: Vec<Layer>,
 }
 bitflags! {
@@ -21,9 +22,14 @@ bitflags! {
 impl Material {
     pub const ID: u32 = MdlxMagic::MTLS as u32;
 
-    pub fn read_mdx(cur: &mut Cursor<&Vec<u8>>) -> Result<Self, MyError> {
+    pub const SHADER_NAME_SIZE: u32 = 80;
+
+    pub fn read_mdx(cur: &mut Cursor<&Vec<u8>>, version: i32) -> Result<Self, MyError> {
         let mut this = Build! { priority_plane: cur.readx()? };
         this.flags = MaterialFlags::from_bits_retain(cur.readx()?);
+        if version > 800 {
+            this.shader = cur.read_string(Self::SHADER_NAME_SIZE)?;
+        }
         if cur.left() > 8 && cur.read_be::<u32>()? == Layer::ID {
             let count: i32 = cur.readx()?;
             for _ in 0..count {
@@ -31,32 +37,36 @@ impl Material {
                 yes!(sz < 4, EXIT1!("{} layer size: {} (need >= 4)", TNAME!(), sz));
                 let body = cur.read_bytes(sz as u32 - 4)?;
                 let mut cur2 = Cursor::new(&body);
-                this.layers.push(Layer::read_mdx(&mut cur2)?);
+                this.layers.push(Layer::read_mdx(&mut cur2, version)?);
             }
         }
         return Ok(this);
     }
 
-    pub fn write_mdx(&self, chunk: &mut MdxChunk) -> Result<(), MyError> {
-        chunk.write(&self.calc_mdx_size())?;
+    pub fn write_mdx(&self, chunk: &mut MdxChunk, version: i32) -> Result<(), MyError> {
+        chunk.write(&self.calc_mdx_size(version))?;
         chunk.write(&self.priority_plane)?;
         chunk.write(&self.flags.bits())?;
+        if version > 800 {
+            chunk.write_string(&self.shader, Self::SHADER_NAME_SIZE)?;
+        }
         chunk.write_be(&Layer::ID)?;
         chunk.write(&self.layers.len())?;
         for a in &self.layers {
-            a.write_mdx(chunk)?;
+            a.write_mdx(chunk, version)?;
         }
         return Ok(());
     }
-    pub fn calc_mdx_size(&self) -> u32 {
+    pub fn calc_mdx_size(&self, version: i32) -> u32 {
         let mut sz: u32 = 20; // sz + priority_plane + flags + "LAYS" + layer_count
+        yes!(version > 800, sz += Self::SHADER_NAME_SIZE);
         for a in &self.layers {
-            sz += a.calc_mdx_size();
+            sz += a.calc_mdx_size(version);
         }
         return sz;
     }
 
-    pub fn read_mdl(block: &MdlBlock) -> Result<Self, MyError> {
+    pub fn read_mdl(block: &MdlBlock, opts: &MdlReadOptions) -> Result<Self, MyError> {
         block.unexpect_frames()?;
         let mut this = Build!();
         for f in &block.fields {
@@ -65,29 +75,45 @@ impl Material {
                 "ConstantColor" => this.flags |= f.expect_flag(MaterialFlags::ConstantColor)?,
                 "SortPrimsFarZ" => this.flags |= f.expect_flag(MaterialFlags::SortPrimsFarZ)?,
                 "FullResolution" => this.flags |= f.expect_flag(MaterialFlags::FullResolution)?,
+                "Shader" => this.shader = f.value.to()?,
                 _other => f.unexpect()?,
             );
         }
         for f in &block.blocks {
             match_istr!(f.typ.as_str(),
-                "Layer" => this.layers.push(Layer::read_mdl(f)?),
+                "Layer" => this.layers.push(Layer::read_mdl(f, opts)?),
                 _other => f.unexpect()?,
             );
         }
         return Ok(this);
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        let (indent, indent2) = (indent!(depth), indent!(depth + 1));
+    pub fn write_mdl(
+        &self,
+        depth: u8,
+        opts: &MdlWriteOptions,
+        version: i32,
+    ) -> Result<Vec<String>, MyError> {
+        let (indent, indent2) = (opts.indent(depth), opts.indent(depth + 1));
         let mut lines: Vec<String> = vec![];
         lines.push(F!("{indent}Material {{"));
-        lines.pushx_if_n0(&F!("{indent2}PriorityPlane"), &self.priority_plane);
-        yes!(self.flags.contains(MaterialFlags::ConstantColor), lines.push(F!("{indent2}ConstantColor,")));
-        yes!(self.flags.contains(MaterialFlags::SortPrimsFarZ), lines.push(F!("{indent2}SortPrimsFarZ,")));
-        yes!(self.flags.contains(MaterialFlags::FullResolution), lines.push(F!("{indent2}FullResolution,")));
+        yes!(version > 800, lines.push(F!("{indent2}Shader \"{}\",", self.shader.escape())));
+        lines.pushx_if_n0(&F!("{indent2}PriorityPlane"), &self.priority_plane, opts);
+        yes!(
+            self.flags.contains(MaterialFlags::ConstantColor),
+            lines.push(F!("{indent2}ConstantColor,"))
+        );
+        yes!(
+            self.flags.contains(MaterialFlags::SortPrimsFarZ),
+            lines.push(F!("{indent2}SortPrimsFarZ,"))
+        );
+        yes!(
+            self.flags.contains(MaterialFlags::FullResolution),
+            lines.push(F!("{indent2}FullResolution,"))
+        );
 
         for layer in &self.layers {
-            lines.append(&mut layer.write_mdl(2)?);
+            lines.append(&mut layer.write_mdl(2, opts, version)?);
         }
 
         lines.push(F!("{indent}}}"));
@@ -110,11 +136,27 @@ pub struct Layer {
     pub coordid: i32,
     #[default(1.0)]
     pub alpha: f32,
+    #[default(1.0)]
+    pub emissive_gain: f32, // v900+
+    #[dbg(formatter = "fmtx")]
+    #[default(Vec3::ONE)]
+    pub fresnel_color: Vec3, // v1000+, RGB
+    pub fresnel_opacity: f32,    // v1000+
+    pub fresnel_team_color: f32, // v1000+
 
     #[dbg(formatter = "fmtxx")]
     pub alpha_anim: Option<Animation<f32>>,
     #[dbg(formatter = "fmtxx")]
     pub texid_anim: Option<Animation<i32>>,
+    #[dbg(formatter = "fmtxx")]
+    pub emissive_gain_anim: Option<Animation<f32>>,
+    #[dbg(formatter = "fmtxx")]
+    pub fresnel_color_anim: Option<Animation<Vec3>>,
+    #[dbg(formatter = "fmtxx")]
+    pub fresnel_opacity_anim: Option<Animation<f32>>,
+    #[dbg(formatter = "fmtxx")]
+    pub fresnel_team_color_anim: Option<Animation<f32>>,
+    pub unknown_tracks: Vec<UnknownTrack>,
 }
 bitflags! {
     #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -132,8 +174,12 @@ impl Layer {
     pub const ID: u32 = MdlxMagic::LAYS;
     const ID_ALPHA: u32 = MdlxMagic::KMTA;
     const ID_TEXID: u32 = MdlxMagic::KMTF;
+    const ID_EMISSIVE_GAIN: u32 = MdlxMagic::KMTE;
+    const ID_FRESNEL_COLOR: u32 = MdlxMagic::KFC3;
+    const ID_FRESNEL_OPACITY: u32 = MdlxMagic::KFCA;
+    const ID_FRESNEL_TEAM_COLOR: u32 = MdlxMagic::KFTC;
 
-    pub fn read_mdx(cur: &mut Cursor<&Vec<u8>>) -> Result<Self, MyError> {
+    pub fn read_mdx(cur: &mut Cursor<&Vec<u8>>, version: i32) -> Result<Self, MyError> {
         let mut this = Build!();
 
         this.filter_mode = FilterMode::from(cur.readx()?);
@@ -146,40 +192,87 @@ impl Layer {
         this.texture_anim_id = cur.readx()?;
         this.coordid = cur.readx()?;
         this.alpha = cur.readx()?;
+        if version > 800 {
+            this.emissive_gain = cur.readx()?;
+        }
+        if version > 900 {
+            this.fresnel_color = cur.readx()?;
+            this.fresnel_opacity = cur.readx()?;
+            this.fresnel_team_color = cur.readx()?;
+        }
 
+        let mut after = 0; // the last known track, for unknown tracks
         while cur.left() >= 16 {
-            match cur.read_be()? {
+            let id = cur.read_be()?;
+            match id {
                 Self::ID_ALPHA => this.alpha_anim = Some(Animation::read_mdx(cur)?),
                 Self::ID_TEXID => this.texid_anim = Some(Animation::read_mdx(cur)?),
-                id => EXIT1!("Unknown animation in {}: {} (0x{:08X})", TNAME!(), u32_to_ascii(id), id),
+                Self::ID_EMISSIVE_GAIN => this.emissive_gain_anim = Some(Animation::read_mdx(cur)?),
+                Self::ID_FRESNEL_COLOR => this.fresnel_color_anim = Some(Animation::read_mdx(cur)?),
+                Self::ID_FRESNEL_OPACITY => {
+                    this.fresnel_opacity_anim = Some(Animation::read_mdx(cur)?)
+                },
+                Self::ID_FRESNEL_TEAM_COLOR => {
+                    this.fresnel_team_color_anim = Some(Animation::read_mdx(cur)?)
+                },
+                _ => {
+                    this.unknown_tracks.push(UnknownTrack::read_mdx(cur, id, after, TNAME!())?);
+                    continue;
+                },
             }
+            after = id;
         }
 
         return Ok(this);
     }
 
-    pub fn write_mdx(&self, chunk: &mut MdxChunk) -> Result<(), MyError> {
-        chunk.write(&self.calc_mdx_size())?;
+    pub fn write_mdx(&self, chunk: &mut MdxChunk, version: i32) -> Result<(), MyError> {
+        chunk.write(&self.calc_mdx_size(version))?;
         chunk.write(&self.filter_mode.to())?;
         chunk.write(&self.flags.bits())?;
         chunk.write(&self.texture_id)?;
         chunk.write(&self.texture_anim_id)?;
         chunk.write(&self.coordid)?;
         chunk.write(&self.alpha)?;
-        MdxWriteAnim!(chunk,
+        if version > 800 {
+            chunk.write(&self.emissive_gain)?;
+        }
+        if version > 900 {
+            chunk.write(&self.fresnel_color)?;
+            chunk.write(&self.fresnel_opacity)?;
+            chunk.write(&self.fresnel_team_color)?;
+        }
+        // tracks of a later version are skipped, not the unknown tracks after them
+        let (v900, v1000) = (version > 800, version > 900);
+        MdxWriteAnim!(chunk, self.unknown_tracks;
             Self::ID_ALPHA => self.alpha_anim,
             Self::ID_TEXID => self.texid_anim,
+            Self::ID_EMISSIVE_GAIN => yesno!(v900, &self.emissive_gain_anim, &None),
+            Self::ID_FRESNEL_COLOR => yesno!(v1000, &self.fresnel_color_anim, &None),
+            Self::ID_FRESNEL_OPACITY => yesno!(v1000, &self.fresnel_opacity_anim, &None),
+            Self::ID_FRESNEL_TEAM_COLOR => yesno!(v1000, &self.fresnel_team_color_anim, &None),
         );
         return Ok(());
     }
-    pub fn calc_mdx_size(&self) -> u32 {
+    pub fn calc_mdx_size(&self, version: i32) -> u32 {
         let mut sz: u32 = 28; // sz + filter_mode + flags + texture_id + texanim_id + coordid + alpha
         sz += self.alpha_anim.calc_mdx_size();
         sz += self.texid_anim.calc_mdx_size();
+        if version > 800 {
+            sz += 4; // emissive_gain
+            sz += self.emissive_gain_anim.calc_mdx_size();
+        }
+        if version > 900 {
+            sz += 20; // fresnel_color + fresnel_opacity + fresnel_team_color
+            sz += self.fresnel_color_anim.calc_mdx_size();
+            sz += self.fresnel_opacity_anim.calc_mdx_size();
+            sz += self.fresnel_team_color_anim.calc_mdx_size();
+        }
+        sz += self.unknown_tracks.calc_mdx_size();
         return sz;
     }
 
-    pub fn read_mdl(block: &MdlBlock) -> Result<Self, MyError> {
+    pub fn read_mdl(block: &MdlBlock, opts: &MdlReadOptions) -> Result<Self, MyError> {
         block.unexpect_frames()?;
         let mut this = Build!();
         for f in &block.fields {
@@ -195,6 +288,10 @@ impl Layer {
                 "NoDepthSet" =>this.flags |= f.expect_flag(LayerFlags::NoDepthSet)?,
                 "CoordId" => this.coordid = f.value.to()?,
                 "Alpha" => this.alpha = f.value.to()?,
+                "EmissiveGain" => this.emissive_gain = f.value.to()?,
+                "FresnelColor" => this.fresnel_color = f.value.to()?,
+                "FresnelOpacity" => this.fresnel_opacity = f.value.to()?,
+                "FresnelTeamColor" => this.fresnel_team_color = f.value.to()?,
                 _other => f.unexpect()?,
             );
         }
@@ -202,14 +299,27 @@ impl Layer {
             match_istr!(f.typ.as_str(),
                 "Alpha" => this.alpha_anim = Some(Animation::read_mdl(f)?),
                 "TextureID" => this.texid_anim = Some(Animation::read_mdl(f)?),
+                "EmissiveGain" => this.emissive_gain_anim = Some(Animation::read_mdl(f)?),
+                "FresnelColor" => this.fresnel_color_anim = Some(Animation::read_mdl(f)?),
+                "FresnelOpacity" => this.fresnel_opacity_anim = Some(Animation::read_mdl(f)?),
+                "FresnelTeamColor" => this.fresnel_team_color_anim = Some(Animation::read_mdl(f)?),
+                "UnknownTrack" => this.unknown_tracks.push(UnknownTrack::from_mdl(f)?),
                 _other => f.unexpect()?,
             );
         }
+        if opts.rgb {
+            this.fresnel_color_anim = this.fresnel_color_anim.map(|a| a.convert(|v| v.reverse()));
+        }
         return Ok(this);
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        let (indent, indent2) = (indent!(depth), indent!(depth + 1));
+    pub fn write_mdl(
+        &self,
+        depth: u8,
+        opts: &MdlWriteOptions,
+        version: i32,
+    ) -> Result<Vec<String>, MyError> {
+        let (indent, indent2) = (opts.indent(depth), opts.indent(depth + 1));
         let mut lines: Vec<String> = vec![];
         lines.push(F!("{indent}Layer {{"));
         lines.push(F!("{indent2}FilterMode {:?},", self.filter_mode));
@@ -223,16 +333,40 @@ impl Layer {
         lines.push_if_nneg1(&F!("{indent2}TVertexAnimId"), &self.texture_anim_id);
         lines.push_if_n0(&F!("{indent2}CoordId"), &self.coordid);
 
-        MdlWriteAnimBoth!(lines, depth + 1,
+        MdlWriteAnimBoth!(lines, depth + 1, opts,
             "TextureID" => self.texid_anim => -1 => self.texture_id,
             "Alpha" => self.alpha_anim => 1.0 => self.alpha,
         );
+        if version > 800 {
+            MdlWriteAnimBoth!(lines, depth + 1, opts,
+                "EmissiveGain" => self.emissive_gain_anim => 1.0 => self.emissive_gain,
+            );
+        }
+        if version > 900 {
+            let bgr_anim = self.fresnel_color_anim.as_ref().map(|a| a.convert(|v| v.reverse()));
+            let fresnel_color_anim = yesno!(opts.rgb, &bgr_anim, &self.fresnel_color_anim);
+            MdlWriteAnimBoth!(lines, depth + 1, opts,
+                "FresnelColor" => fresnel_color_anim => Vec3::ONE => self.fresnel_color,
+                "FresnelOpacity" => self.fresnel_opacity_anim => 0.0 => self.fresnel_opacity,
+                "FresnelTeamColor" => self.fresnel_team_color_anim => 0.0 => self.fresnel_team_color,
+            );
+        }
 
+        lines.append(&mut self.unknown_tracks.write_mdl("UnknownTrack", depth + 1, opts)?);
         lines.push(F!("{indent}}}"));
         return Ok(lines);
     }
 }
 
+impl_VisitAnims!(Layer,
+    "Alpha"            => alpha_anim,
+    "TextureID"        => texid_anim,
+    "EmissiveGain"     => emissive_gain_anim,
+    "FresnelColor"     => fresnel_color_anim,
+    "FresnelOpacity"   => fresnel_opacity_anim,
+    "FresnelTeamColor" => fresnel_team_color_anim,
+);
+
 //#endregion
 //#region FilterMode
 
@@ -275,7 +409,7 @@ impl FilterMode {
             "Modulate" => Ok(Self::Modulate),
             "Modulate2x" => Ok(Self::Modulate2x),
             "AlphaKey" => Ok(Self::AlphaKey),
-            _err => ERR!("Unknown {} {_err:?} (in Material::Layer) at line {}.", f.name, f.line),
+            _err => SYNTAX_ERR!(f.line, f.col, "Unknown {} {_err:?} (in Material::Layer) at line {}.", f.name, f.line),
         )
     }
 
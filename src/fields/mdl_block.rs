@@ -4,11 +4,15 @@ use crate::*;
 
 trait _ExtendPair {
     fn lineno(&self) -> u32;
+    fn colno(&self) -> u32;
 }
 impl _ExtendPair for Pair<'_, Rule> {
     fn lineno(&self) -> u32 {
         self.line_col().0 as u32
     }
+    fn colno(&self) -> u32 {
+        self.line_col().1 as u32
+    }
 }
 
 //#endregion
@@ -20,6 +24,7 @@ pub struct MdlBlock {
     pub name: String,
     pub scope: String,
     pub line: u32,
+    pub col: u32,
     #[dbg(formatter = "fmtx")]
     pub fields: Vec<MdlField>,
     pub frames: Vec<MdlFrame>,
@@ -28,7 +33,7 @@ pub struct MdlBlock {
 
 impl MdlBlock {
     pub fn from(pair: Pair<'_, Rule>, scope: &str) -> Result<Self, MyError> {
-        let mut this = Build! {line: pair.lineno(), scope: scope.s()};
+        let mut this = Build! {line: pair.lineno(), col: pair.colno(), scope: scope.s()};
         let inner = pair.into_inner();
         for p in inner {
             match p.as_rule() {
@@ -52,7 +57,7 @@ impl MdlBlock {
         let typ = yesno!(n.is_empty(), F!("{:?}", t), t.s());
         let name = yesno!(n.is_empty(), "".s(), F!(" {n:?}"));
         let inscope = yesno!(s.is_empty(), "".s(), F!(" (in {s})"));
-        ERR!("Unexpected {typ}{name}{inscope} at line {l}")
+        SYNTAX_ERR!(*l, self.col, "Unexpected {typ}{name}{inscope} at line {l}")
     }
     pub fn unexpect_fields(&self) -> Result<(), MyError> {
         for f in &self.fields {
@@ -83,7 +88,12 @@ impl MdlBlock {
         let item_name = yesno!(name.is_empty(), yesno!(n > 1, "items", "item"), name);
         match a.len() == n {
             true => Ok(a),
-            _ => ERR!("Expecting {n} {item_name} for {scope} at line {line}, got {}", a.len()),
+            _ => SYNTAX_ERR!(
+                *line,
+                self.col,
+                "Expecting {n} {item_name} for {scope} at line {line}, got {}",
+                a.len()
+            ),
         }
     }
 }
@@ -96,14 +106,15 @@ pub struct MdlField {
     pub name: String,
     pub scope: String,
     pub line: u32,
+    pub col: u32,
     #[dbg(fmt = "{:?}")]
     pub value: MdlValue, // option
 }
 
 impl MdlField {
     pub fn from(pair: Pair<'_, Rule>, scope: &str) -> Result<Self, MyError> {
-        let mut this = Build! {scope: scope.s(), line: pair.lineno()};
-        this.value.line = this.line;
+        let mut this = Build! {scope: scope.s(), line: pair.lineno(), col: pair.colno()};
+        (this.value.line, this.value.col) = (this.line, this.col);
         this.value.scope = this.scope.s();
         let inner = pair.into_inner();
         let mut first_ident = true;
@@ -122,7 +133,14 @@ impl MdlField {
 
     pub fn unexpect<T>(&self) -> Result<T, MyError> {
         let name = yesno!(self.name.is_empty(), &self.value.raw, &self.name);
-        ERR!("Unexpected {:?} (in {}) at line {}", name, self.scope, self.line)
+        SYNTAX_ERR!(
+            self.line,
+            self.col,
+            "Unexpected {:?} (in {}) at line {}",
+            name,
+            self.scope,
+            self.line
+        )
     }
     pub fn expect_flag<T>(&self, v: T) -> Result<T, MyError> {
         yesno!(!self.name.is_empty() && self.value.is_empty(), Ok(v), self.value.unexpect())
@@ -130,14 +148,14 @@ impl MdlField {
 }
 
 impl Formatter for MdlField {
-    fn fmt(&self) -> String {
+    fn fmt_with(&self, _: &MdlWriteOptions) -> String {
         F!("{} = {:?}", self.name, &self.value)
     }
 }
 
 impl Formatter for Vec<MdlField> {
-    fn fmt(&self) -> String {
-        F!("{:#?}", self.iter().map(|x| Formatter::fmt(x)).collect::<Vec<_>>())
+    fn fmt_with(&self, opts: &MdlWriteOptions) -> String {
+        F!("{:#?}", self.iter().map(|x| x.fmt_with(opts)).collect::<Vec<_>>())
     }
 }
 
@@ -148,6 +166,7 @@ impl Formatter for Vec<MdlField> {
 pub struct MdlFrame {
     pub scope: String,
     pub line: u32,
+    pub col: u32,
     pub frame: i32,
     #[dbg(fmt = "{:?}")]
     pub value: MdlValue,
@@ -159,13 +178,13 @@ pub struct MdlFrame {
 
 impl MdlFrame {
     pub fn from(pair: Pair<'_, Rule>, scope: &str) -> Result<Self, MyError> {
-        let mut this = Build! { scope:scope.s(), line:pair.lineno() };
+        let mut this = Build! { scope:scope.s(), line:pair.lineno(), col:pair.colno() };
         let mut inner = pair.into_inner();
         let fstr = inner.next().unwrap().as_str();
         this.frame = fstr.parse().unwrap();
         this.value = MdlValue::from(inner.next().unwrap(), fstr, &this.scope)?;
-        this.intan = Build!(MdlValue, name:"InTan".s(),  scope:scope.s(), line:this.value.line);
-        this.outan = Build!(MdlValue, name:"OutTan".s(), scope:scope.s(), line:this.value.line);
+        this.intan = Build!(MdlValue, name:"InTan".s(),  scope:scope.s(), line:this.value.line, col:this.value.col);
+        this.outan = Build!(MdlValue, name:"OutTan".s(), scope:scope.s(), line:this.value.line, col:this.value.col);
         for p in inner {
             let f = MdlField::from(p, &this.scope)?;
             match_istr!(f.name.as_str(),
@@ -178,7 +197,14 @@ impl MdlFrame {
     }
 
     pub fn unexpect<T>(&self) -> Result<T, MyError> {
-        ERR!("Unexpected '{}:' (in {}) at line {}", self.frame, self.scope, self.line)
+        SYNTAX_ERR!(
+            self.line,
+            self.col,
+            "Unexpected '{}:' (in {}) at line {}",
+            self.frame,
+            self.scope,
+            self.line
+        )
     }
 }
 
@@ -205,6 +231,7 @@ pub struct MdlValue {
     pub raw: String,
     pub typ: MdlValueType,
     pub line: u32,
+    pub col: u32,
 }
 
 impl Display for MdlValue {
@@ -216,10 +243,12 @@ impl Display for MdlValue {
 impl MdlValue {
     pub fn from(p: Pair<'_, Rule>, name: &str, scope: &str) -> Result<Self, MyError> {
         let raw = p.as_str();
-        let mut this = Build! {name:name.s(), scope:scope.s(), line: p.lineno(), raw:raw.s()};
+        let mut this =
+            Build! {name:name.s(), scope:scope.s(), line: p.lineno(), col: p.colno(), raw:raw.s()};
         this.typ = match p.as_rule() {
             Rule::integer => MdlValueType::Integer(raw.parse()?),
             Rule::float => MdlValueType::Float(raw.parse()?),
+            Rule::float_bits => MdlValueType::Float(Self::parse_float_bits(raw)?),
             Rule::identifier => MdlValueType::Flag(raw.s()),
             Rule::string => MdlValueType::String(Self::unwrap_string(raw)),
             Rule::identifier_array => MdlValueType::FlagArray(
@@ -233,6 +262,8 @@ impl MdlValue {
                     let s = p.as_str();
                     if p.as_rule() == Rule::float {
                         fv.push(s.parse()?);
+                    } else if p.as_rule() == Rule::float_bits {
+                        fv.push(Self::parse_float_bits(s)?);
                     } else {
                         // 19: number of digits in i64:MAX
                         let i = if s.len() < 19 {
@@ -279,6 +310,11 @@ impl MdlValue {
         }
     }
 
+    /// `0x7FC00000`: the bits of a f32, see `fmt_float_lossless`.
+    fn parse_float_bits(s: &str) -> Result<f32, MyError> {
+        Ok(f32::from_bits(u32::from_str_radix(&s[2..], 16)?))
+    }
+
     pub fn unwrap_string(s: &str) -> String {
         let s = &s[1..s.len() - 1]; // remove quotes
         s.unescape()
@@ -301,17 +337,33 @@ impl MdlValue {
         let forname = yesno!(self.name.is_empty(), "".s(), F!(" for {:?}", self.name));
         let inscope = yesno!(self.scope.is_empty(), "".s(), F!(" (in {})", self.scope));
         let gottype = yesno!(self.is_empty(), "".s(), F!(", got {:?}", self.typ));
-        ERR!("Expecting {s}{forname}{inscope} at line {}{gottype}", self.line)
+        SYNTAX_ERR!(
+            self.line,
+            self.col,
+            "Expecting {s}{forname}{inscope} at line {}{gottype}",
+            self.line
+        )
     }
     pub fn expect_but<T>(&self, s: &str, got: &str) -> Result<T, MyError> {
         let forname = yesno!(self.name.is_empty(), "".s(), F!(" for {:?}", self.name));
         let inscope = yesno!(self.scope.is_empty(), "".s(), F!(" (in {})", self.scope));
-        ERR!("Expecting {s}{forname}{inscope} at line {}, got {got}", self.line)
+        SYNTAX_ERR!(
+            self.line,
+            self.col,
+            "Expecting {s}{forname}{inscope} at line {}, got {got}",
+            self.line
+        )
     }
     pub fn unexpect<T>(&self) -> Result<T, MyError> {
         let forname = yesno!(self.name.is_empty(), "".s(), F!(" for {:?}", self.name));
         let inscope = yesno!(self.scope.is_empty(), "".s(), F!(" (in {})", self.scope));
-        ERR!("Unexpected {:?}{forname}{inscope} at line {}", self.raw, self.line)
+        SYNTAX_ERR!(
+            self.line,
+            self.col,
+            "Unexpected {:?}{forname}{inscope} at line {}",
+            self.raw,
+            self.line
+        )
     }
 }
 
@@ -1,6 +1,6 @@
 use crate::*;
 
-#[derive(Dbg, Default)]
+#[derive(Dbg, Default, Clone)]
 pub struct MdxChunk {
     ///* read */
     pub id: u32,
@@ -13,21 +13,121 @@ pub struct MdxChunk {
 
 impl MdxChunk {
     pub fn read_mdx(cur: &mut Cursor<&Vec<u8>>) -> Result<Self, MyError> {
-        yes!(left = cur.left(), left < 4, EXIT1!("reading chunk id: {}B left (need 4)", left));
+        let truncated = |id, need, cur: &Cursor<&Vec<u8>>| MyError::TruncatedChunk {
+            id,
+            offset: cur.pos(),
+            need,
+            left: cur.left(),
+        };
+
+        yes!(cur.left() < 4, return Err(truncated(0, 4, cur)));
         let id = cur.read_be()?;
-        let estr = F!("reading chunk 0x{id:08X}({})", u32_to_ascii(id));
 
-        yes!(left = cur.left(), left < 4, EXIT1!("{} size: {}B left (need 4)", estr, left));
-        let size = cur.readx().or_else(|e| ERR!("{} size: {}", estr, e))?;
+        yes!(cur.left() < 4, return Err(truncated(id, 4, cur)));
+        let size = cur.readx()?;
         vlog!("chunk = 0x{:08X} ({}) [{}]", id, u32_to_ascii(id), size);
 
-        yes!(left = cur.left(), left < size, EXIT1!("{} body: {}B left (need {})", estr, left, size));
-        let body = cur.read_bytes(size).or_else(|e| ERR!("{} body({}B): {}", estr, size, e))?;
+        yes!(cur.left() < size, return Err(truncated(id, size, cur)));
+        let body = cur.read_bytes(size)?;
         vvvlog!("{}", hexdump(&body, "\t"));
 
         return Ok(MdxChunk { id, size, body, cursor: None });
     }
 
+    /// Salvage mode: skip junk until a known chunk id, and clamp a chunk size that doesn't fit.
+    pub fn read_mdx_salvage(
+        cur: &mut Cursor<&Vec<u8>>,
+        repairs: &mut Vec<String>,
+    ) -> Result<Option<Self>, MyError> {
+        let start = cur.pos();
+        let Some(pos) = Self::find_chunk(cur, start, false) else {
+            REPAIR!(repairs, "Dropped {}B of junk at offset {}", cur.left(), start);
+            cur.set_position(cur.len() as u64);
+            return Ok(None);
+        };
+        if pos > start {
+            REPAIR!(repairs, "Skipped {}B of junk at offset {}", pos - start, start);
+            cur.set_position(pos as u64);
+        }
+
+        let id = cur.read_be()?;
+        let mut size: u32 = cur.readx()?;
+        let (body_pos, end) = (cur.pos(), cur.pos() as u64 + size as u64);
+        if end > cur.len() as u64
+            || (end < cur.len() as u64 && !Self::is_chunk_at(cur, end as u32, true))
+        {
+            let next = Self::find_chunk(cur, body_pos, true).unwrap_or(cur.len());
+            if (next as u64) < end {
+                let ascii = u32_to_ascii(id);
+                REPAIR!(
+                    repairs,
+                    "Chunk {ascii} at offset {pos}: size {size} doesn't fit, clamped to {}",
+                    next - body_pos
+                );
+                size = next - body_pos;
+            }
+        }
+        vlog!("chunk = 0x{:08X} ({}) [{}]", id, u32_to_ascii(id), size);
+
+        let body = cur.read_bytes(size)?;
+        return Ok(Some(MdxChunk { id, size, body, cursor: None }));
+    }
+    /// strict: the chunk size must fit in the rest of the file too
+    fn find_chunk(cur: &Cursor<&Vec<u8>>, from: u32, strict: bool) -> Option<u32> {
+        (from..cur.len()).find(|&i| Self::is_chunk_at(cur, i, strict))
+    }
+    fn is_chunk_at(cur: &Cursor<&Vec<u8>>, i: u32, strict: bool) -> bool {
+        let data = cur.get_ref();
+        let i = i as usize;
+        yes!(i + 8 > data.len(), return false);
+        let id = u32::from_be_bytes(data[i..i + 4].try_into().unwrap());
+        let size = u32::from_le_bytes(data[i + 4..i + 8].try_into().unwrap());
+        let fit = (i + 8) as u64 + size as u64 <= data.len() as u64;
+        return MdlxMagic::CHUNKS.contains(&id) && (fit || !strict);
+    }
+
+    /// An unknown track of `owner`: `id` has just been read, see `track_len` for where it ends.
+    /// If that can't be told, the rest of `cur` is kept as is.
+    /// An `id` which doesn't look like a track tag means the object is misread.
+    pub fn read_track(cur: &mut Cursor<&Vec<u8>>, id: u32, owner: String) -> Result<Self, MyError> {
+        if !Self::is_track_tag(&id.to_be_bytes()) {
+            return Err(MyError::UnknownAnimationTag { owner, tag: id });
+        }
+        let len = Self::track_len(cur).unwrap_or(cur.left());
+        let body = cur.read_bytes(len)?;
+        vlog!("unknown track = 0x{:08X} ({}) [{}]", id, u32_to_ascii(id), body.len());
+        return Ok(Self::from_raw(id, body));
+    }
+    /// Size of the track body at `cur` (key count, interpolation type, global sequence id, keys).
+    /// The value type is unknown, so try 4, 12 and 16 bytes per value (int/float, Vec3, Vec4),
+    /// and take the first one after which the object ends or another track tag follows.
+    fn track_len(cur: &Cursor<&Vec<u8>>) -> Option<u32> {
+        let data = &cur.get_ref()[cur.pos() as usize..];
+        let word = |i: usize| data.get(i..i + 4).map(|b| u32::from_le_bytes(b.try_into().unwrap()));
+        let (nkeys, interp) = (word(0)? as u64, word(4)?);
+        let values = match interp {
+            0 | 1 => 1,
+            2 | 3 => 3, // value, itan, otan
+            _ => return None,
+        };
+        for value_size in [4, 12, 16] {
+            let len = 12 + nkeys * (4 + value_size * values);
+            yes!(len > data.len() as u64, continue);
+            let len = len as usize;
+            if len == data.len() || data.get(len..len + 4).is_some_and(Self::is_track_tag) {
+                return Some(len as u32);
+            }
+        }
+        return None;
+    }
+    /// Track tags look like `KGTR`.
+    fn is_track_tag(tag: &[u8]) -> bool {
+        tag[0] == b'K' && tag.iter().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+    }
+    pub fn from_raw(id: u32, body: Vec<u8>) -> Self {
+        MdxChunk { id, size: body.len() as u32, body, cursor: None }
+    }
+
     pub fn new(id: u32) -> Self {
         Build! { id:id, cursor: Some(Cursor::new(vec![])) }
     }
@@ -64,4 +164,233 @@ impl MdxChunk {
         }
         return Ok(());
     }
+
+    /// Write a read chunk (id + size + body) as is.
+    pub fn copy_to(&self, cur: &mut Cursor<Vec<u8>>) -> Result<(), MyError> {
+        cur.write_be(&self.id)?;
+        cur.writex(&self.size)?;
+        cur.write_all(&self.body)?;
+        return Ok(());
+    }
+
+    /// Unknown chunks/tracks in mdl: `{typ} "ABCD" { "hex", ... }`
+    pub fn read_mdl(block: &MdlBlock, _: &MdlReadOptions) -> Result<Self, MyError> {
+        Self::from_mdl(block)
+    }
+    pub fn from_mdl(block: &MdlBlock) -> Result<Self, MyError> {
+        Self::from_mdl_fields(block, block.fields.iter())
+    }
+    /// `fields`: the hex strings of the body.
+    fn from_mdl_fields<'a, I>(block: &MdlBlock, fields: I) -> Result<Self, MyError>
+    where
+        I: Iterator<Item = &'a MdlField>,
+    {
+        block.unexpect_frames()?;
+        block.unexpect_blocks()?;
+        let id = match Self::name_to_id(&block.name) {
+            Some(id) => id,
+            None => return block.unexpect(),
+        };
+        let mut body = vec![];
+        for f in fields {
+            no!(f.name.is_empty(), f.unexpect()?);
+            let hex: String = f.value.to()?;
+            match hex_to_bytes(&hex) {
+                Some(mut bytes) => body.append(&mut bytes),
+                None => f.value.expect("hex string")?,
+            }
+        }
+        return Ok(Self::from_raw(id, body));
+    }
+
+    pub fn write_mdl(
+        &self,
+        typ: &str,
+        depth: u8,
+        opts: &MdlWriteOptions,
+    ) -> Result<Vec<String>, MyError> {
+        let (indent, indent2) = (opts.indent(depth), opts.indent(depth + 1));
+        let mut lines: Vec<String> = vec![];
+        lines.push(F!("{indent}{typ} \"{}\" {{", Self::id_to_name(self.id)));
+        for a in self.body.chunks(32) {
+            lines.push(F!("{indent2}\"{}\",", bytes_to_hex(a)));
+        }
+        lines.push(F!("{indent}}}"));
+        return Ok(lines);
+    }
+
+    /// `After "ABCD"`: the id of the known chunk/track it follows, 0 if none.
+    fn from_mdl_after(block: &MdlBlock) -> Result<(u32, Self), MyError> {
+        let mut after = 0;
+        for f in block.fields.iter().filter(|f| !f.name.is_empty()) {
+            match_istr!(f.name.as_str(),
+                "After" => match Self::name_to_id(&f.value.to::<String>()?) {
+                    Some(id) => after = id,
+                    None => f.value.expect("chunk id")?,
+                },
+                _other => f.unexpect()?,
+            );
+        }
+        let hex = block.fields.iter().filter(|f| f.name.is_empty());
+        return Ok((after, Self::from_mdl_fields(block, hex)?));
+    }
+    fn write_mdl_after(
+        &self,
+        after: u32,
+        typ: &str,
+        depth: u8,
+        opts: &MdlWriteOptions,
+    ) -> Result<Vec<String>, MyError> {
+        let mut lines = self.write_mdl(typ, depth, opts)?;
+        if after != 0 {
+            let name = Self::id_to_name(after);
+            lines.insert(1, F!("{}After \"{name}\",", opts.indent(depth + 1)));
+        }
+        return Ok(lines);
+    }
+
+    fn id_to_name(id: u32) -> String {
+        let bytes = id.to_be_bytes();
+        match bytes.iter().all(|b| b.is_ascii_alphanumeric()) {
+            true => u32_to_ascii(id),
+            false => F!("0x{:08X}", id),
+        }
+    }
+    fn name_to_id(name: &str) -> Option<u32> {
+        if let Some(hex) = name.strip_prefix("0x") {
+            return u32::from_str_radix(hex, 16).ok();
+        }
+        let bytes: [u8; 4] = name.as_bytes().try_into().ok()?;
+        return Some(u32::from_be_bytes(bytes));
+    }
 }
+
+//#region UnknownChunk
+
+/// An unknown top level chunk, kept as is.
+#[derive(Dbg, Default, Clone)]
+pub struct UnknownChunk {
+    pub after: u32, // id of the known chunk it follows, 0 if none
+    pub chunk: MdxChunk,
+}
+
+impl UnknownChunk {
+    /// Write the chunks which followed `after`, in their original order.
+    pub fn write_after(
+        chunks: &[Self],
+        after: u32,
+        cur: &mut Cursor<Vec<u8>>,
+    ) -> Result<(), MyError> {
+        for a in chunks.iter().filter(|a| a.after == after) {
+            a.chunk.copy_to(cur)?;
+        }
+        return Ok(());
+    }
+
+    /// In mdl: `UnknownChunk "ABCD" { After "SEQS", "hex", ... }`
+    pub fn read_mdl(block: &MdlBlock, _: &MdlReadOptions) -> Result<Self, MyError> {
+        let (after, chunk) = MdxChunk::from_mdl_after(block)?;
+        return Ok(Self { after, chunk });
+    }
+
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        MdxChunk::write_mdl_after(&self.chunk, self.after, "UnknownChunk", depth, opts)
+    }
+}
+
+//#endregion
+//#region UnknownTrack
+
+/// An unknown track of an object, kept as is.
+#[derive(Dbg, Default, Clone)]
+pub struct UnknownTrack {
+    pub after: u32, // id of the known track it follows, 0 if none
+    pub track: MdxChunk,
+}
+
+impl UnknownTrack {
+    /// See [`MdxChunk::read_track`].
+    pub fn read_mdx(
+        cur: &mut Cursor<&Vec<u8>>,
+        id: u32,
+        after: u32,
+        owner: String,
+    ) -> Result<Self, MyError> {
+        Ok(Self { after, track: MdxChunk::read_track(cur, id, owner)? })
+    }
+
+    /// In mdl: `UnknownTrack "KABC" { After "KGTR", "hex", ... }`
+    pub fn from_mdl(block: &MdlBlock) -> Result<Self, MyError> {
+        let (after, track) = MdxChunk::from_mdl_after(block)?;
+        return Ok(Self { after, track });
+    }
+
+    pub fn write_mdl(
+        &self,
+        typ: &str,
+        depth: u8,
+        opts: &MdlWriteOptions,
+    ) -> Result<Vec<String>, MyError> {
+        MdxChunk::write_mdl_after(&self.track, self.after, typ, depth, opts)
+    }
+}
+
+//#endregion
+//#region trait: ExtendUnknownTracks
+
+pub trait _ExtendUnknownTracks {
+    fn calc_mdx_size(&self) -> u32;
+    fn write_after(&self, after: u32, chunk: &mut MdxChunk) -> Result<(), MyError>;
+    fn write_mdl(
+        &self,
+        typ: &str,
+        depth: u8,
+        opts: &MdlWriteOptions,
+    ) -> Result<Vec<String>, MyError>;
+}
+
+impl _ExtendUnknownTracks for Vec<UnknownTrack> {
+    fn calc_mdx_size(&self) -> u32 {
+        self.iter().map(|a| 4 + a.track.body.len() as u32).sum() // id + body
+    }
+    /// Write the tracks which followed `after`, in their original order.
+    fn write_after(&self, after: u32, chunk: &mut MdxChunk) -> Result<(), MyError> {
+        for a in self.iter().filter(|a| a.after == after) {
+            chunk.write_be(&a.track.id)?;
+            chunk.write(&a.track.body)?;
+        }
+        return Ok(());
+    }
+    fn write_mdl(
+        &self,
+        typ: &str,
+        depth: u8,
+        opts: &MdlWriteOptions,
+    ) -> Result<Vec<String>, MyError> {
+        let mut lines: Vec<String> = vec![];
+        for a in self {
+            lines.append(&mut a.write_mdl(typ, depth, opts)?);
+        }
+        return Ok(lines);
+    }
+}
+
+//#endregion
+//#region hex
+
+fn bytes_to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| F!("{:02x}", b)).collect()
+}
+fn hex_to_bytes(s: &str) -> Option<Vec<u8>> {
+    let s: Vec<char> = s.chars().filter(|c| !c.is_whitespace()).collect();
+    yes!(!s.len().is_multiple_of(2), return None);
+    let mut bytes = Vec::with_capacity(s.len() / 2);
+    for pair in s.chunks(2) {
+        let hi = pair[0].to_digit(16)?;
+        let lo = pair[1].to_digit(16)?;
+        bytes.push((hi * 16 + lo) as u8);
+    }
+    return Some(bytes);
+}
+
+//#endregion
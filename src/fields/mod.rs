@@ -1,9 +1,11 @@
 pub mod animation;
 pub mod attachment;
+pub mod bind_pose;
 pub mod bone;
 pub mod camera;
 pub mod collision;
 pub mod eventobj;
+pub mod face_effect;
 pub mod geoset;
 pub mod globalseq;
 pub mod helper;
@@ -17,6 +19,7 @@ pub mod node;
 pub mod particle_emitter;
 pub mod particle_emitter2;
 pub mod pivot;
+pub mod popcorn_emitter;
 pub mod ribbon_emitter;
 pub mod sequence;
 pub mod texture;
@@ -24,10 +27,12 @@ pub mod version;
 
 pub use animation::*;
 pub use attachment::*;
+pub use bind_pose::*;
 pub use bone::*;
 pub use camera::*;
 pub use collision::*;
 pub use eventobj::*;
+pub use face_effect::*;
 pub use geoset::*;
 pub use globalseq::*;
 pub use helper::*;
@@ -41,6 +46,7 @@ pub use node::*;
 pub use particle_emitter::*;
 pub use particle_emitter2::*;
 pub use pivot::*;
+pub use popcorn_emitter::*;
 pub use ribbon_emitter::*;
 pub use sequence::*;
 pub use texture::*;
@@ -12,7 +12,7 @@ pub struct Model {
 
 impl Model {
     pub const ID: u32 = MdlxMagic::MODL;
-    const NAME_SIZE: u32 = 336;
+    pub const NAME_SIZE: u32 = 336;
 
     pub fn read_mdx(cur: &mut Cursor<&Vec<u8>>) -> Result<Self, MyError> {
         Ok(Self {
@@ -31,10 +31,11 @@ impl Model {
         return Ok(());
     }
 
-    pub fn read_mdl(block: &MdlBlock) -> Result<Self, MyError> {
+    pub fn read_mdl(block: &MdlBlock, _: &MdlReadOptions) -> Result<Self, MyError> {
         block.unexpect_frames()?;
         block.unexpect_blocks()?;
-        let mut this = Build! { name: block.name.clone(), extent: BoundExtent::read_mdl(&block, false)? };
+        let mut this =
+            Build! { name: block.name.clone(), extent: BoundExtent::read_mdl(&block, false)? };
         for f in &block.fields {
             match_istr!(f.name.as_str(),
                 "BoundsRadius" | "MinimumExtent" | "MaximumExtent" => (),
@@ -49,11 +50,11 @@ impl Model {
         return Ok(this);
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        let indent = indent!(depth + 1);
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        let indent = opts.indent(depth + 1);
         let mut lines: Vec<String> = vec![];
         lines.push(F!("Model \"{}\" {{", self.name.escape()));
-        lines.append(&mut self.extent.write_mdl(depth + 1)?);
+        lines.append(&mut self.extent.write_mdl(depth + 1, opts)?);
         lines.push(F!("{indent}BlendTime {},", self.blend_time));
         lines.push(F!("}}"));
         return Ok(lines);
@@ -15,6 +15,7 @@ pub struct Node {
     pub rotation: Option<Animation<Vec4>>,
     #[dbg(formatter = "fmtxx")]
     pub scaling: Option<Animation<Vec3>>,
+    pub unknown_tracks: Vec<UnknownTrack>,
 
     mdl_fields: HashSet<String>,
     mdl_blocks: HashSet<String>,
@@ -25,7 +26,7 @@ pub struct Node {
 }
 
 impl Node {
-    const NAME_SIZE: u32 = 80;
+    pub const NAME_SIZE: u32 = 80;
     const ID_T: u32 = MdlxMagic::KGTR; /* Translation */
     const ID_R: u32 = MdlxMagic::KGRT; /* Rotation */
     const ID_S: u32 = MdlxMagic::KGSC; /* Scaling */
@@ -43,13 +44,20 @@ impl Node {
         this.parent_id = cur.readx()?;
         this.flags = NodeFlags::from_bits_retain(cur.readx()?);
 
+        let mut after = 0; // the last known track, for unknown tracks
         while cur.left() >= 16 {
-            match cur.read_be()? {
+            let id = cur.read_be()?;
+            match id {
                 Self::ID_T => this.translation = Some(Animation::read_mdx(&mut cur)?),
                 Self::ID_R => this.rotation = Some(Animation::read_mdx(&mut cur)?),
                 Self::ID_S => this.scaling = Some(Animation::read_mdx(&mut cur)?),
-                id => return ERR!("Unknown animation in {}: {} (0x{:08X})", TNAME!(), u32_to_ascii(id), id),
+                _ => {
+                    let a = UnknownTrack::read_mdx(&mut cur, id, after, TNAME!())?;
+                    this.unknown_tracks.push(a);
+                    continue;
+                },
             }
+            after = id;
         }
 
         return Ok(this);
@@ -61,7 +69,7 @@ impl Node {
         chunk.write(&self.object_id)?;
         chunk.write(&self.parent_id)?;
         chunk.write(&self.flags.bits())?;
-        MdxWriteAnim!(chunk,
+        MdxWriteAnim!(chunk, self.unknown_tracks;
             Self::ID_T => self.translation,
             Self::ID_R => self.rotation,
             Self::ID_S => self.scaling,
@@ -73,6 +81,7 @@ impl Node {
         sz += self.translation.calc_mdx_size();
         sz += self.rotation.calc_mdx_size();
         sz += self.scaling.calc_mdx_size();
+        sz += self.unknown_tracks.calc_mdx_size();
         return sz;
     }
 
@@ -114,6 +123,7 @@ impl Node {
                 "Translation" => this.translation = Some(Animation::read_mdl(f)?),
                 "Rotation" => this.rotation = Some(Animation::read_mdl(f)?),
                 "Scaling" => this.scaling = Some(Animation::read_mdl(f)?),
+                "UnknownNodeTrack" => this.unknown_tracks.push(UnknownTrack::from_mdl(f)?),
                 _other => hit = false,
             );
             if hit {
@@ -138,32 +148,58 @@ impl Node {
         std::mem::replace(&mut self.mdl_unexpected_block, Ok(()))
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        let indent = indent!(depth);
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        let indent = opts.indent(depth);
         let mut lines: Vec<String> = vec![];
 
         lines.push(F!("{indent}ObjectId {},", self.object_id));
         lines.push_if_nneg1(&F!("{indent}Parent"), &self.parent_id);
 
-        lines.push_if(self.flags.contains(NodeFlags::DontInheritT), F!("{indent}DontInherit {{ Translation }},"));
-        lines.push_if(self.flags.contains(NodeFlags::DontInheritR), F!("{indent}DontInherit {{ Rotation }},"));
-        lines.push_if(self.flags.contains(NodeFlags::DontInheritS), F!("{indent}DontInherit {{ Scaling }},"));
+        lines.push_if(
+            self.flags.contains(NodeFlags::DontInheritT),
+            F!("{indent}DontInherit {{ Translation }},"),
+        );
+        lines.push_if(
+            self.flags.contains(NodeFlags::DontInheritR),
+            F!("{indent}DontInherit {{ Rotation }},"),
+        );
+        lines.push_if(
+            self.flags.contains(NodeFlags::DontInheritS),
+            F!("{indent}DontInherit {{ Scaling }},"),
+        );
         lines.push_if(self.flags.contains(NodeFlags::Billboarded), F!("{indent}Billboarded,"));
-        lines.push_if(self.flags.contains(NodeFlags::BillboardedLockX), F!("{indent}BillboardedLockX,"));
-        lines.push_if(self.flags.contains(NodeFlags::BillboardedLockY), F!("{indent}BillboardedLockY,"));
-        lines.push_if(self.flags.contains(NodeFlags::BillboardedLockZ), F!("{indent}BillboardedLockZ,"));
-        lines.push_if(self.flags.contains(NodeFlags::CameraAnchored), F!("{indent}CameraAnchored,"));
+        lines.push_if(
+            self.flags.contains(NodeFlags::BillboardedLockX),
+            F!("{indent}BillboardedLockX,"),
+        );
+        lines.push_if(
+            self.flags.contains(NodeFlags::BillboardedLockY),
+            F!("{indent}BillboardedLockY,"),
+        );
+        lines.push_if(
+            self.flags.contains(NodeFlags::BillboardedLockZ),
+            F!("{indent}BillboardedLockZ,"),
+        );
+        lines
+            .push_if(self.flags.contains(NodeFlags::CameraAnchored), F!("{indent}CameraAnchored,"));
 
-        MdlWriteAnimIfSome!(lines, depth,
+        MdlWriteAnimIfSome!(lines, depth, opts,
             "Translation"   => self.translation,
             "Rotation"      => self.rotation,
             "Scaling"       => self.scaling,
         );
+        lines.append(&mut self.unknown_tracks.write_mdl("UnknownNodeTrack", depth, opts)?);
 
         return Ok(lines);
     }
 }
 
+impl_VisitAnims!(Node,
+    "Translation" => translation,
+    "Rotation"    => rotation,
+    "Scaling"     => scaling,
+);
+
 //#region NodeFlags
 
 bitflags! {
@@ -28,6 +28,7 @@ pub struct ParticleEmitter {
     pub speed_anim: Option<Animation<f32>>,
     #[dbg(formatter = "fmtxx")]
     pub visibility: Option<Animation<f32>>,
+    pub unknown_tracks: Vec<UnknownTrack>,
 }
 
 impl ParticleEmitter {
@@ -39,7 +40,7 @@ impl ParticleEmitter {
     const ID_LS: u32 = MdlxMagic::KPEL; /* Life span */
     const ID_SPD: u32 = MdlxMagic::KPES; /* Speed */
     const ID_V: u32 = MdlxMagic::KPEV; /* Visibility */
-    const PATH_SIZE: u32 = 256;
+    pub const PATH_SIZE: u32 = 256;
 
     pub fn read_mdx(cur: &mut Cursor<&Vec<u8>>) -> Result<Self, MyError> {
         let mut this = Build! { base: Node::read_mdx(cur)? };
@@ -53,8 +54,10 @@ impl ParticleEmitter {
         this.lifespan = cur.readx()?;
         this.speed = cur.readx()?;
 
+        let mut after = 0; // the last known track, for unknown tracks
         while cur.left() >= 16 {
-            match cur.read_be()? {
+            let id = cur.read_be()?;
+            match id {
                 Self::ID_ER => this.emit_rate_anim = Some(Animation::read_mdx(cur)?),
                 Self::ID_G => this.gravity_anim = Some(Animation::read_mdx(cur)?),
                 Self::ID_LO => this.longitude_anim = Some(Animation::read_mdx(cur)?),
@@ -62,8 +65,12 @@ impl ParticleEmitter {
                 Self::ID_LS => this.lifespan_anim = Some(Animation::read_mdx(cur)?),
                 Self::ID_SPD => this.speed_anim = Some(Animation::read_mdx(cur)?),
                 Self::ID_V => this.visibility = Some(Animation::read_mdx(cur)?),
-                id => return ERR!("Unknown animation in {}: {} (0x{:08X})", TNAME!(), u32_to_ascii(id), id),
+                _ => {
+                    this.unknown_tracks.push(UnknownTrack::read_mdx(cur, id, after, TNAME!())?);
+                    continue;
+                },
             }
+            after = id;
         }
 
         return Ok(this);
@@ -82,7 +89,7 @@ impl ParticleEmitter {
         chunk.write(&self.lifespan)?;
         chunk.write(&self.speed)?;
 
-        MdxWriteAnim!(chunk,
+        MdxWriteAnim!(chunk, self.unknown_tracks;
             Self::ID_ER => self.emit_rate_anim,
             Self::ID_G  => self.gravity_anim,
             Self::ID_LO => self.longitude_anim,
@@ -103,10 +110,11 @@ impl ParticleEmitter {
         sz += self.lifespan_anim.calc_mdx_size();
         sz += self.speed_anim.calc_mdx_size();
         sz += self.visibility.calc_mdx_size();
+        sz += self.unknown_tracks.calc_mdx_size();
         return sz;
     }
 
-    pub fn read_mdl(block: &MdlBlock) -> Result<Self, MyError> {
+    pub fn read_mdl(block: &MdlBlock, _: &MdlReadOptions) -> Result<Self, MyError> {
         let mut this = Build! { base: Node::read_mdl(block)? };
         this.base.flags.insert(NodeFlags::ParticleEmitter);
         for f in &block.fields {
@@ -128,6 +136,7 @@ impl ParticleEmitter {
                 "Longitude" => this.longitude_anim = Some(Animation::read_mdl(f)?),
                 "Latitude" => this.latitude_anim = Some(Animation::read_mdl(f)?),
                 "Visibility" => this.visibility = Some(Animation::read_mdl(f)?),
+                "UnknownTrack" => this.unknown_tracks.push(UnknownTrack::from_mdl(f)?),
                 _other => this.base.unexpect_mdl_block(f)?,
             );
         }
@@ -153,36 +162,53 @@ impl ParticleEmitter {
         return Ok(());
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        let (indent, indent2) = (indent!(depth), indent!(depth + 1));
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        let (indent, indent2) = (opts.indent(depth), opts.indent(depth + 1));
         let mut lines: Vec<String> = vec![];
 
-        lines.append(&mut self.base.write_mdl(depth)?);
-        lines.push_if(self.base.flags.contains(NodeFlags::PE1UsesMdl), F!("{indent}EmitterUsesMDL,"));
-        lines.push_if(self.base.flags.contains(NodeFlags::PE1UsesTga), F!("{indent}EmitterUsesTGA,"));
+        lines.append(&mut self.base.write_mdl(depth, opts)?);
+        lines.push_if(
+            self.base.flags.contains(NodeFlags::PE1UsesMdl),
+            F!("{indent}EmitterUsesMDL,"),
+        );
+        lines.push_if(
+            self.base.flags.contains(NodeFlags::PE1UsesTga),
+            F!("{indent}EmitterUsesTGA,"),
+        );
 
-        MdlWriteAnimBoth!(lines, depth,
+        MdlWriteAnimBoth!(lines, depth, opts,
             "EmissionRate" => self.emit_rate_anim => 0.0 => self.emit_rate,
             "Gravity" => self.gravity_anim => 0.0 => self.gravity,
             "Longitude" => self.longitude_anim => 0.0 => self.longitude,
             "Latitude" => self.latitude_anim => 0.0 => self.latitude,
         );
-        MdlWriteAnimIfSome!(lines, depth, "Visibility" => self.visibility);
+        MdlWriteAnimIfSome!(lines, depth, opts, "Visibility" => self.visibility);
 
         {
             let mut tlines: Vec<String> = vec![];
-            MdlWriteAnimBoth!(tlines, depth + 1,
+            MdlWriteAnimBoth!(tlines, depth + 1, opts,
                 "LifeSpan" => self.lifespan_anim => 0.0 => self.lifespan,
                 "InitVelocity" => self.speed_anim => 0.0 => self.speed,
             );
-            tlines.pushx_if_n0(&F!("{indent2}Path"), &self.path.escape_path());
+            tlines.pushx_if_n0(&F!("{indent2}Path"), &self.path.escape_path(), opts);
             if !tlines.is_empty() {
                 lines.push(F!("{indent}Particle {{"));
                 lines.append(&mut tlines);
                 lines.push(F!("{indent}}}"));
             }
         }
+        lines.append(&mut self.unknown_tracks.write_mdl("UnknownTrack", depth, opts)?);
 
         return Ok(lines);
     }
 }
+
+impl_VisitAnims!(ParticleEmitter: base,
+    "EmissionRate" => emit_rate_anim,
+    "Gravity"      => gravity_anim,
+    "Longitude"    => longitude_anim,
+    "Latitude"     => latitude_anim,
+    "LifeSpan"     => lifespan_anim,
+    "InitVelocity" => speed_anim,
+    "Visibility"   => visibility,
+);
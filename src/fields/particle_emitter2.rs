@@ -57,6 +57,7 @@ pub struct ParticleEmitter2 {
     pub width_anim: Option<Animation<f32>>,
     #[dbg(formatter = "fmtxx")]
     pub visibility: Option<Animation<f32>>,
+    pub unknown_tracks: Vec<UnknownTrack>,
 }
 
 impl ParticleEmitter2 {
@@ -101,17 +102,21 @@ impl ParticleEmitter2 {
         this.segment_scaling = cur.read_array(3)?;
 
         this.head_life = PE2UVAnim { start: cur.readx()?, end: cur.readx()?, repeat: cur.readx()? };
-        this.head_decay = PE2UVAnim { start: cur.readx()?, end: cur.readx()?, repeat: cur.readx()? };
+        this.head_decay =
+            PE2UVAnim { start: cur.readx()?, end: cur.readx()?, repeat: cur.readx()? };
         this.tail_life = PE2UVAnim { start: cur.readx()?, end: cur.readx()?, repeat: cur.readx()? };
-        this.tail_decay = PE2UVAnim { start: cur.readx()?, end: cur.readx()?, repeat: cur.readx()? };
+        this.tail_decay =
+            PE2UVAnim { start: cur.readx()?, end: cur.readx()?, repeat: cur.readx()? };
 
         this.texture_id = cur.readx()?;
         this.squirt = 0i32 != cur.readx()?;
         this.priority_plane = cur.readx()?;
         this.replace_id = cur.readx()?;
 
+        let mut after = 0; // the last known track, for unknown tracks
         while cur.left() >= 16 {
-            match cur.read_be()? {
+            let id = cur.read_be()?;
+            match id {
                 Self::ID_SPD => this.speed_anim = Some(Animation::read_mdx(cur)?),
                 Self::ID_VA => this.variation_anim = Some(Animation::read_mdx(cur)?),
                 Self::ID_LA => this.latitude_anim = Some(Animation::read_mdx(cur)?),
@@ -120,8 +125,12 @@ impl ParticleEmitter2 {
                 Self::ID_L => this.length_anim = Some(Animation::read_mdx(cur)?),
                 Self::ID_W => this.width_anim = Some(Animation::read_mdx(cur)?),
                 Self::ID_V => this.visibility = Some(Animation::read_mdx(cur)?),
-                id => return ERR!("Unknown animation in {}: {} (0x{:08X})", TNAME!(), u32_to_ascii(id), id),
+                _ => {
+                    this.unknown_tracks.push(UnknownTrack::read_mdx(cur, id, after, TNAME!())?);
+                    continue;
+                },
             }
+            after = id;
         }
 
         return Ok(this);
@@ -162,7 +171,7 @@ impl ParticleEmitter2 {
         chunk.write(&self.priority_plane)?;
         chunk.write(&self.replace_id)?;
 
-        MdxWriteAnim!(chunk,
+        MdxWriteAnim!(chunk, self.unknown_tracks;
             Self::ID_SPD=> self.speed_anim,
             Self::ID_VA => self.variation_anim,
             Self::ID_LA => self.latitude_anim,
@@ -189,10 +198,11 @@ impl ParticleEmitter2 {
         sz += self.variation_anim.calc_mdx_size();
         sz += self.gravity_anim.calc_mdx_size();
         sz += self.visibility.calc_mdx_size();
+        sz += self.unknown_tracks.calc_mdx_size();
         return sz;
     }
 
-    pub fn read_mdl(block: &MdlBlock) -> Result<Self, MyError> {
+    pub fn read_mdl(block: &MdlBlock, _: &MdlReadOptions) -> Result<Self, MyError> {
         let mut this = Build! { base: Node::read_mdl(block)? };
         this.base.flags.insert(NodeFlags::ParticleEmitter);
 
@@ -251,25 +261,30 @@ impl ParticleEmitter2 {
                 "Length" => this.length_anim = Some(Animation::read_mdl(f)?),
                 "Width" => this.width_anim = Some(Animation::read_mdl(f)?),
                 "Visibility" => this.visibility = Some(Animation::read_mdl(f)?),
+                "UnknownTrack" => this.unknown_tracks.push(UnknownTrack::from_mdl(f)?),
                 _other => this.base.unexpect_mdl_block(f)?,
             );
         }
 
-        this.head_or_tail = yesno!(head && tail, HeadOrTail::Both, yesno!(tail, HeadOrTail::Tail, HeadOrTail::Head));
+        this.head_or_tail = yesno!(
+            head && tail,
+            HeadOrTail::Both,
+            yesno!(tail, HeadOrTail::Tail, HeadOrTail::Head)
+        );
         return Ok(this);
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        let (indent, indent2) = (indent!(depth), indent!(depth + 1));
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        let (indent, indent2) = (opts.indent(depth), opts.indent(depth + 1));
         let mut lines: Vec<String> = vec![];
 
-        lines.append(&mut self.base.write_mdl(depth)?);
+        lines.append(&mut self.base.write_mdl(depth, opts)?);
 
         lines.push(F!("{indent}{:?},", &self.filter_mode));
         {
             let mut clines: Vec<String> = vec![];
             for c in self.segment_color.iter() {
-                clines.pushx(&F!("{indent2}Color"), c);
+                clines.pushx(&F!("{indent2}Color"), c, opts);
             }
             if !clines.is_empty() {
                 lines.push(F!("{indent}SegmentColor {{"));
@@ -278,22 +293,22 @@ impl ParticleEmitter2 {
             }
         }
         {
-            lines.pushx(&F!("{indent}Alpha"), &self.segment_alpha);
-            lines.pushx(&F!("{indent}ParticleScaling"), &self.segment_scaling);
-            lines.pushx_if_n0(&F!("{indent}LifeSpanUVAnim"), &self.head_life);
-            lines.pushx_if_n0(&F!("{indent}DecayUVAnim"), &self.head_decay);
-            lines.pushx_if_n0(&F!("{indent}TailUVAnim"), &self.tail_life);
-            lines.pushx_if_n0(&F!("{indent}TailDecayUVAnim"), &self.tail_decay);
+            lines.pushx(&F!("{indent}Alpha"), &self.segment_alpha, opts);
+            lines.pushx(&F!("{indent}ParticleScaling"), &self.segment_scaling, opts);
+            lines.pushx_if_n0(&F!("{indent}LifeSpanUVAnim"), &self.head_life, opts);
+            lines.pushx_if_n0(&F!("{indent}DecayUVAnim"), &self.head_decay, opts);
+            lines.pushx_if_n0(&F!("{indent}TailUVAnim"), &self.tail_life, opts);
+            lines.pushx_if_n0(&F!("{indent}TailDecayUVAnim"), &self.tail_decay, opts);
         }
         {
-            lines.pushx_if_n0(&F!("{indent}Rows"), &self.rows);
-            lines.pushx_if_n0(&F!("{indent}Columns"), &self.columns);
-            lines.pushx_if_n0(&F!("{indent}Time"), &self.time);
-            lines.pushx_if_n0(&F!("{indent}LifeSpan"), &self.lifespan);
-            lines.pushx_if_n0(&F!("{indent}TailLength"), &self.tail_length);
-            lines.pushx_if_nneg1(&F!("{indent}TextureID"), &self.texture_id);
-            lines.pushx_if_n0(&F!("{indent}ReplaceableId"), &self.replace_id);
-            lines.pushx_if_n0(&F!("{indent}PriorityPlane"), &self.priority_plane);
+            lines.pushx_if_n0(&F!("{indent}Rows"), &self.rows, opts);
+            lines.pushx_if_n0(&F!("{indent}Columns"), &self.columns, opts);
+            lines.pushx_if_n0(&F!("{indent}Time"), &self.time, opts);
+            lines.pushx_if_n0(&F!("{indent}LifeSpan"), &self.lifespan, opts);
+            lines.pushx_if_n0(&F!("{indent}TailLength"), &self.tail_length, opts);
+            lines.pushx_if_nneg1(&F!("{indent}TextureID"), &self.texture_id, opts);
+            lines.pushx_if_n0(&F!("{indent}ReplaceableId"), &self.replace_id, opts);
+            lines.pushx_if_n0(&F!("{indent}PriorityPlane"), &self.priority_plane, opts);
         }
         {
             let flags = self.base.flags;
@@ -307,7 +322,7 @@ impl ParticleEmitter2 {
             lines.push_if(self.head_or_tail.is_valid(), F!("{indent}{:?},", self.head_or_tail));
         }
 
-        MdlWriteAnimBoth!(lines, depth,
+        MdlWriteAnimBoth!(lines, depth, opts,
             "Speed" => self.speed_anim => 0.0 => self.speed,
             "Variation" => self.variation_anim => 0.0 => self.variation,
             "Latitude" => self.latitude_anim => 0.0 => self.latitude,
@@ -316,12 +331,24 @@ impl ParticleEmitter2 {
             "Length" => self.length_anim => 0.0 => self.length,
             "Width" => self.width_anim => 0.0 => self.width,
         );
-        MdlWriteAnimIfSome!(lines, depth, "Visibility" => self.visibility);
+        MdlWriteAnimIfSome!(lines, depth, opts, "Visibility" => self.visibility);
+        lines.append(&mut self.unknown_tracks.write_mdl("UnknownTrack", depth, opts)?);
 
         return Ok(lines);
     }
 }
 
+impl_VisitAnims!(ParticleEmitter2: base,
+    "Speed"        => speed_anim,
+    "Variation"    => variation_anim,
+    "Latitude"     => latitude_anim,
+    "Gravity"      => gravity_anim,
+    "EmissionRate" => emit_rate_anim,
+    "Length"       => length_anim,
+    "Width"        => width_anim,
+    "Visibility"   => visibility,
+);
+
 //#region PE2UVAnim
 
 #[derive(Default)]
@@ -367,7 +394,7 @@ impl CheckValue for PE2UVAnim {
 }
 
 impl Formatter for PE2UVAnim {
-    fn fmt(&self) -> String {
+    fn fmt_with(&self, _: &MdlWriteOptions) -> String {
         F!("{{ {}, {}, {} }}", self.start, self.end, self.repeat)
     }
 }
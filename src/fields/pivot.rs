@@ -17,18 +17,18 @@ impl PivotPoint {
         chunk.write(&self.position)
     }
 
-    pub fn read_mdl(f: &MdlField) -> Result<Self, MyError> {
+    pub fn read_mdl(f: &MdlField, _: &MdlReadOptions) -> Result<Self, MyError> {
         yesno!(f.name.is_empty(), Ok(Self { position: f.value.to()? }), f.unexpect())
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        Ok(vec![F!("{}{},", indent!(depth), fmtx(&self.position))])
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        Ok(vec![F!("{}{},", opts.indent(depth), fmtw(&self.position, opts))])
     }
 }
 
 impl Formatter for Vec<PivotPoint> {
-    fn fmt(&self) -> String {
-        let s = self.iter().map(|x| fmtx(&x.position)).collect::<Vec<_>>().join(", ");
+    fn fmt_with(&self, opts: &MdlWriteOptions) -> String {
+        let s = self.iter().map(|x| fmtw(&x.position, opts)).collect::<Vec<_>>().join(", ");
         return F!("[{}]", s);
     }
 }
@@ -0,0 +1,192 @@
+use crate::*;
+
+#[derive(Dbg, SmartDefault)]
+pub struct PopcornEmitter {
+    pub base: Node,
+
+    pub lifespan: f32,
+    pub emit_rate: f32,
+    pub speed: f32,
+    #[dbg(formatter = "fmtx")]
+    #[default(Vec3::ONE)]
+    pub color: Vec3,
+    #[default(1.0)]
+    pub alpha: f32,
+    pub replaceable_id: i32,
+    pub path: String,
+    pub anim_visibility_guide: String, // e.g. "Stand=on, Walk=off"
+
+    #[dbg(formatter = "fmtxx")]
+    pub lifespan_anim: Option<Animation<f32>>,
+    #[dbg(formatter = "fmtxx")]
+    pub emit_rate_anim: Option<Animation<f32>>,
+    #[dbg(formatter = "fmtxx")]
+    pub speed_anim: Option<Animation<f32>>,
+    #[dbg(formatter = "fmtxx")]
+    pub color_anim: Option<Animation<Vec3>>,
+    #[dbg(formatter = "fmtxx")]
+    pub alpha_anim: Option<Animation<f32>>,
+    #[dbg(formatter = "fmtxx")]
+    pub visibility: Option<Animation<f32>>,
+    pub unknown_tracks: Vec<UnknownTrack>,
+}
+
+impl PopcornEmitter {
+    pub const ID: u32 = MdlxMagic::CORN;
+    const ID_LS: u32 = MdlxMagic::KPPL; /* Life span */
+    const ID_ER: u32 = MdlxMagic::KPPE; /* Emission rate */
+    const ID_SPD: u32 = MdlxMagic::KPPS; /* Speed */
+    const ID_C: u32 = MdlxMagic::KPPC; /* Color */
+    const ID_A: u32 = MdlxMagic::KPPA; /* Alpha */
+    const ID_V: u32 = MdlxMagic::KPPV; /* Visibility */
+    pub const PATH_SIZE: u32 = 260;
+
+    pub fn read_mdx(cur: &mut Cursor<&Vec<u8>>) -> Result<Self, MyError> {
+        let mut this = Build! { base: Node::read_mdx(cur)? };
+
+        this.lifespan = cur.readx()?;
+        this.emit_rate = cur.readx()?;
+        this.speed = cur.readx()?;
+        this.color = cur.readx()?;
+        this.alpha = cur.readx()?;
+        this.replaceable_id = cur.readx()?;
+        this.path = cur.read_string(Self::PATH_SIZE)?;
+        this.anim_visibility_guide = cur.read_string(Self::PATH_SIZE)?;
+
+        let mut after = 0; // the last known track, for unknown tracks
+        while cur.left() >= 16 {
+            let id = cur.read_be()?;
+            match id {
+                Self::ID_LS => this.lifespan_anim = Some(Animation::read_mdx(cur)?),
+                Self::ID_ER => this.emit_rate_anim = Some(Animation::read_mdx(cur)?),
+                Self::ID_SPD => this.speed_anim = Some(Animation::read_mdx(cur)?),
+                Self::ID_C => this.color_anim = Some(Animation::read_mdx(cur)?),
+                Self::ID_A => this.alpha_anim = Some(Animation::read_mdx(cur)?),
+                Self::ID_V => this.visibility = Some(Animation::read_mdx(cur)?),
+                _ => {
+                    this.unknown_tracks.push(UnknownTrack::read_mdx(cur, id, after, TNAME!())?);
+                    continue;
+                },
+            }
+            after = id;
+        }
+
+        return Ok(this);
+    }
+
+    pub fn write_mdx(&self, chunk: &mut MdxChunk) -> Result<(), MyError> {
+        chunk.write(&self.calc_mdx_size())?;
+        self.base.write_mdx(chunk)?;
+
+        chunk.write(&self.lifespan)?;
+        chunk.write(&self.emit_rate)?;
+        chunk.write(&self.speed)?;
+        chunk.write(&self.color)?;
+        chunk.write(&self.alpha)?;
+        chunk.write(&self.replaceable_id)?;
+        chunk.write_string(&self.path, Self::PATH_SIZE)?;
+        chunk.write_string(&self.anim_visibility_guide, Self::PATH_SIZE)?;
+
+        MdxWriteAnim!(chunk, self.unknown_tracks;
+            Self::ID_LS => self.lifespan_anim,
+            Self::ID_ER => self.emit_rate_anim,
+            Self::ID_SPD=> self.speed_anim,
+            Self::ID_C  => self.color_anim,
+            Self::ID_A  => self.alpha_anim,
+            Self::ID_V  => self.visibility,
+        );
+        return Ok(());
+    }
+    pub fn calc_mdx_size(&self) -> u32 {
+        let mut sz: u32 = 36 + Self::PATH_SIZE * 2; // sz + ls + er + spd + c + a + repl_id + path + guide
+        sz += self.base.calc_mdx_size();
+        sz += self.lifespan_anim.calc_mdx_size();
+        sz += self.emit_rate_anim.calc_mdx_size();
+        sz += self.speed_anim.calc_mdx_size();
+        sz += self.color_anim.calc_mdx_size();
+        sz += self.alpha_anim.calc_mdx_size();
+        sz += self.visibility.calc_mdx_size();
+        sz += self.unknown_tracks.calc_mdx_size();
+        return sz;
+    }
+
+    pub fn read_mdl(block: &MdlBlock, opts: &MdlReadOptions) -> Result<Self, MyError> {
+        let mut this = Build! { base: Node::read_mdl(block)? };
+        for f in &block.fields {
+            match_istr!(f.name.as_str(),
+                "Unshaded" => this.base.flags |= f.expect_flag(NodeFlags::PE2Unshaded)?,
+                "SortPrimsFarZ" => this.base.flags |= f.expect_flag(NodeFlags::PE2SortPrimFarZ)?,
+                "Unfogged" => this.base.flags |= f.expect_flag(NodeFlags::Unfogged)?,
+                "LifeSpan" => this.lifespan = f.value.to()?,
+                "EmissionRate" => this.emit_rate = f.value.to()?,
+                "Speed" => this.speed = f.value.to()?,
+                "Color" => this.color = f.value.to()?,
+                "Alpha" => this.alpha = f.value.to()?,
+                "ReplaceableId" => this.replaceable_id = f.value.to()?,
+                "Path" => this.path = f.value.to()?,
+                "AnimVisibilityGuide" => this.anim_visibility_guide = f.value.to()?,
+                _other => this.base.unexpect_mdl_field(f)?,
+            );
+        }
+        for f in &block.blocks {
+            match_istr!(f.typ.as_str(),
+                "LifeSpan" => this.lifespan_anim = Some(Animation::read_mdl(f)?),
+                "EmissionRate" => this.emit_rate_anim = Some(Animation::read_mdl(f)?),
+                "Speed" => this.speed_anim = Some(Animation::read_mdl(f)?),
+                "Color" => this.color_anim = Some(Animation::read_mdl(f)?),
+                "Alpha" => this.alpha_anim = Some(Animation::read_mdl(f)?),
+                "Visibility" => this.visibility = Some(Animation::read_mdl(f)?),
+                "UnknownTrack" => this.unknown_tracks.push(UnknownTrack::from_mdl(f)?),
+                _other => this.base.unexpect_mdl_block(f)?,
+            );
+        }
+        if opts.rgb {
+            this.color_anim = this.color_anim.map(|a| a.convert(|v| v.reverse()));
+        }
+        return Ok(this);
+    }
+
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        let indent = opts.indent(depth);
+        let mut lines: Vec<String> = vec![];
+
+        lines.append(&mut self.base.write_mdl(depth, opts)?);
+        lines.push_if(self.base.flags.contains(NodeFlags::PE2Unshaded), F!("{indent}Unshaded,"));
+        lines.push_if(
+            self.base.flags.contains(NodeFlags::PE2SortPrimFarZ),
+            F!("{indent}SortPrimsFarZ,"),
+        );
+        lines.push_if(self.base.flags.contains(NodeFlags::Unfogged), F!("{indent}Unfogged,"));
+
+        let bgr_anim = self.color_anim.as_ref().map(|a| a.convert(|v| v.reverse()));
+        let color_anim = yesno!(opts.rgb, &bgr_anim, &self.color_anim);
+
+        MdlWriteAnimBoth!(lines, depth, opts,
+            "LifeSpan" => self.lifespan_anim => 0.0 => self.lifespan,
+            "EmissionRate" => self.emit_rate_anim => 0.0 => self.emit_rate,
+            "Speed" => self.speed_anim => 0.0 => self.speed,
+            "Color" => color_anim => Vec3::ONE => self.color,
+            "Alpha" => self.alpha_anim => 1.0 => self.alpha,
+        );
+        lines.pushx_if_n0(&F!("{indent}ReplaceableId"), &self.replaceable_id, opts);
+        lines.pushx_if_n0(&F!("{indent}Path"), &self.path.escape_path(), opts);
+        lines.pushx_if_n0(
+            &F!("{indent}AnimVisibilityGuide"),
+            &self.anim_visibility_guide.escape(),
+            opts,
+        );
+        MdlWriteAnimIfSome!(lines, depth, opts, "Visibility" => self.visibility);
+        lines.append(&mut self.unknown_tracks.write_mdl("UnknownTrack", depth, opts)?);
+
+        return Ok(lines);
+    }
+}
+
+impl_VisitAnims!(PopcornEmitter: base,
+    "LifeSpan"     => lifespan_anim,
+    "EmissionRate" => emit_rate_anim,
+    "Speed"        => speed_anim,
+    "Color"        => color_anim,
+    "Alpha"        => alpha_anim,
+    "Visibility"   => visibility,
+);
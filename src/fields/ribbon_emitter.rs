@@ -30,6 +30,7 @@ pub struct RibbonEmitter {
     pub texslot_anim: Option<Animation<i32>>,
     #[dbg(formatter = "fmtxx")]
     pub visibility: Option<Animation<f32>>,
+    pub unknown_tracks: Vec<UnknownTrack>,
 }
 
 impl RibbonEmitter {
@@ -56,16 +57,22 @@ impl RibbonEmitter {
         this.material_id = cur.readx()?;
         this.gravity = cur.readx()?;
 
+        let mut after = 0; // the last known track, for unknown tracks
         while cur.left() >= 16 {
-            match cur.read_be()? {
+            let id = cur.read_be()?;
+            match id {
                 Self::ID_HA => this.height_above_anim = Some(Animation::read_mdx(cur)?),
                 Self::ID_HB => this.height_below_anim = Some(Animation::read_mdx(cur)?),
                 Self::ID_A => this.alpha_anim = Some(Animation::read_mdx(cur)?),
                 Self::ID_C => this.color_anim = Some(Animation::read_mdx(cur)?),
                 Self::ID_TS => this.texslot_anim = Some(Animation::read_mdx(cur)?),
                 Self::ID_V => this.visibility = Some(Animation::read_mdx(cur)?),
-                id => return ERR!("Unknown animation in {}: {} (0x{:08X})", TNAME!(), u32_to_ascii(id), id),
+                _ => {
+                    this.unknown_tracks.push(UnknownTrack::read_mdx(cur, id, after, TNAME!())?);
+                    continue;
+                },
             }
+            after = id;
         }
 
         return Ok(this);
@@ -87,7 +94,7 @@ impl RibbonEmitter {
         chunk.write(&self.material_id)?;
         chunk.write(&self.gravity)?;
 
-        MdxWriteAnim!(chunk,
+        MdxWriteAnim!(chunk, self.unknown_tracks;
             Self::ID_HA => self.height_above_anim,
             Self::ID_HB => self.height_below_anim,
             Self::ID_A  => self.alpha_anim,
@@ -106,10 +113,11 @@ impl RibbonEmitter {
         sz += self.color_anim.calc_mdx_size();
         sz += self.texslot_anim.calc_mdx_size();
         sz += self.visibility.calc_mdx_size();
+        sz += self.unknown_tracks.calc_mdx_size();
         return sz;
     }
 
-    pub fn read_mdl(block: &MdlBlock) -> Result<Self, MyError> {
+    pub fn read_mdl(block: &MdlBlock, opts: &MdlReadOptions) -> Result<Self, MyError> {
         let mut this = Build! { base: Node::read_mdl(block)? };
         this.base.flags.insert(NodeFlags::RibbonEmitter);
         for f in &block.fields {
@@ -135,42 +143,53 @@ impl RibbonEmitter {
                 "Color" => this.color_anim = Some(Animation::read_mdl(f)?),
                 "TextureSlot" => this.texslot_anim = Some(Animation::read_mdl(f)?),
                 "Visibility" => this.visibility = Some(Animation::read_mdl(f)?),
+                "UnknownTrack" => this.unknown_tracks.push(UnknownTrack::from_mdl(f)?),
                 _other => this.base.unexpect_mdl_block(f)?,
             );
         }
-        if *mdl_rgb!() {
+        if opts.rgb {
             this.color_anim = this.color_anim.map(|a| a.convert(|v| v.reverse()));
         }
         return Ok(this);
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        let indent = indent!(depth);
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        let indent = opts.indent(depth);
         let mut lines: Vec<String> = vec![];
 
-        lines.append(&mut self.base.write_mdl(depth)?);
+        lines.append(&mut self.base.write_mdl(depth, opts)?);
 
         let bgr_anim = self.color_anim.as_ref().and_then(|a| Some(a.convert(|v| v.reverse())));
-        let color_anim = yesno!(*mdl_rgb!(), &bgr_anim, &self.color_anim);
+        let color_anim = yesno!(opts.rgb, &bgr_anim, &self.color_anim);
 
-        lines.pushx_if_n0(&F!("{indent}EmissionRate"), &self.emit_rate);
-        lines.pushx_if_n0(&F!("{indent}LifeSpan"), &self.lifespan);
-        lines.pushx_if_n0(&F!("{indent}Gravity"), &self.gravity);
-        lines.pushx_if_n0(&F!("{indent}Rows"), &self.rows);
-        lines.pushx_if_n0(&F!("{indent}Columns"), &self.columns);
-        lines.pushx_if_nneg1(&F!("{indent}MaterialID"), &self.material_id);
+        lines.pushx_if_n0(&F!("{indent}EmissionRate"), &self.emit_rate, opts);
+        lines.pushx_if_n0(&F!("{indent}LifeSpan"), &self.lifespan, opts);
+        lines.pushx_if_n0(&F!("{indent}Gravity"), &self.gravity, opts);
+        lines.pushx_if_n0(&F!("{indent}Rows"), &self.rows, opts);
+        lines.pushx_if_n0(&F!("{indent}Columns"), &self.columns, opts);
+        lines.pushx_if_nneg1(&F!("{indent}MaterialID"), &self.material_id, opts);
 
-        MdlWriteAnimBoth!(lines, depth,
+        MdlWriteAnimBoth!(lines, depth, opts,
             "HeightAbove" => self.height_above_anim => 0.0 => self.height_above,
             "HeightBelow" => self.height_below_anim => 0.0 => self.height_below,
             "Alpha" => self.alpha_anim => 0.0 => self.alpha,
             "Color" => color_anim => Vec3::ZERO => self.color,
         );
-        MdlWriteAnimIfSome!(lines, depth,
+        MdlWriteAnimIfSome!(lines, depth, opts,
             "TextureSlot" => self.texslot_anim,
             "Visibility" => self.visibility,
         );
+        lines.append(&mut self.unknown_tracks.write_mdl("UnknownTrack", depth, opts)?);
 
         return Ok(lines);
     }
 }
+
+impl_VisitAnims!(RibbonEmitter: base,
+    "HeightAbove" => height_above_anim,
+    "HeightBelow" => height_below_anim,
+    "Alpha"       => alpha_anim,
+    "Color"       => color_anim,
+    "TextureSlot" => texslot_anim,
+    "Visibility"  => visibility,
+);
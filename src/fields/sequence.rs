@@ -20,7 +20,7 @@ pub struct Sequence {
 
 impl Sequence {
     pub const ID: u32 = MdlxMagic::SEQS;
-    const NAME_SIZE: u32 = 80;
+    pub const NAME_SIZE: u32 = 80;
 
     pub fn read_mdx(cur: &mut Cursor<&Vec<u8>>) -> Result<Self, MyError> {
         Ok(Self {
@@ -51,7 +51,7 @@ impl Sequence {
         return Ok(());
     }
 
-    pub fn read_mdl(block: &MdlBlock) -> Result<Self, MyError> {
+    pub fn read_mdl(block: &MdlBlock, _: &MdlReadOptions) -> Result<Self, MyError> {
         block.unexpect_frames()?;
         block.unexpect_blocks()?;
         let mut this = Build! { name: block.name.clone() };
@@ -75,18 +75,18 @@ impl Sequence {
         return Ok(this);
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        let (indent, indent2) = (indent!(depth), indent!(depth + 1));
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        let (indent, indent2) = (opts.indent(depth), opts.indent(depth + 1));
         let mut lines: Vec<String> = vec![];
         lines.push(F!("{indent}Anim \"{}\" {{", self.name.escape()));
         lines.push(F!("{indent2}Interval {{ {}, {} }},", self.start_frame, self.end_frame));
-        lines.pushx_if_n0(&F!("{indent2}MoveSpeed"), &self.move_speed);
+        lines.pushx_if_n0(&F!("{indent2}MoveSpeed"), &self.move_speed, opts);
         no!(self.looping, lines.push(F!("{indent2}NonLooping,")));
-        lines.pushx_if_n0(&F!("{indent2}Rarity"), &self.rarity);
+        lines.pushx_if_n0(&F!("{indent2}Rarity"), &self.rarity, opts);
         if !(self.bounds_radius.is0() && self.min_extent.is0() && self.max_extent.is0()) {
-            lines.pushx(&F!("{indent2}BoundsRadius"), &self.bounds_radius);
-            lines.pushx(&F!("{indent2}MinimumExtent"), &self.min_extent);
-            lines.pushx(&F!("{indent2}MaximumExtent"), &self.max_extent);
+            lines.pushx(&F!("{indent2}BoundsRadius"), &self.bounds_radius, opts);
+            lines.pushx(&F!("{indent2}MinimumExtent"), &self.min_extent, opts);
+            lines.pushx(&F!("{indent2}MaximumExtent"), &self.max_extent, opts);
         }
         lines.push(F!("{indent}}}"));
         return Ok(lines);
@@ -19,7 +19,7 @@ bitflags! {
 
 impl Texture {
     pub const ID: u32 = MdlxMagic::TEXS;
-    const PATH_SIZE: u32 = 256;
+    pub const PATH_SIZE: u32 = 256;
 
     pub fn read_mdx(cur: &mut Cursor<&Vec<u8>>) -> Result<Self, MyError> {
         Ok(Self {
@@ -38,7 +38,7 @@ impl Texture {
         return Ok(());
     }
 
-    pub fn read_mdl(block: &MdlBlock) -> Result<Self, MyError> {
+    pub fn read_mdl(block: &MdlBlock, _: &MdlReadOptions) -> Result<Self, MyError> {
         block.unexpect_frames()?;
         block.unexpect_blocks()?;
         let mut this = Build!();
@@ -54,8 +54,8 @@ impl Texture {
         return Ok(this);
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        let (indent, indent2) = (indent!(depth), indent!(depth + 1));
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        let (indent, indent2) = (opts.indent(depth), opts.indent(depth + 1));
         let mut lines: Vec<String> = vec![];
         lines.push(F!("{indent}Bitmap {{"));
         lines.push_if_n0(&F!("{indent2}ReplaceableId"), &self.replace_id);
@@ -75,6 +75,7 @@ pub struct TextureAnim {
     pub rotation: Option<Animation<Vec4>>,
     #[dbg(formatter = "fmtxx")]
     pub scaling: Option<Animation<Vec3>>,
+    pub unknown_tracks: Vec<UnknownTrack>,
 }
 
 impl TextureAnim {
@@ -85,20 +86,26 @@ impl TextureAnim {
 
     pub fn read_mdx(cur: &mut Cursor<&Vec<u8>>) -> Result<Self, MyError> {
         let mut this = Build!();
+        let mut after = 0; // the last known track, for unknown tracks
         while cur.left() >= 16 {
-            match cur.read_be()? {
+            let id = cur.read_be()?;
+            match id {
                 Self::ID_T => this.translation = Some(Animation::read_mdx(cur)?),
                 Self::ID_R => this.rotation = Some(Animation::read_mdx(cur)?),
                 Self::ID_S => this.scaling = Some(Animation::read_mdx(cur)?),
-                id => return ERR!("Unknown animation in {}: {} (0x{:08X})", TNAME!(), u32_to_ascii(id), id),
+                _ => {
+                    this.unknown_tracks.push(UnknownTrack::read_mdx(cur, id, after, TNAME!())?);
+                    continue;
+                },
             }
+            after = id;
         }
         return Ok(this);
     }
 
     pub fn write_mdx(&self, chunk: &mut MdxChunk) -> Result<(), MyError> {
         chunk.write(&self.calc_mdx_size())?;
-        MdxWriteAnim!(chunk,
+        MdxWriteAnim!(chunk, self.unknown_tracks;
             Self::ID_T => self.translation,
             Self::ID_R => self.rotation,
             Self::ID_S => self.scaling,
@@ -110,10 +117,11 @@ impl TextureAnim {
         sz += self.translation.calc_mdx_size();
         sz += self.rotation.calc_mdx_size();
         sz += self.scaling.calc_mdx_size();
+        sz += self.unknown_tracks.calc_mdx_size();
         return sz;
     }
 
-    pub fn read_mdl(block: &MdlBlock) -> Result<Self, MyError> {
+    pub fn read_mdl(block: &MdlBlock, _: &MdlReadOptions) -> Result<Self, MyError> {
         block.unexpect_fields()?;
         block.unexpect_frames()?;
         let mut this = Build!();
@@ -122,22 +130,30 @@ impl TextureAnim {
                 "Translation" => this.translation = Some(Animation::read_mdl(f)?),
                 "Rotation" => this.rotation = Some(Animation::read_mdl(f)?),
                 "Scaling" => this.scaling = Some(Animation::read_mdl(f)?),
+                "UnknownTrack" => this.unknown_tracks.push(UnknownTrack::from_mdl(f)?),
                 _other => f.unexpect()?,
             );
         }
         return Ok(this);
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        let indent = indent!(depth);
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        let indent = opts.indent(depth);
         let mut lines: Vec<String> = vec![];
         lines.push(F!("{indent}TVertexAnim {{"));
-        MdlWriteAnimIfSome!(lines, 2,
+        MdlWriteAnimIfSome!(lines, 2, opts,
             "Translation" => self.translation,
             "Rotation" => self.rotation,
             "Scaling" => self.scaling,
         );
+        lines.append(&mut self.unknown_tracks.write_mdl("UnknownTrack", depth + 1, opts)?);
         lines.push(F!("{indent}}}"));
         return Ok(lines);
     }
 }
+
+impl_VisitAnims!(TextureAnim,
+    "Translation" => translation,
+    "Rotation"    => rotation,
+    "Scaling"     => scaling,
+);
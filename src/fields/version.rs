@@ -7,20 +7,19 @@ pub struct Version {
 
 impl Version {
     pub const ID: u32 = MdlxMagic::VERS;
-    pub const SUPPORTED_VERSION: [i32; 1] = [800];
+    pub const SUPPORTED_VERSION: [i32; 3] = [800, 900, 1000];
 
-    fn validate(&self) -> Result<(), MyError> {
-        let ver = self.format_version;
-        let svers = Version::SUPPORTED_VERSION.to_vec();
-        if !svers.contains(&ver) {
-            EXIT1!("Unsupported version {ver} (must be {})", svers.to_or_string());
+    fn validate(&self, line: Option<u32>) -> Result<(), MyError> {
+        let (version, supported) = (self.format_version, Version::SUPPORTED_VERSION.to_vec());
+        if !supported.contains(&version) {
+            return Err(MyError::UnsupportedVersion { version, supported, line });
         }
         return Ok(());
     }
 
     pub fn read_mdx(cur: &mut Cursor<&Vec<u8>>) -> Result<Self, MyError> {
         let this = Self { format_version: cur.readx()? };
-        this.validate()?;
+        this.validate(None)?;
         return Ok(this);
     }
 
@@ -28,22 +27,22 @@ impl Version {
         chunk.write(&self.format_version)
     }
 
-    pub fn read_mdl(block: &MdlBlock) -> Result<Self, MyError> {
+    pub fn read_mdl(block: &MdlBlock, _: &MdlReadOptions) -> Result<Self, MyError> {
         block.unexpect_frames()?;
         block.unexpect_blocks()?;
-        let mut this = Build!();
+        let (mut this, mut line) = (Build!(), block.line);
         for f in &block.fields {
             match_istr!(f.name.as_str(),
-                "FormatVersion" => this.format_version = f.value.to()?,
+                "FormatVersion" => (this.format_version, line) = (f.value.to()?, f.line),
                 _other => f.unexpect()?,
             );
         }
-        this.validate()?;
+        this.validate(Some(line))?;
         return Ok(this);
     }
 
-    pub fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        let (indent, indent2) = (indent!(depth), indent!(depth + 1));
+    pub fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        let (indent, indent2) = (opts.indent(depth), opts.indent(depth + 1));
         return Ok(vec![
             F!("{indent}Version {{"),
             F!("{indent2}FormatVersion {},", self.format_version),
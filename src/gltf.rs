@@ -0,0 +1,1255 @@
+use crate::*;
+
+const FLOAT: u32 = 5126;
+const UNSIGNED_SHORT: u32 = 5123;
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const REPEAT: u32 = 10497;
+const CLAMP_TO_EDGE: u32 = 33071;
+const GLB_MAGIC: u32 = 0x46546C67; // "glTF"
+const GLB_JSON: u32 = 0x4E4F534A; // "JSON"
+const GLB_BIN: u32 = 0x004E4942; // "BIN\0"
+const SAMPLE_STEP: usize = 33; // ms, ~30fps for curves and global sequences
+
+//#region GltfBuffer
+
+/// The binary buffer of a glTF file, with its views and accessors.
+#[derive(Debug, Default)]
+struct GltfBuffer {
+    bin: Vec<u8>,
+    views: Vec<Json>,
+    accessors: Vec<Json>,
+}
+
+impl GltfBuffer {
+    fn push(&mut self, bytes: Vec<u8>, target: Option<u32>, accessor: Json) -> usize {
+        self.bin.resize(self.bin.len().next_multiple_of(4), 0);
+        let mut view =
+            JOBJ! { "buffer" => 0, "byteOffset" => self.bin.len(), "byteLength" => bytes.len() };
+        if let Some(t) = target {
+            view.set("target", t);
+        }
+        self.bin.extend(bytes);
+        self.views.push(view);
+
+        let mut a = JOBJ! { "bufferView" => self.views.len() - 1 };
+        if let Json::Obj(pairs) = accessor {
+            pairs.into_iter().for_each(|(k, v)| a.set(&k, v));
+        }
+        self.accessors.push(a);
+        return self.accessors.len() - 1;
+    }
+
+    /// `bounds`: add min/max, required by positions and animation inputs.
+    fn floats<const N: usize>(
+        &mut self,
+        items: &[[f32; N]],
+        target: Option<u32>,
+        bounds: bool,
+    ) -> usize {
+        let typ = match N {
+            1 => "SCALAR",
+            2 => "VEC2",
+            3 => "VEC3",
+            4 => "VEC4",
+            _ => "MAT4",
+        };
+        let bytes = items.iter().flatten().flat_map(|f| f.to_le_bytes()).collect();
+        let mut a = JOBJ! { "componentType" => FLOAT, "count" => items.len(), "type" => typ };
+        if bounds && !items.is_empty() {
+            let (mut min, mut max) = (items[0], items[0]);
+            for v in items.iter() {
+                for k in 0..N {
+                    (min[k], max[k]) = (min[k].min(v[k]), max[k].max(v[k]));
+                }
+            }
+            a.set("min", min);
+            a.set("max", max);
+        }
+        return self.push(bytes, target, a);
+    }
+
+    fn shorts<const N: usize>(&mut self, items: &[[u16; N]], target: Option<u32>) -> usize {
+        let typ = yesno!(N == 1, "SCALAR", "VEC4");
+        let bytes = items.iter().flatten().flat_map(|f| f.to_le_bytes()).collect();
+        let a = JOBJ! { "componentType" => UNSIGNED_SHORT, "count" => items.len(), "type" => typ };
+        return self.push(bytes, target, a);
+    }
+}
+
+//#endregion
+//#region write_gltf
+
+impl MdlxData {
+    /// Write as glTF 2.0, `*.glb` is binary, `*.gltf` embeds the buffer as a data uri.
+    pub fn write_gltf(&self, path: &Path) -> Result<(), MyError> {
+        let (mut json, bin) = self.to_gltf();
+        let binary = path.ext_lower() == "glb";
+        if !bin.is_empty() {
+            let mut buffer = JOBJ! { "byteLength" => bin.len() };
+            no!(
+                binary,
+                buffer.set("uri", F!("data:application/octet-stream;base64,{}", base64(&bin)))
+            );
+            json.set("buffers", vec![buffer]);
+        }
+        let json = json.to_string();
+        return Ok(fs::write(path, yesno!(binary, glb(&json, &bin), json.into_bytes()))?);
+    }
+
+    /// The glTF json (without buffers) and the binary buffer.
+    /// The model is put under a root node which turns it Z-up to Y-up,
+    /// each node is a joint placed at its pivot, DontInherit and billboard flags are ignored.
+    pub fn to_gltf(&self) -> (Json, Vec<u8>) {
+        let mut buf = GltfBuffer::default();
+        let (nodes, index) = self.gltf_nodes();
+        let pivot = |id: i32| {
+            usize::try_from(id)
+                .ok()
+                .and_then(|i| self.pivot_points.get(i))
+                .map_or(Vec3::ZERO, |p| p.position)
+        };
+        let rest = |n: &Node| pivot(n.object_id) - nodes.parent_of(n).map_or(Vec3::ZERO, pivot);
+
+        let r = std::f32::consts::FRAC_1_SQRT_2;
+        let mut gnodes =
+            vec![JOBJ! { "name" => self.model.name.as_str(), "rotation" => [-r, 0.0, 0.0, r] }];
+        let mut children: Vec<Vec<usize>> = vec![vec![]; nodes.list.len() + 1];
+        for n in nodes.list.iter() {
+            gnodes.push(JOBJ! { "name" => n.name.as_str(), "translation" => rest(n).to_array() });
+            children[nodes.parent_of(n).map_or(0, |p| index[&p])].push(index[&n.object_id]);
+        }
+
+        // skin: the root and all nodes
+        let skinned = !nodes.list.is_empty();
+        let mut skins = vec![];
+        if skinned {
+            let mut ibms = vec![Mat4::IDENTITY.to_cols_array()];
+            ibms.extend(
+                nodes
+                    .list
+                    .iter()
+                    .map(|n| Mat4::from_translation(-pivot(n.object_id)).to_cols_array()),
+            );
+            let ibm = buf.floats(&ibms, None, false);
+            let joints: Vec<usize> = (0..gnodes.len()).collect();
+            skins.push(JOBJ! { "inverseBindMatrices" => ibm, "skeleton" => 0, "joints" => joints });
+        }
+
+        // meshes
+        let (mut meshes, mut roots) = (vec![], vec![0]);
+        for (i, g) in self.geosets.iter().enumerate() {
+            let tris = g.triangle_corners();
+            yes!(tris.is_empty(), continue);
+            let nvert = g.vertices.len();
+            let indices: Vec<[u16; 1]> =
+                tris.iter().flatten().map(|&c| [g.face_vertices[c]]).collect();
+            let positions: Vec<[f32; 3]> = g.vertices.iter().map(|v| v.to_array()).collect();
+
+            let mut attrs =
+                JOBJ! { "POSITION" => buf.floats(&positions, Some(ARRAY_BUFFER), true) };
+            if g.normals.len() == nvert {
+                let normals: Vec<[f32; 3]> =
+                    g.normals.iter().map(|n| n.normalize_or(Vec3::Z).to_array()).collect();
+                attrs.set("NORMAL", buf.floats(&normals, Some(ARRAY_BUFFER), false));
+            }
+            for (k, uvs) in g.uvss.iter().filter(|uvs| uvs.len() == nvert).enumerate() {
+                let uvs: Vec<[f32; 2]> = uvs.iter().map(|uv| uv.to_array()).collect();
+                attrs.set(&F!("TEXCOORD_{k}"), buf.floats(&uvs, Some(ARRAY_BUFFER), false));
+            }
+            if skinned {
+                let (joints, weights) = gltf_weights(g, &index);
+                attrs.set("JOINTS_0", buf.shorts(&joints, Some(ARRAY_BUFFER)));
+                attrs.set("WEIGHTS_0", buf.floats(&weights, Some(ARRAY_BUFFER), false));
+            }
+
+            let indices = buf.shorts(&indices, Some(ELEMENT_ARRAY_BUFFER));
+            let mut prim = JOBJ! { "attributes" => attrs, "indices" => indices };
+            if usize::try_from(g.material_id).is_ok_and(|m| m < self.materials.len()) {
+                prim.set("material", g.material_id);
+            }
+            meshes.push(JOBJ! { "name" => F!("Geoset{i}"), "primitives" => vec![prim] });
+            let mut node = JOBJ! { "name" => F!("Geoset{i}"), "mesh" => meshes.len() - 1 };
+            yes!(skinned, node.set("skin", 0));
+            gnodes.push(node);
+            // skinned meshes ignore the transforms of their parents,
+            // the root turns them through the joints
+            match skinned {
+                true => roots.push(gnodes.len() - 1),
+                false => children[0].push(gnodes.len() - 1),
+            }
+        }
+        for (node, list) in gnodes.iter_mut().zip(children) {
+            no!(list.is_empty(), node.set("children", list));
+        }
+
+        let (materials, textures, images, samplers) = self.gltf_materials();
+        let animations = self.gltf_animations(&nodes, &index, &mut buf, rest);
+
+        let generator = F!("war3mdlx {}", env!("CARGO_PKG_VERSION"));
+        let mut json = JOBJ! {
+            "asset" => JOBJ! { "version" => "2.0", "generator" => generator },
+            "scene" => 0,
+            "scenes" => vec![JOBJ! { "nodes" => roots }],
+            "nodes" => gnodes,
+        };
+        no!(meshes.is_empty(), json.set("meshes", meshes));
+        no!(skins.is_empty(), json.set("skins", skins));
+        no!(materials.is_empty(), json.set("materials", materials));
+        no!(textures.is_empty(), json.set("textures", textures));
+        no!(images.is_empty(), json.set("images", images));
+        no!(samplers.is_empty(), json.set("samplers", samplers));
+        no!(animations.is_empty(), json.set("animations", animations));
+        no!(buf.views.is_empty(), json.set("bufferViews", buf.views));
+        no!(buf.accessors.is_empty(), json.set("accessors", buf.accessors));
+        return (json, buf.bin);
+    }
+
+    /// Nodes sorted by object_id, and their glTF node indices (after the root).
+    fn gltf_nodes(&self) -> (GltfNodes<'_>, HashMap<i32, usize>) {
+        let mut list: Vec<&Node> = self.nodes().into_iter().map(|(_, n)| n).collect();
+        list.sort_by_key(|n| n.object_id);
+        let index: HashMap<i32, usize> =
+            list.iter().enumerate().map(|(i, n)| (n.object_id, i + 1)).collect();
+        let parents: HashMap<i32, i32> = list.iter().map(|n| (n.object_id, n.parent_id)).collect();
+        return (GltfNodes { list, parents }, index);
+    }
+
+    /// Materials from the first layer of each material, textures and images from texture paths.
+    fn gltf_materials(&self) -> (Vec<Json>, Vec<Json>, Vec<Json>, Vec<Json>) {
+        let (mut textures, mut images, mut samplers) = (vec![], vec![], vec![]);
+        let mut tex_index: HashMap<usize, usize> = HashMap::new(); // texture id => glTF texture
+        for (i, t) in self.textures.iter().enumerate() {
+            yes!(t.path.is_empty(), continue); // replaceable
+            images.push(JOBJ! { "uri" => uri_encode(&t.path.replace('\\', "/")) });
+            let wrap = |f: TextureFlags| yesno!(t.flags.contains(f), REPEAT, CLAMP_TO_EDGE);
+            let (wrap_s, wrap_t) = (wrap(TextureFlags::WrapWidth), wrap(TextureFlags::WrapHeight));
+            samplers.push(JOBJ! { "wrapS" => wrap_s, "wrapT" => wrap_t });
+            textures.push(JOBJ! { "source" => images.len() - 1, "sampler" => samplers.len() - 1 });
+            tex_index.insert(i, textures.len() - 1);
+        }
+
+        let mut materials = vec![];
+        for (i, m) in self.materials.iter().enumerate() {
+            let mut pbr = JOBJ! { "metallicFactor" => 0, "roughnessFactor" => 1 };
+            let mut mat = JOBJ! { "name" => F!("Material{i}") };
+            if let Some(layer) = m.layers.first() {
+                pbr.set("baseColorFactor", [1.0, 1.0, 1.0, layer.alpha]);
+                if let Some(t) =
+                    usize::try_from(layer.texture_id).ok().and_then(|t| tex_index.get(&t))
+                {
+                    pbr.set(
+                        "baseColorTexture",
+                        JOBJ! { "index" => *t, "texCoord" => layer.coordid.max(0) },
+                    );
+                }
+                match layer.filter_mode {
+                    FilterMode::None => {},
+                    FilterMode::Transparent | FilterMode::AlphaKey => {
+                        mat.set("alphaMode", "MASK");
+                        mat.set("alphaCutoff", 0.75);
+                    },
+                    _ => mat.set("alphaMode", "BLEND"),
+                }
+                yes!(layer.flags.contains(LayerFlags::TwoSided), mat.set("doubleSided", true));
+            }
+            mat.set("pbrMetallicRoughness", pbr);
+            materials.push(mat);
+        }
+        return (materials, textures, images, samplers);
+    }
+
+    /// One animation per sequence, each node track sampled within it.
+    fn gltf_animations<F: Fn(&Node) -> Vec3>(
+        &self,
+        nodes: &GltfNodes,
+        index: &HashMap<i32, usize>,
+        buf: &mut GltfBuffer,
+        rest: F,
+    ) -> Vec<Json> {
+        let mut animations = vec![];
+        for seq in self.sequences.iter() {
+            let mut anim = GltfAnimation::default();
+            let time = |f: i32| f - seq.start_frame;
+            let times = |frames: &[i32]| {
+                frames.iter().map(|&f| [time(f) as f32 / 1000.0]).collect::<Vec<_>>()
+            };
+            let step = |a: InterpolationType| a == InterpolationType::DontInterp;
+            for n in nodes.list.iter() {
+                let node = index[&n.object_id];
+                if let Some(a) = &n.translation {
+                    let (frames, base) = (self.gltf_frames(a, seq), rest(n));
+                    let values = frames
+                        .iter()
+                        .map(|&f| base + self.sample(&n.translation, f, seq, time(f), Vec3::ZERO));
+                    let values: Vec<[f32; 3]> = values.map(|v| v.to_array()).collect();
+                    anim.add(
+                        buf,
+                        node,
+                        "translation",
+                        &times(&frames),
+                        &values,
+                        step(a.interp_type),
+                    );
+                }
+                if let Some(a) = &n.rotation {
+                    let frames = self.gltf_frames(a, seq);
+                    let values = frames
+                        .iter()
+                        .map(|&f| to_quat(self.sample(&n.rotation, f, seq, time(f), Vec4::W)));
+                    let values: Vec<[f32; 4]> = values.map(|q| q.to_array()).collect();
+                    anim.add(buf, node, "rotation", &times(&frames), &values, step(a.interp_type));
+                }
+                if let Some(a) = &n.scaling {
+                    let frames = self.gltf_frames(a, seq);
+                    let values =
+                        frames.iter().map(|&f| self.sample(&n.scaling, f, seq, time(f), Vec3::ONE));
+                    let values: Vec<[f32; 3]> = values.map(|v| v.to_array()).collect();
+                    anim.add(buf, node, "scale", &times(&frames), &values, step(a.interp_type));
+                }
+            }
+            yes!(anim.channels.is_empty(), continue);
+            let GltfAnimation { samplers, channels } = anim;
+            let name = seq.name.as_str();
+            animations
+                .push(JOBJ! { "name" => name, "samplers" => samplers, "channels" => channels });
+        }
+        return animations;
+    }
+
+    /// Frames to sample a track at within a sequence: its ends and keys,
+    /// or every SAMPLE_STEP for curves and global sequences.
+    fn gltf_frames<T: Interpolate>(&self, anim: &Animation<T>, seq: &Sequence) -> Vec<i32> {
+        let (start, end) = (seq.start_frame, seq.end_frame.max(seq.start_frame));
+        let mut frames = vec![start, end];
+        let global = usize::try_from(anim.global_seq_id).is_ok_and(|i| i < self.globalseqs.len());
+        let curve =
+            matches!(anim.interp_type, InterpolationType::Hermite | InterpolationType::Bezier);
+        if global || curve {
+            frames.extend((start..end).step_by(SAMPLE_STEP));
+        }
+        no!(
+            global,
+            frames.extend(
+                anim.key_frames.iter().map(|k| k.frame).filter(|f| start <= *f && *f <= end)
+            )
+        );
+        frames.sort();
+        frames.dedup();
+        return frames;
+    }
+}
+
+/// Samplers and channels of an exported animation.
+#[derive(Default)]
+struct GltfAnimation {
+    samplers: Vec<Json>,
+    channels: Vec<Json>,
+}
+
+impl GltfAnimation {
+    /// A channel animating `path` of `node`, `times` in seconds,
+    /// `step` holds each value until the next.
+    fn add<const N: usize>(
+        &mut self,
+        buf: &mut GltfBuffer,
+        node: usize,
+        path: &str,
+        times: &[[f32; 1]],
+        values: &[[f32; N]],
+        step: bool,
+    ) {
+        let (input, output) = (buf.floats(times, None, true), buf.floats(values, None, false));
+        let interpolation = yesno!(step, "STEP", "LINEAR");
+        self.samplers
+            .push(JOBJ! { "input" => input, "output" => output, "interpolation" => interpolation });
+        let target = JOBJ! { "node" => node, "path" => path };
+        self.channels.push(JOBJ! { "sampler" => self.samplers.len() - 1, "target" => target });
+    }
+}
+
+/// Nodes to export, with parents which are missing or make a loop ignored.
+struct GltfNodes<'a> {
+    list: Vec<&'a Node>,
+    parents: HashMap<i32, i32>,
+}
+
+impl GltfNodes<'_> {
+    fn parent_of(&self, n: &Node) -> Option<i32> {
+        yes!(!self.parents.contains_key(&n.parent_id), return None);
+        let mut p = n.parent_id;
+        for _ in 0..self.list.len() {
+            yes!(p == n.object_id, return None); // n is in a loop
+            match self.parents.get(&p) {
+                Some(&pp) => p = pp,
+                None => break,
+            }
+        }
+        return Some(n.parent_id);
+    }
+}
+
+/// Up to 4 joints per vertex (by the largest weights), those without any follow the root.
+fn gltf_weights(g: &Geoset, index: &HashMap<i32, usize>) -> (Vec<[u16; 4]>, Vec<[f32; 4]>) {
+    let (mut joints, mut weights) = (vec![], vec![]);
+    for mut bones in g.vertex_bones() {
+        bones.retain(|(id, _)| index.contains_key(id));
+        bones.sort_by(|a, b| b.1.total_cmp(&a.1));
+        bones.truncate(4);
+        let total: f32 = bones.iter().map(|b| b.1).sum();
+        let (mut j, mut w) = ([0_u16; 4], [0_f32; 4]);
+        if total > 0.0 {
+            for (k, (id, weight)) in bones.iter().enumerate() {
+                (j[k], w[k]) = (index[id] as u16, weight / total);
+            }
+        } else {
+            w[0] = 1.0;
+        }
+        joints.push(j);
+        weights.push(w);
+    }
+    return (joints, weights);
+}
+
+fn glb(json: &str, bin: &[u8]) -> Vec<u8> {
+    let mut json = json.as_bytes().to_vec();
+    json.resize(json.len().next_multiple_of(4), b' ');
+    let mut bin = bin.to_vec();
+    bin.resize(bin.len().next_multiple_of(4), 0);
+
+    let total = 12 + 8 + json.len() + yesno!(bin.is_empty(), 0, 8 + bin.len());
+    let mut out = Vec::with_capacity(total);
+    for v in [GLB_MAGIC, 2, total as u32, json.len() as u32, GLB_JSON] {
+        out.extend(v.to_le_bytes());
+    }
+    out.extend(json);
+    if !bin.is_empty() {
+        out.extend((bin.len() as u32).to_le_bytes());
+        out.extend(GLB_BIN.to_le_bytes());
+        out.extend(bin);
+    }
+    return out;
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut s = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for c in bytes.chunks(3) {
+        let n = (c[0] as u32) << 16
+            | (*c.get(1).unwrap_or(&0) as u32) << 8
+            | *c.get(2).unwrap_or(&0) as u32;
+        for k in 0..4 {
+            let ch = yesno!(k <= c.len(), TABLE[(n >> (18 - 6 * k) & 63) as usize] as char, '=');
+            s.push(ch);
+        }
+    }
+    return s;
+}
+
+fn uri_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(b as char)
+            },
+            _ => out += &F!("%{b:02X}"),
+        }
+    }
+    return out;
+}
+
+//#endregion
+//#region read_gltf
+
+const MIN_WEIGHT: f32 = 0.1; // influences below this (of the total) are dropped when quantizing
+const MAX_INFLUENCES: usize = 4;
+
+/// A glTF document with its buffers loaded.
+struct GltfDoc {
+    json: Json,
+    buffers: Vec<Vec<u8>>,
+}
+
+impl GltfDoc {
+    fn load(path: &Path) -> Result<Self, MyError> {
+        let bytes = fs::read(path)?;
+        let (text, mut glb_bin) = match bytes.starts_with(&GLB_MAGIC.to_le_bytes()) {
+            true => read_glb(&bytes)?,
+            false => (String::from_utf8_lossy(&bytes).into_owned(), None),
+        };
+        let json = Json::parse(&text)?;
+        let mut buffers = vec![];
+        for (i, b) in json.get("buffers").arr().iter().enumerate() {
+            let data = match b.get("uri").str() {
+                None => {
+                    glb_bin.take().ok_or_else(|| MyError::String(F!("buffers[{i}] has no data")))?
+                },
+                Some(uri) if uri.starts_with("data:") => match uri.split_once(',') {
+                    Some((_, data)) => base64_decode(data)
+                        .ok_or_else(|| MyError::String(F!("buffers[{i}]: bad base64")))?,
+                    None => return ERR!("buffers[{i}]: bad data uri"),
+                },
+                Some(uri) => {
+                    let file = path.parent().unwrap_or(Path::new("")).join(uri_decode(uri));
+                    fs::read(&file)
+                        .map_err(|e| MyError::from(e).within(F!("buffers[{i}] {:?}", file)))?
+                },
+            };
+            buffers.push(data);
+        }
+        return Ok(Self { json, buffers });
+    }
+
+    /// Elements of an accessor of type `typ` as floats,
+    /// normalized integers are mapped to [0, 1] (or [-1, 1]).
+    fn accessor(&self, index: usize, typ: &str) -> Result<Vec<Vec<f32>>, MyError> {
+        let a = self.json.get("accessors").at(index);
+        let owner = F!("accessors[{index}]");
+        yes!(
+            a.is_null(),
+            return Err(MyError::BadReference {
+                owner: "glTF".s(),
+                field: "accessor".s(),
+                index: index as i64,
+                count: self.json.get("accessors").arr().len()
+            })
+        );
+        yes!(
+            !a.get("sparse").is_null(),
+            return ERR!("{owner}: sparse accessors are not supported")
+        );
+        let count = a.get("count").usize().unwrap_or(0);
+        let found = a.get("type").str().unwrap_or("");
+        yes!(found != typ, return ERR!("{owner}: expected {typ}, found {found:?}"));
+        let n = match found {
+            "SCALAR" => 1,
+            "VEC2" => 2,
+            "VEC3" => 3,
+            "VEC4" => 4,
+            "MAT4" => 16,
+            other => return ERR!("{owner}: unsupported type {other:?}"),
+        };
+        let (ctype, normalized) =
+            (a.get("componentType").usize().unwrap_or(0), a.get("normalized") == &Json::Bool(true));
+        let size = match ctype {
+            5120 | 5121 => 1,
+            5122 | 5123 => 2,
+            5125 | 5126 => 4,
+            other => return ERR!("{owner}: unsupported componentType {other}"),
+        };
+        let Some(view) = a.get("bufferView").usize() else { return Ok(vec![vec![0.0; n]; count]) };
+        let view = self.json.get("bufferViews").at(view);
+        let Some(buffer) = view.get("buffer").usize().and_then(|b| self.buffers.get(b)) else {
+            return ERR!("{owner}: missing buffer");
+        };
+        let offset =
+            view.get("byteOffset").usize().unwrap_or(0) + a.get("byteOffset").usize().unwrap_or(0);
+        let stride = view.get("byteStride").usize().unwrap_or(n * size);
+        let end = offset + stride * count.saturating_sub(1) + n * size;
+        yes!(
+            count > 0 && end > buffer.len(),
+            return ERR!("{owner}: out of buffer ({end} > {})", buffer.len())
+        );
+
+        let mut out = Vec::with_capacity(count);
+        for i in 0..count {
+            let item = (0..n).map(|k| {
+                let p = offset + i * stride + k * size;
+                let b = &buffer[p..p + size];
+                match (ctype, normalized) {
+                    (5120, true) => (b[0] as i8 as f32 / 127.0).max(-1.0),
+                    (5120, false) => b[0] as i8 as f32,
+                    (5121, true) => b[0] as f32 / 255.0,
+                    (5121, false) => b[0] as f32,
+                    (5122, true) => (i16::from_le_bytes([b[0], b[1]]) as f32 / 32767.0).max(-1.0),
+                    (5122, false) => i16::from_le_bytes([b[0], b[1]]) as f32,
+                    (5123, true) => u16::from_le_bytes([b[0], b[1]]) as f32 / 65535.0,
+                    (5123, false) => u16::from_le_bytes([b[0], b[1]]) as f32,
+                    (5125, _) => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32,
+                    _ => f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+                }
+            });
+            out.push(item.collect());
+        }
+        return Ok(out);
+    }
+
+    /// Rest transform of a node relative to its parent.
+    fn local(&self, node: usize) -> (Vec3, Quat, Vec3) {
+        let n = self.json.get("nodes").at(node);
+        let m = n.get("matrix").nums();
+        if m.len() == 16 {
+            let (s, r, t) = Mat4::from_cols_slice(&m).to_scale_rotation_translation();
+            return (t, r, s);
+        }
+        let (t, r, s) =
+            (n.get("translation").nums(), n.get("rotation").nums(), n.get("scale").nums());
+        return (
+            yesno!(t.len() == 3, Vec3::from_slice(&t), Vec3::ZERO),
+            yesno!(r.len() == 4, to_quat(Vec4::from_slice(&r)), Quat::IDENTITY),
+            yesno!(s.len() == 3, Vec3::from_slice(&s), Vec3::ONE),
+        );
+    }
+}
+
+impl MdlxData {
+    /// Read a skinned, animated glTF 2.0 (`*.gltf` or `*.glb`) as a version 800 model,
+    /// Y-up is turned to Z-up. Joints become bones, weights are quantized into matrix groups,
+    /// animations are laid end to end as sequences.
+    pub fn read_gltf(path: &Path) -> Result<Self, MyError> {
+        let doc = GltfDoc::load(path)?;
+        let mut this = Self::default();
+        this.version.format_version = FORMAT_VERSION;
+        this.model.name = path.file_stem().map_or("".s(), |s| s.to_string_lossy().into_owned());
+        GltfImport::new(&doc).build(&mut this)?;
+        this.recalc_extents();
+        return Ok(this);
+    }
+}
+
+/// State of a glTF import: the node tree, and the joints as bones (by object_id).
+struct GltfImport<'a> {
+    doc: &'a GltfDoc,
+    parents: HashMap<usize, usize>,
+    rest: Vec<Mat4>,                // global rest transform of each node (Y-up)
+    bones: HashMap<usize, i32>,     // joint node => object_id
+    materials: HashMap<usize, i32>, // glTF material => material id
+    textures: HashMap<String, i32>, // path => texture id
+}
+
+impl<'a> GltfImport<'a> {
+    fn new(doc: &'a GltfDoc) -> Self {
+        let nodes = doc.json.get("nodes").arr();
+        let mut parents = HashMap::new();
+        for (i, n) in nodes.iter().enumerate() {
+            for c in n.get("children").arr().iter().filter_map(|c| c.usize()) {
+                parents.entry(c).or_insert(i);
+            }
+        }
+        let mut this = Self {
+            doc,
+            parents,
+            rest: vec![],
+            bones: HashMap::new(),
+            materials: HashMap::new(),
+            textures: HashMap::new(),
+        };
+        this.rest = (0..nodes.len()).map(|i| this.global_rest(i, 0)).collect();
+        return this;
+    }
+
+    fn global_rest(&self, node: usize, depth: usize) -> Mat4 {
+        let (t, r, s) = self.doc.local(node);
+        let local = Mat4::from_scale_rotation_translation(s, r, t);
+        return match self.parents.get(&node) {
+            Some(&p) if depth < self.parents.len() => self.global_rest(p, depth + 1) * local,
+            _ => local,
+        };
+    }
+
+    /// Y-up to Z-up.
+    fn zup() -> Mat4 {
+        Mat4::from_rotation_x(std::f32::consts::FRAC_PI_2)
+    }
+
+    /// The nearest ancestor (or itself) which is a bone.
+    fn bone_of(&self, mut node: usize) -> Option<i32> {
+        for _ in 0..=self.parents.len() {
+            if let Some(&id) = self.bones.get(&node) {
+                return Some(id);
+            }
+            node = *self.parents.get(&node)?;
+        }
+        return None;
+    }
+
+    fn build(&mut self, data: &mut MdlxData) -> Result<(), MyError> {
+        let json = &self.doc.json;
+        let nodes = json.get("nodes").arr();
+
+        // bones from the joints of all skins
+        let mut joints: Vec<usize> = vec![];
+        for skin in json.get("skins").arr() {
+            for j in skin
+                .get("joints")
+                .arr()
+                .iter()
+                .filter_map(|j| j.usize())
+                .filter(|&j| j < nodes.len())
+            {
+                no!(joints.contains(&j), joints.push(j));
+            }
+        }
+        for (id, &j) in joints.iter().enumerate() {
+            self.bones.insert(j, id as i32);
+        }
+        for &j in joints.iter() {
+            let mut bone = Bone::default();
+            bone.base.name = nodes[j]
+                .get("name")
+                .str()
+                .filter(|s| !s.is_empty())
+                .map_or(F!("Joint{j}"), |s| s.s());
+            bone.base.object_id = self.bones[&j];
+            bone.base.parent_id = self.parents.get(&j).and_then(|&p| self.bone_of(p)).unwrap_or(-1);
+            bone.base.flags = NodeFlags::Bone;
+            data.bones.push(bone);
+            data.pivot_points.push(PivotPoint {
+                position: (Self::zup() * self.rest[j]).transform_point3(Vec3::ZERO),
+            });
+        }
+
+        // geosets from the primitives of all mesh nodes
+        for (i, n) in nodes.iter().enumerate() {
+            let Some(mesh) = n.get("mesh").usize() else { continue };
+            let skin = n.get("skin").usize().map(|s| json.get("skins").at(s));
+            for (k, prim) in json.get("meshes").at(mesh).get("primitives").arr().iter().enumerate()
+            {
+                let parts = self
+                    .read_primitive(data, i, skin, prim)
+                    .map_err(|e| e.within(F!("meshes[{mesh}].primitives[{k}]")))?;
+                data.geosets.extend(parts);
+            }
+        }
+
+        self.read_animations(data, &joints)?;
+        return Ok(());
+    }
+
+    fn read_primitive(
+        &mut self,
+        data: &mut MdlxData,
+        node: usize,
+        skin: Option<&Json>,
+        prim: &Json,
+    ) -> Result<Vec<Geoset>, MyError> {
+        let attrs = prim.get("attributes");
+        let attr = |name: &str, typ: &str| {
+            let a = attrs.get(name).usize().map(|a| self.doc.accessor(a, typ)).transpose();
+            a.map_err(|e| e.within(name))
+        };
+        let Some(positions) = attr("POSITION", "VEC3")? else { return Ok(vec![]) };
+        let nvert = positions.len();
+        let normals = attr("NORMAL", "VEC3")?.filter(|n| n.len() == nvert);
+        let vec3 = |v: &Vec<f32>| Vec3::new(v[0], v[1], v[2]);
+
+        // faces
+        let indices: Vec<usize> = match prim.get("indices").usize() {
+            Some(a) => self.doc.accessor(a, "SCALAR")?.iter().map(|v| v[0] as usize).collect(),
+            None => (0..nvert).collect(),
+        };
+        if let Some(&v) = indices.iter().find(|&&v| v >= nvert) {
+            return ERR!("index {v} out of {nvert} vertices");
+        }
+        let faces: Vec<i32> = match prim.get("mode").usize().unwrap_or(4) {
+            4 => indices.chunks_exact(3).flatten().map(|&v| v as i32).collect(),
+            5 => (2..indices.len())
+                .flat_map(|k| match k % 2 {
+                    0 => [indices[k - 2], indices[k - 1], indices[k]],
+                    _ => [indices[k - 1], indices[k - 2], indices[k]],
+                })
+                .map(|v| v as i32)
+                .collect(),
+            6 => (2..indices.len())
+                .flat_map(|k| [indices[0], indices[k - 1], indices[k]])
+                .map(|v| v as i32)
+                .collect(),
+            _ => return Ok(vec![]), // points and lines
+        };
+
+        // bones (object_id, weight) of each vertex, and their rest matrices
+        let mut bones: Vec<Vec<(i32, f32)>> = vec![vec![]; nvert];
+        let mut matrices: Vec<Mat4> = vec![self.rest[node]; nvert];
+        if let (Some(skin), Some(js), Some(ws)) =
+            (skin, attr("JOINTS_0", "VEC4")?, attr("WEIGHTS_0", "VEC4")?)
+        {
+            let joints: Vec<usize> =
+                skin.get("joints").arr().iter().map(|j| j.usize().unwrap_or(usize::MAX)).collect();
+            let ibms = match skin.get("inverseBindMatrices").usize() {
+                Some(a) => {
+                    self.doc.accessor(a, "MAT4")?.iter().map(|m| Mat4::from_cols_slice(m)).collect()
+                },
+                None => vec![Mat4::IDENTITY; joints.len()],
+            };
+            for (v, (j, w)) in js.iter().zip(ws.iter()).enumerate().take(nvert) {
+                let (mut sum, mut total) = (Mat4::ZERO, 0.0);
+                for k in 0..4 {
+                    let Some(&node) = joints.get(j[k] as usize) else { continue };
+                    let (Some(&id), Some(ibm)) = (self.bones.get(&node), ibms.get(j[k] as usize))
+                    else {
+                        continue;
+                    };
+                    yes!(w[k] <= 0.0, continue);
+                    sum += self.rest[node] * *ibm * w[k];
+                    total += w[k];
+                    bones[v].push((id, w[k]));
+                }
+                yes!(total > 0.0, matrices[v] = sum / total);
+            }
+        }
+        let fallback = self.bone_of(node);
+
+        let mut this = Geoset::default();
+        let zup = Self::zup();
+        for v in 0..nvert {
+            let m = zup * matrices[v];
+            this.vertices.push(m.transform_point3(vec3(&positions[v])));
+            if let Some(normals) = &normals {
+                let m = Mat3::from_mat4(m);
+                let m = yesno!(m.determinant().abs() > 1e-12, m.inverse().transpose(), m);
+                this.normals.push((m * vec3(&normals[v])).normalize_or(Vec3::Z));
+            }
+        }
+        for k in 0.. {
+            let name = F!("TEXCOORD_{k}");
+            let Some(uvs) = attr(&name, "VEC2")? else { break };
+            yes!(
+                uvs.len() != nvert,
+                return ERR!("{name}: {} coordinates for {nvert} vertices", uvs.len())
+            );
+            this.uvss.push(uvs.iter().map(|uv| Vec2::new(uv[0], uv[1])).collect());
+        }
+        this.material_id = self.material(data, prim.get("material").usize());
+
+        // quantize weights into matrix groups
+        let (mut groups, mut group_index): (Vec<Vec<i32>>, HashMap<Vec<i32>, i32>) =
+            (vec![], HashMap::new());
+        let mut vtxgrps = Vec::with_capacity(nvert);
+        for mut list in bones {
+            list.sort_by(|a, b| b.1.total_cmp(&a.1));
+            let total: f32 = list.iter().map(|b| b.1).sum();
+            let mut ids: Vec<i32> = list
+                .iter()
+                .filter(|b| b.1 >= total * MIN_WEIGHT)
+                .map(|b| b.0)
+                .take(MAX_INFLUENCES)
+                .collect();
+            yes!(ids.is_empty(), ids.push(fallback.unwrap_or_else(|| self.root_bone(data))));
+            ids.sort();
+            let g = *group_index.entry(ids.clone()).or_insert_with(|| {
+                groups.push(ids);
+                groups.len() as i32 - 1
+            });
+            vtxgrps.push(g);
+        }
+        let mut parts = this.from_triangles(&faces, &vtxgrps, &groups)?;
+        if normals.is_none() {
+            for g in parts.iter_mut() {
+                g.recalc_normals(&NormalOptions::default())?;
+            }
+        }
+        return Ok(parts);
+    }
+
+    /// A bone for vertices which follow no joint, created at the first use.
+    fn root_bone(&mut self, data: &mut MdlxData) -> i32 {
+        if let Some(b) = data.bones.iter().find(|b| b.base.name == "Root" && b.base.parent_id == -1)
+        {
+            return b.base.object_id;
+        }
+        let mut bone = Bone::default();
+        (bone.base.name, bone.base.object_id, bone.base.flags) =
+            ("Root".s(), data.bones.len() as i32, NodeFlags::Bone);
+        data.pivot_points.push(PivotPoint { position: Vec3::ZERO });
+        data.bones.push(bone);
+        return data.bones.len() as i32 - 1;
+    }
+
+    /// The material id of a glTF material (or of a default material), created at the first use.
+    fn material(&mut self, data: &mut MdlxData, index: Option<usize>) -> i32 {
+        let key = index.unwrap_or(usize::MAX);
+        if let Some(&id) = self.materials.get(&key) {
+            return id;
+        }
+        let m = index.map_or(&Json::Null, |i| self.doc.json.get("materials").at(i));
+
+        let mut layer = Layer::default();
+        let pbr = m.get("pbrMetallicRoughness");
+        let factor = pbr.get("baseColorFactor").nums();
+        yes!(factor.len() == 4, layer.alpha = factor[3]);
+        layer.filter_mode = match m.get("alphaMode").str() {
+            Some("BLEND") => FilterMode::Blend,
+            Some("MASK") => FilterMode::Transparent,
+            _ => FilterMode::None,
+        };
+        yes!(m.get("doubleSided") == &Json::Bool(true), layer.flags.insert(LayerFlags::TwoSided));
+        let tex = pbr.get("baseColorTexture");
+        layer.coordid = tex.get("texCoord").usize().unwrap_or(0) as i32;
+        let image = tex
+            .get("index")
+            .usize()
+            .and_then(|t| self.doc.json.get("textures").at(t).get("source").usize());
+        let uri = image
+            .and_then(|i| self.doc.json.get("images").at(i).get("uri").str())
+            .filter(|u| !u.starts_with("data:"));
+        let path = uri.map_or(WHITE_TEXTURE.s(), |u| uri_decode(u).replace('/', "\\"));
+        layer.texture_id = *self.textures.entry(path.clone()).or_insert_with(|| {
+            data.textures.push(Texture { path, ..Default::default() });
+            data.textures.len() as i32 - 1
+        });
+
+        let mut material = Material::default();
+        material.layers.push(layer);
+        data.materials.push(material);
+        let id = data.materials.len() as i32 - 1;
+        self.materials.insert(key, id);
+        return id;
+    }
+
+    /// One sequence per animation, laid end to end, with linear tracks on the animated bones.
+    fn read_animations(&self, data: &mut MdlxData, joints: &[usize]) -> Result<(), MyError> {
+        let zup = Self::zup();
+        let mut start = 0;
+        let mut tracks: HashMap<usize, JointTracks> = HashMap::new();
+        for (i, anim) in self.doc.json.get("animations").arr().iter().enumerate() {
+            let channels = self.read_channels(anim).map_err(|e| e.within(F!("animations[{i}]")))?;
+            let duration =
+                channels.iter().flat_map(|c| c.times.last()).fold(0.0_f32, |a, b| a.max(*b));
+            let end = start + (duration * 1000.0).round() as i32;
+            let name = anim.get("name").str().map_or(F!("Animation{i}"), |s| s.s());
+            let seq = Sequence { name, start_frame: start, end_frame: end, ..Default::default() };
+            data.sequences.push(seq);
+
+            for &j in joints.iter() {
+                let mine: Vec<&GltfChannel> = channels.iter().filter(|c| c.node == j).collect();
+                yes!(mine.is_empty(), continue);
+                let mut frames: Vec<i32> = mine
+                    .iter()
+                    .flat_map(|c| c.times.iter().map(|t| start + (t * 1000.0).round() as i32))
+                    .collect();
+                frames.sort();
+                frames.dedup();
+
+                let (t0, r0, s0) = self.doc.local(j);
+                let parent = self.parents.get(&j).map_or(Mat4::IDENTITY, |&p| self.rest[p]);
+                let to_mdx = zup * parent;
+                let from_mdx = to_mdx.inverse();
+                let rest_inv = Mat4::from_scale_rotation_translation(s0, r0, t0).inverse();
+                let pivot = (zup * self.rest[j]).transform_point3(Vec3::ZERO);
+                let entry = tracks.entry(j).or_default();
+                for &frame in frames.iter() {
+                    let time = (frame - start) as f32 / 1000.0;
+                    let (mut t, mut r, mut s) = (t0, r0, s0);
+                    for c in mine.iter() {
+                        match c.path {
+                            0 => t = Vec3::from_slice(&c.sample(time)),
+                            1 => r = to_quat(Vec4::from_slice(&c.sample(time))),
+                            _ => s = Vec3::from_slice(&c.sample(time)),
+                        }
+                    }
+                    let m = to_mdx
+                        * Mat4::from_scale_rotation_translation(s, r, t)
+                        * rest_inv
+                        * from_mdx;
+                    let (ms, mr, _) = m.to_scale_rotation_translation();
+                    let animated = |path: usize| mine.iter().any(|c| c.path == path);
+                    let value = m.transform_point3(pivot) - pivot;
+                    yes!(
+                        animated(0),
+                        entry.translation.push(KeyFrame { frame, value, ..Default::default() })
+                    );
+                    yes!(
+                        animated(1),
+                        entry.rotation.push(KeyFrame {
+                            frame,
+                            value: from_quat(mr),
+                            ..Default::default()
+                        })
+                    );
+                    yes!(
+                        animated(2),
+                        entry.scaling.push(KeyFrame { frame, value: ms, ..Default::default() })
+                    );
+                }
+            }
+            start = end + 1;
+        }
+
+        for (j, t) in tracks {
+            let Some(bone) = data.bones.iter_mut().find(|b| b.base.object_id == self.bones[&j])
+            else {
+                continue;
+            };
+            bone.base.translation = linear_track(t.translation);
+            bone.base.rotation = linear_track(t.rotation);
+            bone.base.scaling = linear_track(t.scaling);
+        }
+        return Ok(());
+    }
+
+    fn read_channels(&self, anim: &Json) -> Result<Vec<GltfChannel>, MyError> {
+        let mut channels = vec![];
+        for c in anim.get("channels").arr() {
+            let target = c.get("target");
+            let path = match target.get("path").str() {
+                Some("translation") => 0,
+                Some("rotation") => 1,
+                Some("scale") => 2,
+                _ => continue, // weights
+            };
+            let (Some(node), Some(sampler)) =
+                (target.get("node").usize(), c.get("sampler").usize())
+            else {
+                continue;
+            };
+            let sampler = anim.get("samplers").at(sampler);
+            let (Some(input), Some(output)) =
+                (sampler.get("input").usize(), sampler.get("output").usize())
+            else {
+                continue;
+            };
+            let times: Vec<f32> =
+                self.doc.accessor(input, "SCALAR")?.iter().map(|t| t[0]).collect();
+            yes!(times.is_empty(), return ERR!("sampler input has no times"));
+            let mut values = self.doc.accessor(output, yesno!(path == 1, "VEC4", "VEC3"))?;
+            let interp = sampler.get("interpolation").str().unwrap_or("LINEAR");
+            if interp == "CUBICSPLINE" {
+                // in-tangent, value, out-tangent
+                values = values.chunks(3).filter_map(|v| v.get(1).cloned()).collect();
+            }
+            yes!(
+                values.len() < times.len(),
+                return ERR!("sampler output has {} values for {} times", values.len(), times.len())
+            );
+            channels.push(GltfChannel { node, path, times, values, step: interp == "STEP" });
+        }
+        return Ok(channels);
+    }
+}
+
+/// Keys of an imported joint, in MDX space.
+#[derive(Default)]
+struct JointTracks {
+    translation: Vec<KeyFrame<Vec3>>,
+    rotation: Vec<KeyFrame<Vec4>>,
+    scaling: Vec<KeyFrame<Vec3>>,
+}
+
+/// A channel of a glTF animation, `path`: 0 translation, 1 rotation, 2 scale.
+struct GltfChannel {
+    node: usize,
+    path: usize,
+    times: Vec<f32>,
+    values: Vec<Vec<f32>>,
+    step: bool,
+}
+
+impl GltfChannel {
+    fn sample(&self, time: f32) -> Vec<f32> {
+        let i = self.times.partition_point(|&t| t <= time);
+        yes!(i == 0, return self.values[0].clone());
+        yes!(i >= self.times.len() || self.step, return self.values[i - 1].clone());
+        let (a, b) = (&self.values[i - 1], &self.values[i]);
+        let t = (time - self.times[i - 1]) / (self.times[i] - self.times[i - 1]).max(f32::EPSILON);
+        return match self.path {
+            1 => from_quat(to_quat(Vec4::from_slice(a)).slerp(to_quat(Vec4::from_slice(b)), t))
+                .to_array()
+                .to_vec(),
+            _ => a.iter().zip(b.iter()).map(|(x, y)| x + (y - x) * t).collect(),
+        };
+    }
+}
+
+fn linear_track<T: TAnimation>(key_frames: Vec<KeyFrame<T>>) -> Option<Animation<T>> {
+    yes!(key_frames.is_empty(), return None);
+    return Some(Animation {
+        interp_type: InterpolationType::Linear,
+        key_frames,
+        ..Default::default()
+    });
+}
+
+fn read_glb(bytes: &[u8]) -> Result<(String, Option<Vec<u8>>), MyError> {
+    let u32_at =
+        |p: usize| bytes.get(p..p + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+    let (mut pos, mut json, mut bin) = (12, None, None);
+    while let (Some(len), Some(typ)) = (u32_at(pos), u32_at(pos + 4)) {
+        let Some(chunk) = bytes.get(pos + 8..pos + 8 + len as usize) else {
+            return ERR!("truncated glb chunk at {pos}");
+        };
+        match typ {
+            GLB_JSON => json = Some(String::from_utf8_lossy(chunk).into_owned()),
+            GLB_BIN => bin = Some(chunk.to_vec()),
+            _ => {},
+        }
+        pos += 8 + len as usize;
+    }
+    return Ok((json.ok_or_else(|| MyError::String("glb without a json chunk".s()))?, bin));
+}
+
+fn base64_decode(s: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(s.len() / 4 * 3);
+    let (mut acc, mut bits) = (0_u32, 0);
+    for c in s.bytes().filter(|c| !c.is_ascii_whitespace() && *c != b'=') {
+        let v = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return None,
+        };
+        (acc, bits) = (acc << 6 | v as u32, bits + 6);
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+    }
+    return Some(out);
+}
+
+fn uri_decode(s: &str) -> String {
+    let (b, mut out, mut i) = (s.as_bytes(), vec![], 0);
+    while i < b.len() {
+        let hex = b
+            .get(i + 1..i + 3)
+            .and_then(|h| u8::from_str_radix(std::str::from_utf8(h).ok()?, 16).ok());
+        match (b[i], hex) {
+            (b'%', Some(h)) => (out.push(h), i += 3).1,
+            (c, _) => (out.push(c), i += 1).1,
+        }
+    }
+    return String::from_utf8_lossy(&out).into_owned();
+}
+
+//#endregion
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A triangle skinned to a two-joint chain, with one rotation channel on the second joint.
+    fn fixture(name: &str, position_type: &str) -> PathBuf {
+        let f32s =
+            |values: &[f32]| values.iter().flat_map(|v| v.to_le_bytes()).collect::<Vec<u8>>();
+        let bin = [
+            f32s(&[0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 2.0, 0.0]), // positions
+            vec![0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0],             // joints
+            f32s(&[1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0]), // weights
+            f32s(&[0.0, 1.0]),                                    // times
+            f32s(&[0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.70710677, 0.70710677]), // rotations
+        ]
+        .concat();
+
+        let json = r#"{
+            "asset": { "version": "2.0" },
+            "nodes": [
+                { "name": "Hip", "children": [1] },
+                { "name": "Knee", "translation": [0, 1, 0] },
+                { "mesh": 0, "skin": 0 }
+            ],
+            "skins": [{ "joints": [0, 1] }],
+            "meshes": [{
+                "primitives": [{ "attributes": { "POSITION": 0, "JOINTS_0": 1, "WEIGHTS_0": 2 } }]
+            }],
+            "animations": [{
+                "name": "Bend",
+                "channels": [{ "sampler": 0, "target": { "node": 1, "path": "rotation" } }],
+                "samplers": [{ "input": 3, "output": 4 }]
+            }],
+            "accessors": [
+                { "bufferView": 0, "componentType": 5126, "count": 3, "type": "POSITION_TYPE" },
+                { "bufferView": 1, "componentType": 5121, "count": 3, "type": "VEC4" },
+                { "bufferView": 2, "componentType": 5126, "count": 3, "type": "VEC4" },
+                { "bufferView": 3, "componentType": 5126, "count": 2, "type": "SCALAR" },
+                { "bufferView": 4, "componentType": 5126, "count": 2, "type": "VEC4" }
+            ],
+            "bufferViews": [
+                { "buffer": 0, "byteOffset": 0, "byteLength": 36 },
+                { "buffer": 0, "byteOffset": 36, "byteLength": 12 },
+                { "buffer": 0, "byteOffset": 48, "byteLength": 48 },
+                { "buffer": 0, "byteOffset": 96, "byteLength": 8 },
+                { "buffer": 0, "byteOffset": 104, "byteLength": 32 }
+            ],
+            "buffers": [{ "uri": "BIN_NAME", "byteLength": 136 }]
+        }"#;
+
+        let dir = std::env::temp_dir().join("war3mdlx_gltf_test");
+        fs::create_dir_all(&dir).unwrap();
+        let bin_name = F!("{name}.bin");
+        fs::write(dir.join(&bin_name), &bin).unwrap();
+        let path = dir.join(F!("{name}.gltf"));
+        fs::write(
+            &path,
+            json.replace("POSITION_TYPE", position_type).replace("BIN_NAME", &bin_name),
+        )
+        .unwrap();
+        return path;
+    }
+
+    #[test]
+    fn skinned_import() {
+        let data = MdlxData::read_gltf(&fixture("skinned", "VEC3")).unwrap();
+        assert_eq!(data.bones.len(), 2);
+        assert_eq!(data.bones[0].base.name, "Hip");
+        assert_eq!(data.bones[1].base.parent_id, 0);
+        assert_eq!(data.geosets.len(), 1);
+        assert_eq!(data.geosets[0].vertices.len(), 3);
+        assert_eq!(data.geosets[0].mtxgrpcnts.len(), 2);
+        assert_eq!((data.sequences[0].start_frame, data.sequences[0].end_frame), (0, 1000));
+        let rotation = data.bones[1].base.rotation.as_ref().unwrap();
+        assert_eq!(rotation.key_frames.iter().map(|k| k.frame).collect::<Vec<_>>(), vec![0, 1000]);
+        assert!(data.bones[0].base.rotation.is_none());
+    }
+
+    #[test]
+    fn wrong_accessor_type() {
+        assert!(MdlxData::read_gltf(&fixture("vec2_positions", "VEC2")).is_err());
+    }
+
+    #[test]
+    fn skinned_export() {
+        let data = MdlxData::read_gltf(&fixture("export", "VEC3")).unwrap();
+        let (json, bin) = data.to_gltf();
+        // the Z-up root, 2 joints and 1 mesh node; the skin joints are the root and the joints
+        assert_eq!(json.get("nodes").arr().len(), 4);
+        assert_eq!(json.get("skins").at(0).get("joints").nums(), vec![0.0, 1.0, 2.0]);
+        assert_eq!(json.get("nodes").at(3).get("skin").usize(), Some(0));
+
+        // inverse bind matrices, POSITION, NORMAL, JOINTS_0, WEIGHTS_0, indices,
+        // and the input and output of the rotation channel
+        let (accessors, views) = (json.get("accessors").arr(), json.get("bufferViews").arr());
+        assert_eq!((accessors.len(), views.len()), (8, 8));
+        let counts: Vec<_> = accessors.iter().map(|a| a.get("count").usize().unwrap()).collect();
+        assert_eq!(counts, [3, 3, 3, 3, 3, 3, 2, 2]);
+        for v in views {
+            let offset = v.get("byteOffset").usize().unwrap();
+            let len = v.get("byteLength").usize().unwrap();
+            assert!(offset.is_multiple_of(4) && offset + len <= bin.len());
+        }
+        let channel = json.get("animations").at(0).get("channels").at(0);
+        assert_eq!(channel.get("target").get("node").usize(), Some(2));
+    }
+
+    #[test]
+    fn glb_layout() {
+        let out = glb("{}", &[1, 2, 3, 4, 5]);
+        let u32_at = |p: usize| u32::from_le_bytes(out[p..p + 4].try_into().unwrap());
+        // header 12, json chunk 8 + 4 (padded with spaces), bin chunk 8 + 8 (padded with 0)
+        assert_eq!(out.len(), 40);
+        assert_eq!((u32_at(0), u32_at(4), u32_at(8)), (GLB_MAGIC, 2, 40));
+        assert_eq!((u32_at(12), u32_at(16)), (4, GLB_JSON));
+        assert_eq!(&out[20..24], b"{}  ");
+        assert_eq!((u32_at(24), u32_at(28)), (8, GLB_BIN));
+        assert_eq!(&out[32..], &[1, 2, 3, 4, 5, 0, 0, 0]);
+
+        let (json, bin) = read_glb(&out).unwrap();
+        assert_eq!((json.trim_end(), bin), ("{}", Some(out[32..].to_vec())));
+        assert_eq!(glb("{}", &[]).len(), 24); // no bin chunk
+    }
+
+    #[test]
+    fn base64_vectors() {
+        // RFC 4648
+        let vectors =
+            [("", ""), ("f", "Zg=="), ("fo", "Zm8="), ("foo", "Zm9v"), ("foobar", "Zm9vYmFy")];
+        for (text, encoded) in vectors {
+            assert_eq!(base64(text.as_bytes()), encoded);
+            assert_eq!(base64_decode(encoded).unwrap(), text.as_bytes());
+        }
+        let bytes: Vec<u8> = (0..=255).collect();
+        assert_eq!(base64_decode(&base64(&bytes)).unwrap(), bytes);
+        assert!(base64_decode("Zm9v!").is_none());
+    }
+}
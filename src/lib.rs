@@ -0,0 +1,73 @@
+//! Read, inspect and write Warcraft 3 model files (*.mdl / *.mdx).
+//!
+//! ```no_run
+//! use std::path::Path;
+//! use war3mdlx::{ExportOptions, MdlReadOptions, MdlWriteOptions, MdlxData};
+//!
+//! let mut model = MdlxData::read(Path::new("input.mdx"), &MdlReadOptions::default()).unwrap();
+//! println!("{} geosets", model.geosets.len());
+//! model.write(Path::new("output.mdl"), &MdlWriteOptions::default(), &ExportOptions::default()).unwrap();
+//! ```
+
+use bitflags::bitflags;
+use byteorder::{BigEndian, LittleEndian, ReadBytesExt, WriteBytesExt};
+use chrono::{DateTime, Local};
+use derive_debug::Dbg;
+use glam::{Mat3, Mat4, Quat, Vec2, Vec3, Vec4};
+use lazy_static::lazy_static;
+use paste::paste;
+use pest::Parser;
+use pest::iterators::Pair;
+use pest_derive::Parser;
+use pretty_hex::*;
+use regex::Regex;
+use smart_default::SmartDefault;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::ffi::OsStr;
+use std::fmt::{Debug as stdDebug, Display, Formatter as stdFormatter, Result as stdResult};
+use std::fs;
+use std::io::{Cursor, Error as ioError, Read, Write};
+use std::panic;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+mod bvh;
+mod cli;
+mod convert;
+pub mod data;
+mod extends;
+mod extents;
+pub mod fields;
+mod gltf;
+mod limits;
+pub mod mdl;
+mod mdx;
+mod normals;
+mod obj;
+mod pose;
+mod sample;
+pub mod utils;
+pub mod validate;
+pub mod verify;
+pub mod worker;
+
+pub use cli::run;
+use cli::*;
+pub use convert::*;
+pub use data::*;
+use extends::*;
+pub use fields::*;
+pub use mdl::*;
+pub use normals::*;
+pub use pose::*;
+pub use sample::*;
+pub use utils::MyError;
+use utils::*;
+pub use validate::*;
+pub use verify::*;
+pub use worker::*;
+
+lazy_static! {
+    pub static ref StartTime: DateTime<Local> = Local::now();
+}
@@ -0,0 +1,155 @@
+use crate::*;
+
+//#region check_limits
+
+macro_rules! check_strings {
+    ($owner:expr, $( $var:expr => $limit:expr ),+ $(,)?) => {
+        // a fixed size string needs room for its terminating NUL
+        $(if $var.len() >= $limit as usize {
+            let (owner, value, limit) = ($owner, $var.len(), $limit as usize - 1);
+            let field = F!("{} length", stringify!($var).rsplit('.').next().unwrap_or_default());
+            return Err(MyError::LimitExceeded { owner, field, value, limit });
+        })+
+    };
+}
+
+impl MdlxData {
+    /// Check the structural limits of the mdx format (fixed size strings, u16 face vertices,
+    /// u8 vertex groups), which would silently truncate or wrap around when written.
+    pub fn check_limits(&self) -> Result<(), MyError> {
+        let version = self.version.format_version;
+
+        check_strings!("Model".s(), self.model.name => Model::NAME_SIZE);
+        for (i, a) in self.sequences.iter().enumerate() {
+            check_strings!(F!("Sequence[{i}]"), a.name => Sequence::NAME_SIZE);
+        }
+        for (i, a) in self.textures.iter().enumerate() {
+            check_strings!(F!("Texture[{i}]"), a.path => Texture::PATH_SIZE);
+        }
+        for (i, a) in self.materials.iter().enumerate() {
+            yes!(
+                version > 800,
+                check_strings!(F!("Material[{i}]"), a.shader => Material::SHADER_NAME_SIZE)
+            );
+        }
+        for (i, a) in self.geosets.iter().enumerate() {
+            let owner = F!("Geoset[{i}]");
+            yes!(version > 800, check_strings!(owner.clone(), a.lod_name => Geoset::LOD_NAME_SIZE));
+            let (nvert, ngrp) = (a.vertices.len(), a.mtxgrpcnts.len());
+            if nvert > Geoset::MAX_VERTICES {
+                let (field, limit) = ("vertex count".s(), Geoset::MAX_VERTICES);
+                return Err(MyError::LimitExceeded { owner, field, value: nvert, limit });
+            }
+            if !a.vtxgrps.is_empty() && ngrp > Geoset::MAX_VERTEX_GROUPS {
+                let (field, limit) = ("matrix group count".s(), Geoset::MAX_VERTEX_GROUPS);
+                return Err(MyError::LimitExceeded { owner, field, value: ngrp, limit });
+            }
+        }
+        for (path, a) in self.nodes() {
+            check_strings!(F!("{path} {:?}", a.name), a.name => Node::NAME_SIZE);
+        }
+        for (i, a) in self.cameras.iter().enumerate() {
+            check_strings!(F!("Camera[{i}]"), a.name => Camera::NAME_SIZE);
+        }
+        for (i, a) in self.attachments.iter().enumerate() {
+            check_strings!(F!("Attachment[{i}] {:?}", a.base.name),
+                a.child_path => Attachment::PATH_SIZE,
+            );
+        }
+        for (i, a) in self.particle_emitters.iter().enumerate() {
+            check_strings!(F!("ParticleEmitter[{i}] {:?}", a.base.name),
+                a.path => ParticleEmitter::PATH_SIZE,
+            );
+        }
+        for (i, a) in self.popcorn_emitters.iter().enumerate() {
+            check_strings!(F!("PopcornEmitter[{i}] {:?}", a.base.name),
+                a.path => PopcornEmitter::PATH_SIZE,
+                a.anim_visibility_guide => PopcornEmitter::PATH_SIZE,
+            );
+        }
+        for (i, a) in self.face_effects.iter().enumerate() {
+            check_strings!(F!("FaceEffect[{i}]"),
+                a.name => FaceEffect::NAME_SIZE,
+                a.path => FaceEffect::PATH_SIZE,
+            );
+        }
+
+        return Ok(());
+    }
+}
+
+//#endregion
+//#region remap_split_geosets
+
+impl MdlxData {
+    /// Point references to a split geoset to its first part,
+    /// and animate the other parts like the first one.
+    pub(crate) fn remap_split_geosets(&mut self) {
+        yes!(self.geoset_parts.iter().all(|&n| n == 1), return);
+
+        let mut first: Vec<i32> = vec![];
+        let mut n = 0_i32;
+        for &parts in self.geoset_parts.iter() {
+            first.push(n);
+            n += parts as i32;
+        }
+        let remap = |id: i32| first.get(id.max(0) as usize).copied().filter(|_| id >= 0);
+
+        let mut extra: Vec<GeosetAnim> = vec![];
+        for a in self.geoanims.iter_mut() {
+            let Some(id) = remap(a.geoset_id) else { continue };
+            let parts = self.geoset_parts[a.geoset_id as usize];
+            a.geoset_id = id;
+            for k in 1..parts {
+                extra.push(GeosetAnim { geoset_id: id + k as i32, ..a.clone() });
+            }
+        }
+        self.geoanims.append(&mut extra);
+
+        for a in self.bones.iter_mut() {
+            if let Some(id) = remap(a.geoset_id) {
+                a.geoset_id = id;
+            }
+        }
+    }
+}
+
+//#endregion
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_limit() {
+        let n = Model::NAME_SIZE as usize;
+        let mut data = MdlxData::default();
+        data.model.name = "a".repeat(n - 1);
+        assert!(data.check_limits().is_ok());
+
+        data.model.name.push('a'); // no room left for NUL
+        match data.check_limits() {
+            Err(MyError::LimitExceeded { value, limit, .. }) => {
+                assert_eq!((value, limit), (n, n - 1));
+            },
+            ret => panic!("{ret:?}"),
+        }
+    }
+
+    #[test]
+    fn remap_split_geosets() {
+        let mut data = MdlxData::default();
+        data.geoset_parts = vec![2, 1]; // geoset 0 -> 0 and 1, geoset 1 -> 2
+        data.geoanims = vec![
+            GeosetAnim { geoset_id: 0, alpha: 0.5, ..Default::default() },
+            GeosetAnim { geoset_id: 1, ..Default::default() },
+        ];
+        data.bones = vec![Bone { geoset_id: 1, ..Default::default() }, Bone::default()];
+        data.remap_split_geosets();
+
+        let ids: Vec<_> = data.geoanims.iter().map(|a| (a.geoset_id, a.alpha)).collect();
+        assert_eq!(ids, [(0, 0.5), (2, 1.0), (1, 0.5)]);
+        let ids: Vec<_> = data.bones.iter().map(|a| a.geoset_id).collect();
+        assert_eq!(ids, [2, -1]);
+    }
+}
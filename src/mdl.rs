@@ -1,23 +1,25 @@
 use crate::*;
 
 macro_rules! MdlReadType1 {
-    ($block:expr, $( $ty:ty => $var:expr ),+ $(,)?) => {
+    ($this:expr, $block:expr, $opts:expr, $( $ty:ty => $var:expr ),+ $(,)?) => {
         $(if $block.typ == stringify!($ty) {
-            $var = <$ty>::read_mdl(&$block).or_else(|e| ERR!("{}: {}", TNAME!($ty), e))?;
+            $var = <$ty>::read_mdl(&$block, $opts).map_err(|e| e.within(TNAME!($ty)))?;
+            $this.mdl_lines.insert(TNAME!($ty).s(), $block.line);
             return Ok(());
         })+
     };
 }
 macro_rules! MdlReadType2 {
-    ($block:expr, $( $ty:ty => $name:expr => $var:expr ),+ $(,)?) => {
+    ($this:expr, $block:expr, $opts:expr, $( $ty:ty => $name:expr => $var:expr ),+ $(,)?) => {
         $(if $block.typ == F!("{}s", stringify!($ty)) {
             $block.unexpect_fields()?;
             $block.unexpect_frames()?;
             for a in $block.blocks.iter() {
                 if a.typ == $name {
                     let name = yesno!(a.name.is_empty(), $var.len().s(), F!("{:?}",a.name));
-                    $var.push(<$ty>::read_mdl(a)
-                    .or_else(|e| ERR!("{}[{}]: {}", TNAME!($ty), name, e))?);
+                    $this.mdl_lines.insert(F!("{}[{}]", TNAME!($ty), $var.len()), a.line);
+                    $var.push(<$ty>::read_mdl(a, $opts)
+                    .map_err(|e| e.within(F!("{}[{}]", TNAME!($ty), name)))?);
                 } else {
                     return a.unexpect();
                 }
@@ -27,21 +29,25 @@ macro_rules! MdlReadType2 {
     };
 }
 macro_rules! MdlReadType3 {
-    ($block:expr, $( $ty:ty => $var:expr ),+ $(,)?) => {
-        $(if $block.typ == stringify!($ty) {
+    ($this:expr, $block:expr, $opts:expr, $( $ty:ty $(as $typ:literal)? => $var:expr ),+ $(,)?) => {
+        $(if $block.typ == MdlReadType3!(@typ $ty $(, $typ)?) {
             let name = yesno!($block.name.is_empty(), $var.len().s(), F!("{:?}",$block.name));
-            $var.push(<$ty>::read_mdl(&$block).or_else(|e| ERR!("{}[{}]: {}", TNAME!($ty), name, e))?);
+            $this.mdl_lines.insert(F!("{}[{}]", TNAME!($ty), $var.len()), $block.line);
+            $var.push(<$ty>::read_mdl(&$block, $opts).map_err(|e| e.within(F!("{}[{}]", TNAME!($ty), name)))?);
             return Ok(());
         })+
     };
+    (@typ $ty:ty) => { stringify!($ty) };
+    (@typ $ty:ty, $typ:literal) => { $typ };
 }
 macro_rules! MdlReadType4 {
-    ($block:expr, $( $ty:ty => $var:expr ),+ $(,)?) => {
+    ($this:expr, $block:expr, $opts:expr, $( $ty:ty => $var:expr ),+ $(,)?) => {
         $(if $block.typ == F!("{}s", stringify!($ty)) {
             $block.unexpect_frames()?;
             $block.unexpect_blocks()?;
             for a in $block.fields.iter() {
-                $var.push(<$ty>::read_mdl(a).or_else(|e| ERR!("{}: {}", TNAME!($ty), e))?);
+                $this.mdl_lines.insert(F!("{}[{}]", TNAME!($ty), $var.len()), a.line);
+                $var.push(<$ty>::read_mdl(a, $opts).map_err(|e| e.within(TNAME!($ty)))?);
             }
             return Ok(());
         })+
@@ -50,18 +56,31 @@ macro_rules! MdlReadType4 {
 
 #[macro_export]
 macro_rules! MdlWriteType1 {
-    ($lines:ident, $depth:expr, $( $var:expr ),+ $(,)?) => {
-        $( $lines.append(&mut $var.write_mdl($depth)?); )+
+    ($lines:ident, $depth:expr, $opts:expr, $ver:expr; $( $var:expr ),+ $(,)?) => {
+        $( $lines.append(&mut $var.write_mdl($depth, $opts, $ver)?); )+
+    };
+    ($lines:ident, $depth:expr, $opts:expr, $( $var:expr ),+ $(,)?) => {
+        $( $lines.append(&mut $var.write_mdl($depth, $opts)?); )+
     };
 }
 #[macro_export]
 macro_rules! MdlWriteType2 {
-    ($lines:ident, $depth:expr, $( $name:expr => $var:expr ),+ $(,)?) => {
+    ($lines:ident, $depth:expr, $opts:expr, $ver:expr; $( $name:expr => $var:expr ),+ $(,)?) => {
         $(if !$var.is_empty() {
-            let indent = indent!($depth);
+            let indent = $opts.indent($depth);
             $lines.push(F!("{indent}{} {} {{", $name, $var.len()));
             for a in $var.iter() {
-                MdlWriteType1!($lines, $depth+1, a);
+                MdlWriteType1!($lines, $depth+1, $opts, $ver; a);
+            }
+            $lines.push(F!("{indent}}}"));
+        })+
+    };
+    ($lines:ident, $depth:expr, $opts:expr, $( $name:expr => $var:expr ),+ $(,)?) => {
+        $(if !$var.is_empty() {
+            let indent = $opts.indent($depth);
+            $lines.push(F!("{indent}{} {} {{", $name, $var.len()));
+            for a in $var.iter() {
+                MdlWriteType1!($lines, $depth+1, $opts, a);
             }
             $lines.push(F!("{indent}}}"));
         })+
@@ -69,11 +88,20 @@ macro_rules! MdlWriteType2 {
 }
 #[macro_export]
 macro_rules! MdlWriteType3 {
-    ($lines:ident, $depth:expr, $( $name:expr => $var:expr ),+ $(,)?) => {
+    ($lines:ident, $depth:expr, $opts:expr, $ver:expr; $( $name:expr => $var:expr ),+ $(,)?) => {
         $(if !$var.is_empty() {
             for a in $var.iter() {
                 $lines.push(F!("{} {{", $name));
-                MdlWriteType1!($lines, $depth+1, a);
+                MdlWriteType1!($lines, $depth+1, $opts, $ver; a);
+                $lines.push(F!("}}"));
+            }
+        })+
+    };
+    ($lines:ident, $depth:expr, $opts:expr, $( $name:expr => $var:expr ),+ $(,)?) => {
+        $(if !$var.is_empty() {
+            for a in $var.iter() {
+                $lines.push(F!("{} {{", $name));
+                MdlWriteType1!($lines, $depth+1, $opts, a);
                 $lines.push(F!("}}"));
             }
         })+
@@ -81,64 +109,139 @@ macro_rules! MdlWriteType3 {
 }
 #[macro_export] // Nodes
 macro_rules! MdlWriteType4 {
-    ($lines:ident, $depth:expr, $member:expr, $( $name:expr => $var:expr ),+ $(,)?) => {
+    ($lines:ident, $depth:expr, $opts:expr, $member:expr, $( $name:expr => $var:expr ),+ $(,)?) => {
         $(if !$var.is_empty() {
             for a in $var.iter() {
                 paste!{ $lines.push(F!("{} \"{}\" {{", $name, a.$member.escape())); }
-                MdlWriteType1!($lines, $depth+1, a);
+                MdlWriteType1!($lines, $depth+1, $opts, a);
                 $lines.push(F!("}}"));
             }
         })+
     };
 }
 
+//#region MdlReadOptions, MdlWriteOptions
+
+#[derive(Debug, Default, Clone)]
+pub struct MdlReadOptions {
+    pub rgb: bool,           // colors are in RGB order (instead of BGR)
+    pub salvage: bool,       // read damaged mdx files as far as possible (instead of failing)
+    pub split_geosets: bool, // split geosets over the engine limits (instead of failing)
+}
+
+#[derive(Debug, SmartDefault, Clone)]
+pub struct MdlWriteOptions {
+    #[default("\t".s())]
+    pub indent: String, // one level of indentation
+    #[default("\r\n".s())]
+    pub line_ending: String,
+    #[default(4)]
+    pub precision: u8, // max precision of decimal numbers
+    pub lossless: bool, // write decimal numbers exactly (ignore precision)
+    pub rgb: bool,      // write colors in RGB order (instead of BGR)
+}
+
+impl MdlWriteOptions {
+    pub fn indent(&self, depth: u8) -> String {
+        self.indent.repeat(depth as usize)
+    }
+}
+
+//#endregion
+
 #[derive(Parser)]
 #[grammar = "mdl.pest"]
 pub struct MdlParser;
 
 impl MdlxData {
-    pub fn write_mdl(&mut self, path: &Path) -> Result<(), MyError> {
+    pub fn from_mdl_str(input: &str, opts: &MdlReadOptions) -> Result<Self, MyError> {
+        Self::read_mdl(input, opts)
+    }
+    pub fn read_mdl_from<R: Read>(reader: &mut R, opts: &MdlReadOptions) -> Result<Self, MyError> {
+        let mut input = String::new();
+        reader.read_to_string(&mut input)?;
+        Self::read_mdl(&input, opts)
+    }
+
+    pub fn write_mdl(&self, path: &Path, opts: &MdlWriteOptions) -> Result<(), MyError> {
+        Ok(std::fs::write(path, self.mdl_text(path.file_name(), opts)?)?)
+    }
+    pub fn write_mdl_to<W: Write>(
+        &self,
+        writer: &mut W,
+        opts: &MdlWriteOptions,
+    ) -> Result<(), MyError> {
+        Ok(writer.write_all(self.to_mdl_string(opts)?.as_bytes())?)
+    }
+
+    pub fn to_mdl_string(&self, opts: &MdlWriteOptions) -> Result<String, MyError> {
+        self.mdl_text(None, opts)
+    }
+    fn mdl_text(
+        &self,
+        file_name: Option<&OsStr>,
+        opts: &MdlWriteOptions,
+    ) -> Result<String, MyError> {
         let mut lines: Vec<String> = vec![];
-        Self::write_mdl_comment(&mut lines, path);
+        Self::write_mdl_comment(&mut lines, file_name);
 
-        MdlWriteType1!(lines, 0, self.version, self.model);
-        MdlWriteType2!(lines, 0,
+        let version = self.version.format_version;
+        MdlWriteType1!(lines, 0, opts, self.version, self.model);
+        MdlWriteType2!(lines, 0, opts,
             "Sequences"         => self.sequences,
             "GlobalSequences"   => self.globalseqs,
             "Textures"          => self.textures,
+        );
+        MdlWriteType2!(lines, 0, opts, version;
             "Materials"         => self.materials,
+        );
+        MdlWriteType2!(lines, 0, opts,
             "TextureAnims"      => self.texanims,
             "PivotPoints"       => self.pivot_points,
         );
-        MdlWriteType3!(lines, 0,
+        MdlWriteType3!(lines, 0, opts, version;
             "Geoset"            => self.geosets,
+        );
+        MdlWriteType3!(lines, 0, opts,
             "GeosetAnim"        => self.geoanims,
         );
-        MdlWriteType4!(lines, 0, base.name,
+        MdlWriteType4!(lines, 0, opts, base.name,
             "Bone"              => self.bones,
             "Light"             => self.lights,
             "Helper"            => self.helpers,
             "Attachment"        => self.attachments,
             "ParticleEmitter"   => self.particle_emitters,
             "ParticleEmitter2"  => self.particle_emitters2,
+            "ParticleEmitterPopcorn" => self.popcorn_emitters,
             "RibbonEmitter"     => self.ribbon_emitters,
             "EventObject"       => self.eventobjs,
             "CollisionShape"    => self.collisions,
         );
-        MdlWriteType4!(lines, 0, name, "Camera" => self.cameras );
+        MdlWriteType4!(lines, 0, opts, name,
+            "Camera"            => self.cameras,
+            "FaceFX"            => self.face_effects,
+        );
+        if !self.bind_pose.is_empty() {
+            MdlWriteType1!(lines, 0, opts, self.bind_pose);
+        }
+        for chunk in &self.unknown_chunks {
+            lines.append(&mut chunk.write_mdl(0, opts)?);
+        }
 
-        let line_ending = line_ending!();
-        let text = lines.join(line_ending) + line_ending;
-        return Ok(std::fs::write(path, text)?);
+        let line_ending = &opts.line_ending;
+        return Ok(lines.join(line_ending) + line_ending);
     }
-    fn write_mdl_comment(lines: &mut Vec<String>, path: &Path) {
+    fn write_mdl_comment(lines: &mut Vec<String>, file_name: Option<&OsStr>) {
         let width = 48;
-        let comments = vec![
-            F!("{:?}", path.file_name().unwrap()),
+        let mut comments = vec![];
+        if let Some(name) = file_name {
+            comments.push(F!("{:?}", name));
+        }
+        comments.append(&mut vec![
             F!("Generated by {} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
             StartTime.format("%Y-%m-%d %H:%M:%S (%Z)").to_string(),
             F!("{}", env!("CARGO_PKG_HOMEPAGE")),
-        ];
+        ]);
         lines.push(F!("//+{}+", "-".repeat(width)));
         for c in comments {
             lines.push(F!("//|{:^w$}|", c, w = width));
@@ -146,37 +249,50 @@ impl MdlxData {
         lines.push(F!("//+{}+", "-".repeat(width)));
     }
 
-    pub fn read_mdl(input: &str) -> Result<Self, MyError> {
-        let mdl = MdlParser::parse(Rule::file, input).map_err(|e| F!("Failed to parse mdl: {}", e))?;
+    pub fn read_mdl(input: &str, opts: &MdlReadOptions) -> Result<Self, MyError> {
+        let mdl = MdlParser::parse(Rule::file, input)?;
         let mut this = MdlxData::default();
 
         for pair in mdl {
             if let Rule::file = pair.as_rule() {
                 for p in pair.into_inner() {
                     if let Rule::block = p.as_rule() {
-                        this.parse_mdl_block(MdlBlock::from(p, "")?)?;
+                        this.parse_mdl_block(MdlBlock::from(p, "")?, opts)?;
                     }
                 }
                 break; // only 1 [file] rule
             }
         }
 
+        this.after_read();
+        this.remap_split_geosets();
         return Ok(this);
     }
 
-    fn parse_mdl_block(&mut self, block: MdlBlock) -> Result<(), MyError> {
-        MdlReadType1!(block,
+    fn parse_mdl_block(&mut self, block: MdlBlock, opts: &MdlReadOptions) -> Result<(), MyError> {
+        MdlReadType1!(self, block, opts,
             Version     => self.version,
             Model       => self.model,
+            BindPose    => self.bind_pose,
         );
-        MdlReadType2!(block,
+        MdlReadType2!(self, block, opts,
             Sequence    => "Anim"       => self.sequences,
             Texture     => "Bitmap"     => self.textures,
             TextureAnim => "TVertexAnim"=> self.texanims,
             Material    => "Material"   => self.materials,
         );
-        MdlReadType3!(block,
-            Geoset          => self.geosets,
+        if block.typ == "Geoset" {
+            let idx = self.geoset_parts.len();
+            let parts = Geoset::read_mdl_split(&block, opts);
+            let mut parts = parts.map_err(|e| e.within(F!("Geoset[{idx}]")))?;
+            for i in 0..parts.len() {
+                self.mdl_lines.insert(F!("Geoset[{}]", self.geosets.len() + i), block.line);
+            }
+            self.geoset_parts.push(parts.len());
+            self.geosets.append(&mut parts);
+            return Ok(());
+        }
+        MdlReadType3!(self, block, opts,
             GeosetAnim      => self.geoanims,
             Bone            => self.bones,
             Light           => self.lights,
@@ -184,12 +300,15 @@ impl MdlxData {
             Attachment      => self.attachments,
             ParticleEmitter => self.particle_emitters,
             ParticleEmitter2=> self.particle_emitters2,
+            PopcornEmitter as "ParticleEmitterPopcorn" => self.popcorn_emitters,
             RibbonEmitter   => self.ribbon_emitters,
             EventObject     => self.eventobjs,
             CollisionShape  => self.collisions,
             Camera          => self.cameras,
+            FaceEffect as "FaceFX" => self.face_effects,
+            UnknownChunk    => self.unknown_chunks,
         );
-        MdlReadType4!(block,
+        MdlReadType4!(self, block, opts,
             GlobalSequence  => self.globalseqs,
             PivotPoint      => self.pivot_points,
         );
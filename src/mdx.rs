@@ -4,7 +4,7 @@ macro_rules! MdxReadType1 {
     ($chunk:expr, $cur:expr, $( $ty:ty => $var:expr ),+ $(,)?) => {
         $(if $chunk.id == <$ty>::ID {
             $var = <$ty>::read_mdx(&mut $cur)
-            .or_else(|e| ERR!("{}({}): {}", TNAME!($ty), u32_to_ascii(<$ty>::ID), e))?;
+            .map_err(|e| e.within(F!("{}({})", TNAME!($ty), u32_to_ascii(<$ty>::ID))))?;
             return Ok(());
         })+
     };
@@ -14,8 +14,8 @@ macro_rules! MdxReadType2 {
         $(if $chunk.id == <$ty>::ID {
             while !$cur.eol() {
                 $var.push(
-                    <$ty>::read_mdx(&mut $cur).or_else(|e| {
-                        ERR!("{}({})[{}th]: {}", TNAME!($ty), u32_to_ascii(<$ty>::ID), $var.len(), e)
+                    <$ty>::read_mdx(&mut $cur).map_err(|e| {
+                        e.within(F!("{}({})[{}th]", TNAME!($ty), u32_to_ascii(<$ty>::ID), $var.len()))
                     })?,
                 );
             }
@@ -24,72 +24,146 @@ macro_rules! MdxReadType2 {
     };
 }
 macro_rules! MdxReadType3 {
-    ($chunk:expr, $cur:expr, $( $ty:ty => $var:expr ),+ $(,)?) => {
-        $(if $chunk.id == <$ty>::ID {
+    ($chunk:expr, $cur:expr, $repairs:expr, $ver:expr; $( $ty:ty => $var:expr ),+ $(,)?) => {
+        $( MdxReadType3!(@ $chunk, $cur, $repairs, $ty => $var, |c| <$ty>::read_mdx(c, $ver)); )+
+    };
+    ($chunk:expr, $cur:expr, $repairs:expr; $( $ty:ty => $var:expr ),+ $(,)?) => {
+        $( MdxReadType3!(@ $chunk, $cur, $repairs, $ty => $var, |c| <$ty>::read_mdx(c)); )+
+    };
+    // $repairs: `Some` in salvage mode
+    (@ $chunk:expr, $cur:expr, $repairs:expr, $ty:ty => $var:expr, $read:expr) => {
+        if $chunk.id == <$ty>::ID {
+            let truncated = |need, cur: &Cursor<&Vec<u8>>| MyError::TruncatedChunk {
+                id: $chunk.id,
+                offset: cur.pos(),
+                need,
+                left: cur.left(),
+            };
             while !$cur.eol() {
                 let left = $cur.left();
-                yes!(left < 4, EXIT!("{} size: {}B left (need 4)", TNAME!($ty), left));
+                if left < 4 {
+                    let e = truncated(4, &$cur).within(F!("{} size", TNAME!($ty)));
+                    MdxReadType3!(@drop $repairs, e);
+                }
                 let sz = $cur.readx::<u32>()?;
-                yes!(sz < 4, EXIT!("{} size: {} (need >= 4)", TNAME!($ty), sz));
-                let sz = sz - 4;
+                if sz < 4 {
+                    let e = MyError::String(F!("{} size: {} (need >= 4)", TNAME!($ty), sz));
+                    MdxReadType3!(@drop $repairs, e);
+                }
+                let mut sz = sz - 4;
 
                 let left = $cur.left();
-                yes!(left < sz, EXIT!("{} body: {}B left (need {})", TNAME!($ty), left, sz));
-                let body = $cur.read_bytes(sz).or_else(|e| ERR!("{} body({}B): {}", TNAME!($ty), sz, e))?;
+                if left < sz {
+                    let e = truncated(sz, &$cur).within(F!("{} body", TNAME!($ty)));
+                    let Some(repairs) = $repairs.as_deref_mut() else { return Err(e) };
+                    let i = $var.len();
+                    REPAIR!(repairs, "{}[{}th] body: {}B left (need {}), clamped", TNAME!($ty), i, left, sz);
+                    sz = left;
+                }
+                let body = $cur.read_bytes(sz).map_err(|e| e.within(F!("{} body({}B)", TNAME!($ty), sz)))?;
 
                 let mut cur2 = Cursor::new(&body);
-                $var.push(
-                    <$ty>::read_mdx(&mut cur2).or_else(|e| {
-                        ERR!("{}({})[{}th]: {}", TNAME!($ty), u32_to_ascii(<$ty>::ID), $var.len(), e)
-                    })?,
-                );
+                let ret = ($read)(&mut cur2).map_err(|e| {
+                    e.within(F!("{}({})[{}th]", TNAME!($ty), u32_to_ascii(<$ty>::ID), $var.len()))
+                });
+                match (ret, $repairs.as_deref_mut()) {
+                    (Ok(a), _) => $var.push(a),
+                    (Err(e), Some(repairs)) => REPAIR!(repairs, "{}, dropped", e),
+                    (Err(e), None) => return Err(e),
+                }
             }
             EXIT!();
-        })+
+        }
     };
+    (@drop $repairs:expr, $e:expr) => {{
+        let Some(repairs) = $repairs.as_deref_mut() else { return Err($e) };
+        REPAIR!(repairs, "{}, dropped the rest", $e);
+        EXIT!();
+    }};
 }
 
 macro_rules! MdxWriteType1 {
-    ($cur:expr, $( $ty:ty => $var:expr ),+ $(,)?) => {
+    ($cur:expr, $unknown:expr; $( $ty:ty => $var:expr ),+ $(,)?) => {
         $(
             let mut chunk = MdxChunk::new(<$ty>::ID);
             $var.write_mdx(&mut chunk)
-            .or_else(|e| ERR!("{}: {}", TNAME!($ty), e))?;
+            .map_err(|e| e.within(TNAME!($ty)))?;
             chunk.flush_to(&mut $cur)?;
+            UnknownChunk::write_after($unknown, <$ty>::ID, &mut $cur)?;
         )+
     };
 }
 macro_rules! MdxWriteType2 {
-    ($cur:expr, $( $ty:ty => $var:expr ),+ $(,)?) => {
+    ($cur:expr, $unknown:expr, $ver:expr; $( $ty:ty => $var:expr ),+ $(,)?) => {
         $(if !$var.is_empty() {
             let mut chunk = MdxChunk::new(<$ty>::ID);
             for a in $var.iter() {
-                a.write_mdx(&mut chunk).or_else(|e| ERR!("{}: {}", TNAME!($ty), e))?;
+                a.write_mdx(&mut chunk, $ver).map_err(|e| e.within(TNAME!($ty)))?;
             }
             chunk.flush_to(&mut $cur)?;
-        })+
+        }
+        UnknownChunk::write_after($unknown, <$ty>::ID, &mut $cur)?;)+
+    };
+    ($cur:expr, $unknown:expr; $( $ty:ty => $var:expr ),+ $(,)?) => {
+        $(if !$var.is_empty() {
+            let mut chunk = MdxChunk::new(<$ty>::ID);
+            for a in $var.iter() {
+                a.write_mdx(&mut chunk).map_err(|e| e.within(TNAME!($ty)))?;
+            }
+            chunk.flush_to(&mut $cur)?;
+        }
+        UnknownChunk::write_after($unknown, <$ty>::ID, &mut $cur)?;)+
     };
 }
 
 impl MdlxData {
+    pub fn from_mdx_bytes(input: &[u8]) -> Result<Self, MyError> {
+        Self::read_mdx(&input.to_vec())
+    }
+    pub fn read_mdx_from<R: Read>(reader: &mut R) -> Result<Self, MyError> {
+        let mut input = vec![];
+        reader.read_to_end(&mut input)?;
+        Self::read_mdx(&input)
+    }
+
     pub fn write_mdx(&self, path: &Path) -> Result<(), MyError> {
+        Ok(std::fs::write(path, self.to_mdx_bytes()?)?)
+    }
+    pub fn write_mdx_to<W: Write>(&self, writer: &mut W) -> Result<(), MyError> {
+        Ok(writer.write_all(&self.to_mdx_bytes()?)?)
+    }
+
+    pub fn to_mdx_bytes(&self) -> Result<Vec<u8>, MyError> {
+        self.check_limits()?;
         let mut cur = Cursor::new(Vec::<u8>::with_capacity(0x40000_usize));
 
         if let Err(e) = cur.write_be(&MdlxMagic::MDLX) {
             EXIT1!("writing magic: {}", e);
         }
 
-        MdxWriteType1!(cur,
+        // unknown chunks go back after the known chunk they followed
+        let unknown = &self.unknown_chunks;
+        UnknownChunk::write_after(unknown, 0, &mut cur)?;
+        MdxWriteType1!(cur, unknown;
             Version         => self.version,
             Model           => self.model,
         );
-        MdxWriteType2!(cur,
+        let version = self.version.format_version;
+        MdxWriteType2!(cur, unknown;
             Sequence        => self.sequences,
             GlobalSequence  => self.globalseqs,
+        );
+        MdxWriteType2!(cur, unknown, version;
             Material        => self.materials,
+        );
+        MdxWriteType2!(cur, unknown;
             Texture         => self.textures,
             TextureAnim     => self.texanims,
+        );
+        MdxWriteType2!(cur, unknown, version;
             Geoset          => self.geosets,
+        );
+        MdxWriteType2!(cur, unknown;
             GeosetAnim      => self.geoanims,
             Bone            => self.bones,
             Light           => self.lights,
@@ -98,40 +172,90 @@ impl MdlxData {
             PivotPoint      => self.pivot_points,
             ParticleEmitter => self.particle_emitters,
             ParticleEmitter2=> self.particle_emitters2,
+            PopcornEmitter  => self.popcorn_emitters,
             RibbonEmitter   => self.ribbon_emitters,
             Camera          => self.cameras,
             EventObject     => self.eventobjs,
             CollisionShape  => self.collisions,
+            FaceEffect      => self.face_effects,
         );
+        if !self.bind_pose.is_empty() {
+            MdxWriteType1!(cur, unknown; BindPose => self.bind_pose);
+        } else {
+            UnknownChunk::write_after(unknown, BindPose::ID, &mut cur)?;
+        }
+        for u in unknown.iter().filter(|u| u.after != 0 && !MdlxMagic::CHUNKS.contains(&u.after)) {
+            u.chunk.copy_to(&mut cur)?; // after no known chunk
+        }
 
-        std::fs::write(path, cur.into_inner())?;
-        EXIT!();
+        return Ok(cur.into_inner());
+    }
+
+    /// Read a damaged mdx file as far as possible, see [`MdlxData::repairs`].
+    pub fn read_mdx_salvage(input: &Vec<u8>) -> Result<Self, MyError> {
+        let mut repairs = vec![];
+        let mut this = Self::read_mdx_with(input, Some(&mut repairs))?;
+        this.repairs = repairs;
+        return Ok(this);
     }
 
     pub fn read_mdx(input: &Vec<u8>) -> Result<Self, MyError> {
+        Self::read_mdx_with(input, None)
+    }
+
+    /// `repairs`: `Some` in salvage mode, collects the repairs made.
+    fn read_mdx_with(
+        input: &Vec<u8>,
+        mut repairs: Option<&mut Vec<String>>,
+    ) -> Result<Self, MyError> {
         let mut this = MdlxData::default();
         let mut cur = Cursor::new(input);
 
         let magic = cur.read_be::<u32>().unwrap_or(0);
         if magic != MdlxMagic::MDLX as u32 {
-            return ERR!("Invalid magic: 0x{:08X} ({})", magic, u32_to_ascii(magic));
+            let Some(repairs) = repairs.as_deref_mut() else {
+                return ERR!("Invalid magic: 0x{:08X} ({})", magic, u32_to_ascii(magic));
+            };
+            REPAIR!(repairs, "Invalid magic: 0x{:08X} ({}), ignored", magic, u32_to_ascii(magic));
         }
 
+        let mut after = 0; // the last known chunk, for unknown chunks
         while !cur.eol() {
-            let chunk = MdxChunk::read_mdx(&mut cur)?;
-            this.parse_mdx_chunk(&chunk)?;
+            let Some(repairs) = repairs.as_deref_mut() else {
+                let chunk = MdxChunk::read_mdx(&mut cur)?;
+                this.parse_mdx_chunk(&chunk, after, None)?;
+                yes!(MdlxMagic::CHUNKS.contains(&chunk.id), after = chunk.id);
+                continue;
+            };
+            if let Some(chunk) = MdxChunk::read_mdx_salvage(&mut cur, repairs)? {
+                if let Err(e) = this.parse_mdx_chunk(&chunk, after, Some(repairs)) {
+                    REPAIR!(repairs, "{}, dropped the rest of chunk {}", e, u32_to_ascii(chunk.id));
+                }
+                yes!(MdlxMagic::CHUNKS.contains(&chunk.id), after = chunk.id);
+            }
         }
+        this.after_read();
 
         vvvlog!("{}", F!("{:#?}", &this).replace("    ", "  "));
         return Ok(this);
     }
 
-    fn parse_mdx_chunk(&mut self, chunk: &MdxChunk) -> Result<(), MyError> {
+    fn parse_mdx_chunk(
+        &mut self,
+        chunk: &MdxChunk,
+        after: u32,
+        mut repairs: Option<&mut Vec<String>>,
+    ) -> Result<(), MyError> {
         let mut cur = Cursor::new(&chunk.body);
         MdxReadType1!(chunk, cur,
             Version         => self.version,
             Model           => self.model,
         );
+        if chunk.id == BindPose::ID {
+            self.bind_pose = BindPose::read_mdx(&mut cur, repairs)
+                .map_err(|e| e.within(F!("BindPose({})", u32_to_ascii(BindPose::ID))))?;
+            EXIT!();
+        }
         MdxReadType2!(chunk, cur,
             Sequence        => self.sequences,
             GlobalSequence  => self.globalseqs,
@@ -141,19 +265,28 @@ impl MdlxData {
             EventObject     => self.eventobjs,
             CollisionShape  => self.collisions,
             PivotPoint      => self.pivot_points,
+            FaceEffect      => self.face_effects,
         );
-        MdxReadType3!(chunk, cur,
-            TextureAnim     => self.texanims,
+        let version = self.version.format_version;
+        MdxReadType3!(chunk, cur, repairs, version;
             Material        => self.materials,
             Geoset          => self.geosets,
+        );
+        MdxReadType3!(chunk, cur, repairs;
+            TextureAnim     => self.texanims,
             GeosetAnim      => self.geoanims,
             Attachment      => self.attachments,
             Light           => self.lights,
             ParticleEmitter => self.particle_emitters,
             ParticleEmitter2=> self.particle_emitters2,
+            PopcornEmitter  => self.popcorn_emitters,
             RibbonEmitter   => self.ribbon_emitters,
             Camera          => self.cameras,
         );
+
+        vlog!("Unknown chunk 0x{:08X} ({}), kept as is", chunk.id, u32_to_ascii(chunk.id));
+        self.unknown_chunks
+            .push(UnknownChunk { after, chunk: MdxChunk::from_raw(chunk.id, chunk.body.clone()) });
         return Ok(());
     }
 }
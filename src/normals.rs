@@ -0,0 +1,280 @@
+use crate::*;
+
+//#region NormalOptions
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NormalWeighting {
+    #[default]
+    Area, // by the area of each face
+    Angle, // by the angle of each face at the vertex
+}
+
+#[derive(Debug, SmartDefault, Clone)]
+pub struct NormalOptions {
+    pub weighting: NormalWeighting,
+    /// Edges sharper than this (in degrees) are hard, their vertices are split.
+    /// 180 means smooth everywhere.
+    #[default(180.0)]
+    pub crease_angle: f32,
+}
+
+//#endregion
+//#region recalc_normals
+
+impl MdlxData {
+    /// Rebuild normals of all geosets, see `Geoset::recalc_normals`.
+    /// Nothing changes if any geoset fails.
+    pub fn recalc_normals(&mut self, opts: &NormalOptions) -> Result<(), MyError> {
+        let mut geosets = self.geosets.clone();
+        for (i, g) in geosets.iter_mut().enumerate() {
+            let split = g.recalc_normals(opts).map_err(|e| e.within(F!("Geoset[{i}]")))?;
+            yes!(split > 0, vlog!("Geoset[{i}]: split {split} vertices at hard edges."));
+        }
+        self.geosets = geosets;
+        return Ok(());
+    }
+}
+
+impl Geoset {
+    /// Rebuild normals from triangles (other face types are ignored),
+    /// smooth across vertices at the same position. Vertices on hard edges
+    /// (see `NormalOptions::crease_angle`) are duplicated, with all their attributes.
+    /// Return the number of added vertices, or fail without changes if they are too many.
+    pub fn recalc_normals(&mut self, opts: &NormalOptions) -> Result<usize, MyError> {
+        let nvert = self.vertices.len();
+        let tris = self.triangle_corners();
+
+        // weld by position, so that uv seams are not hard edges
+        let mut firsts: HashMap<[u32; 3], usize> = HashMap::new();
+        let weld: Vec<usize> = (0..nvert)
+            .map(|v| *firsts.entry(self.vertices[v].to_array().map(f32::to_bits)).or_insert(v))
+            .collect();
+
+        let pos = |c: usize| self.vertices[self.face_vertices[c] as usize];
+        let mut face_normals: Vec<Vec3> = vec![];
+        let mut weights: Vec<[f32; 3]> = vec![];
+        for t in tris.iter() {
+            let (p0, p1, p2) = (pos(t[0]), pos(t[1]), pos(t[2]));
+            let cross = (p1 - p0).cross(p2 - p0);
+            face_normals.push(cross.normalize_or_zero());
+            weights.push(match opts.weighting {
+                NormalWeighting::Area => [cross.length(); 3],
+                NormalWeighting::Angle => [
+                    (p1 - p0).angle_between(p2 - p0),
+                    (p2 - p1).angle_between(p0 - p1),
+                    (p0 - p2).angle_between(p1 - p2),
+                ],
+            });
+        }
+
+        // corners around each welded vertex, clustered by smooth edges
+        // [welded vertex] => (triangle, k)
+        let mut corners: Vec<Vec<(usize, usize)>> = vec![vec![]; nvert];
+        for (i, t) in tris.iter().enumerate() {
+            for k in 0..3 {
+                corners[weld[self.face_vertices[t[k]] as usize]].push((i, k));
+            }
+        }
+        let welded_of = |i: usize| tris[i].map(|c| weld[self.face_vertices[c] as usize]);
+        let smooth_all = opts.crease_angle >= 180.0;
+        let cos_crease = opts.crease_angle.to_radians().cos();
+        // corner => (normal, cluster)
+        let mut corner_normal: HashMap<usize, (Vec3, usize)> = HashMap::new();
+        for (w, list) in corners.iter().enumerate() {
+            let mut parent: Vec<usize> = (0..list.len()).collect();
+            fn root(parent: &mut [usize], mut i: usize) -> usize {
+                while parent[i] != i {
+                    parent[i] = parent[parent[i]];
+                    i = parent[i];
+                }
+                return i;
+            }
+            for i in 0..list.len() {
+                for j in i + 1..list.len() {
+                    let (fi, fj) = (list[i].0, list[j].0);
+                    let (vi, vj) = (welded_of(fi), welded_of(fj));
+                    let share_edge = vi.iter().any(|v| *v != w && vj.contains(v));
+                    if smooth_all
+                        || (share_edge && face_normals[fi].dot(face_normals[fj]) >= cos_crease)
+                    {
+                        let (ri, rj) = (root(&mut parent, i), root(&mut parent, j));
+                        parent[ri] = rj;
+                    }
+                }
+            }
+            let mut sums: HashMap<usize, Vec3> = HashMap::new();
+            for (i, &(f, k)) in list.iter().enumerate() {
+                *sums.entry(root(&mut parent, i)).or_default() += face_normals[f] * weights[f][k];
+            }
+            for (i, &(f, k)) in list.iter().enumerate() {
+                let r = root(&mut parent, i);
+                let n = sums[&r].try_normalize().unwrap_or(face_normals[f]);
+                corner_normal.insert(tris[f][k], (n, r));
+            }
+        }
+
+        // assign normals, split vertices which are in more than one cluster
+        let mut normals =
+            yesno!(self.normals.len() == nvert, self.normals.clone(), vec![Vec3::Z; nvert]);
+        let mut owner: Vec<Option<usize>> = vec![None; nvert]; // cluster which keeps the vertex
+        // (vertex, cluster) => copy
+        let mut copies: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut sources: Vec<usize> = vec![]; // vertex of each copy
+        let mut remap: Vec<(usize, usize)> = vec![]; // (corner, copy)
+        for t in tris.iter() {
+            for &c in t.iter() {
+                let (v, (n, cluster)) = (self.face_vertices[c] as usize, corner_normal[&c]);
+                match owner[v] {
+                    None => {
+                        owner[v] = Some(cluster);
+                        normals[v] = n;
+                    },
+                    Some(o) if o == cluster => {},
+                    Some(_) => {
+                        let copy = *copies.entry((v, cluster)).or_insert_with(|| {
+                            normals.push(n);
+                            sources.push(v);
+                            nvert + sources.len() - 1
+                        });
+                        remap.push((c, copy));
+                    },
+                }
+            }
+        }
+
+        // only change anything once the copies are known to fit
+        let count = nvert + sources.len();
+        yes!(
+            !sources.is_empty() && count > Self::MAX_VERTICES,
+            return Err(Self::too_many_vertices(count))
+        );
+        for &v in sources.iter() {
+            self.duplicate_vertex(v);
+        }
+        for (c, copy) in remap {
+            self.face_vertices[c] = copy as u16;
+        }
+        self.normals = normals;
+        return Ok(sources.len());
+    }
+
+    /// Corners (indices of face_vertices) of each triangle,
+    /// those referring to missing vertices are ignored.
+    pub(crate) fn triangle_corners(&self) -> Vec<[usize; 3]> {
+        let (nvert, mut tris, mut start) = (self.vertices.len(), vec![], 0_usize);
+        for (t, n) in self.face_types.iter().zip(self.face_vtxcnts.iter()) {
+            let end = (start + (*n).max(0) as usize).min(self.face_vertices.len());
+            if *t == FaceType::Triangles {
+                for c in (start..end).step_by(3).filter(|c| c + 2 < end) {
+                    let t = [c, c + 1, c + 2];
+                    yes!(t.iter().all(|&c| (self.face_vertices[c] as usize) < nvert), tris.push(t));
+                }
+            }
+            start = end;
+        }
+        return tris;
+    }
+
+    /// Append a copy of vertex `v` with all its attributes (except normal), return the new index.
+    fn duplicate_vertex(&mut self, v: usize) -> usize {
+        let n = self.vertices.len();
+        for uvs in self.uvss.iter_mut() {
+            yes!(uvs.len() == n, uvs.push(uvs[v]));
+        }
+        yes!(self.vtxgrps.len() == n, self.vtxgrps.push(self.vtxgrps[v]));
+        yes!(self.tangents.len() == n, self.tangents.push(self.tangents[v]));
+        if self.skin_weights.len() == n * Self::SKIN_WEIGHT_SIZE {
+            let w = Self::SKIN_WEIGHT_SIZE;
+            self.skin_weights.extend_from_within(v * w..v * w + w);
+        }
+        self.vertices.push(self.vertices[v]);
+        return n;
+    }
+
+    fn too_many_vertices(value: usize) -> MyError {
+        let (owner, field) = (TNAME!().s(), "vertex count after splitting".s());
+        MyError::LimitExceeded { owner, field, value, limit: Self::MAX_VERTICES }
+    }
+}
+
+//#endregion
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two unit triangles folded at a right angle along the edge 0-1,
+    /// `extra` unused vertices after them.
+    fn fold(extra: usize) -> Geoset {
+        let mut g = Geoset::default();
+        g.vertices = vec![Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::Z];
+        g.vertices.extend(vec![Vec3::splat(9.0); extra]);
+        g.uvss = vec![(0..g.vertices.len()).map(|i| Vec2::new(i as f32, 0.0)).collect()];
+        g.face_types = vec![FaceType::Triangles];
+        g.face_vtxcnts = vec![6];
+        g.face_vertices = vec![0, 1, 2, 1, 0, 3];
+        return g;
+    }
+
+    fn opts(crease_angle: f32) -> NormalOptions {
+        NormalOptions { crease_angle, ..Default::default() }
+    }
+
+    #[test]
+    fn smooth() {
+        let mut g = fold(0);
+        assert_eq!(g.recalc_normals(&opts(180.0)).unwrap(), 0);
+        let n = (Vec3::Y + Vec3::Z).normalize();
+        assert_eq!(g.face_vertices, [0, 1, 2, 1, 0, 3]);
+        assert!(
+            g.normals.iter().zip([n, n, Vec3::Z, Vec3::Y]).all(|(a, b)| a.abs_diff_eq(b, 1e-6))
+        );
+    }
+
+    #[test]
+    fn crease_split() {
+        let mut g = fold(0);
+        assert_eq!(g.recalc_normals(&opts(60.0)).unwrap(), 2);
+        // the second triangle gets copies of 1 and 0, with their uvs
+        assert_eq!(g.face_vertices, [0, 1, 2, 4, 5, 3]);
+        assert_eq!(g.vertices[4..], [Vec3::X, Vec3::ZERO]);
+        assert_eq!(g.uvss[0][4..], [Vec2::new(1.0, 0.0), Vec2::ZERO]);
+        assert_eq!(g.normals, [Vec3::Z, Vec3::Z, Vec3::Z, Vec3::Y, Vec3::Y, Vec3::Y]);
+        // a shallower fold stays smooth
+        assert_eq!(fold(0).recalc_normals(&opts(100.0)).unwrap(), 0);
+    }
+
+    #[test]
+    fn duplicate_vertex() {
+        let mut g = fold(0);
+        g.uvss.push(vec![Vec2::ONE; 3]); // not one per vertex, left alone
+        g.vtxgrps = vec![0, 1, 2, 3];
+        g.tangents = (0..4).map(|i| Vec4::splat(i as f32)).collect();
+        g.skin_weights = (0..32).collect();
+        assert_eq!(g.duplicate_vertex(2), 4);
+
+        assert_eq!(g.vertices[4], Vec3::Y);
+        assert_eq!((g.uvss[0][4], g.uvss[1].len()), (Vec2::new(2.0, 0.0), 3));
+        assert_eq!((g.vtxgrps[4], g.tangents[4]), (2, Vec4::splat(2.0)));
+        assert_eq!(g.skin_weights[32..], [16, 17, 18, 19, 20, 21, 22, 23]);
+    }
+
+    #[test]
+    fn too_many_vertices() {
+        // 2 copies would make one more than the limit
+        let mut g = fold(Geoset::MAX_VERTICES - 5);
+        assert!(g.recalc_normals(&opts(60.0)).is_err());
+        assert_eq!(g.vertices.len(), Geoset::MAX_VERTICES - 1);
+        assert_eq!(g.face_vertices, [0, 1, 2, 1, 0, 3]);
+        assert!(g.normals.is_empty());
+
+        // nor does any other geoset change
+        let mut data = MdlxData::default();
+        data.geosets = vec![fold(0), fold(Geoset::MAX_VERTICES - 5)];
+        assert!(data.recalc_normals(&opts(60.0)).is_err());
+        assert_eq!(data.geosets[0].vertices.len(), 4);
+        assert!(data.geosets[0].normals.is_empty());
+        assert!(data.recalc_normals(&opts(180.0)).is_ok());
+        assert_eq!(data.geosets[0].normals.len(), 4);
+    }
+}
@@ -0,0 +1,487 @@
+use crate::*;
+
+//#region write_obj
+
+impl MdlxData {
+    /// Write geosets as Wavefront OBJ (one object per geoset), with a *.mtl of the same name next
+    /// to it. Geometry is in the bind pose, or posed at a frame of a sequence by `eopts.pose`,
+    /// leaving out the geosets hidden by GeosetAnim alpha then.
+    pub fn write_obj(
+        &self,
+        path: &Path,
+        opts: &MdlWriteOptions,
+        eopts: &ExportOptions,
+    ) -> Result<(), MyError> {
+        let mtl_path = path.with_extension("mtl");
+        let mtllib = mtl_path.file_name().map_or("".s(), |s| s.to_string_lossy().into_owned());
+        let (obj, mtl) = self.to_obj(&mtllib, opts, eopts)?;
+        fs::write(path, obj)?;
+        fs::write(&mtl_path, mtl)?;
+        return Ok(());
+    }
+
+    /// Text of the *.obj and *.mtl files, `mtllib` is the file name of the latter.
+    pub fn to_obj(
+        &self,
+        mtllib: &str,
+        opts: &MdlWriteOptions,
+        eopts: &ExportOptions,
+    ) -> Result<(String, String), MyError> {
+        let meshes: Vec<PosedMesh> = match &eopts.pose {
+            Some(spec) => {
+                let (seq, frame) = self.find_frame(spec)?;
+                self.posed_meshes(seq, frame)
+            },
+            None => self
+                .geosets
+                .iter()
+                .enumerate()
+                .map(|(i, g)| PosedMesh {
+                    geoset_id: i,
+                    vertices: g.vertices.clone(),
+                    normals: g.normals.clone(),
+                    alpha: 1.0,
+                })
+                .collect(),
+        };
+
+        let generator =
+            F!("# Generated by {} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+        let num = |v: f32| v.fmt_with(opts);
+        let mut lines = vec![generator.clone(), F!("mtllib {mtllib}")];
+        let (mut nv, mut nvt, mut nvn) = (0, 0, 0); // indices are global and 1-based
+        for m in meshes.iter().filter(|m| m.alpha > 0.0) {
+            let g = &self.geosets[m.geoset_id];
+            let nvert = m.vertices.len();
+            let uvs = g.uvss.first().filter(|uvs| uvs.len() == nvert);
+            let has_normals = m.normals.len() == nvert;
+
+            lines.push(F!("o Geoset{}", m.geoset_id));
+            let has_material =
+                usize::try_from(g.material_id).is_ok_and(|i| i < self.materials.len());
+            yes!(has_material, lines.push(F!("usemtl Material{}", g.material_id)));
+            lines.extend(m.vertices.iter().map(|v| F!("v {} {} {}", num(v.x), num(v.y), num(v.z))));
+            if let Some(uvs) = uvs {
+                // V goes up in OBJ
+                lines.extend(uvs.iter().map(|uv| F!("vt {} {}", num(uv.x), num(1.0 - uv.y))));
+            }
+            if has_normals {
+                lines.extend(
+                    m.normals.iter().map(|n| F!("vn {} {} {}", num(n.x), num(n.y), num(n.z))),
+                );
+            }
+            for t in g.triangle_corners() {
+                let corner = |c: usize| {
+                    let v = g.face_vertices[c] as usize + 1;
+                    match (uvs.is_some(), has_normals) {
+                        (true, true) => F!("{}/{}/{}", nv + v, nvt + v, nvn + v),
+                        (true, false) => F!("{}/{}", nv + v, nvt + v),
+                        (false, true) => F!("{}//{}", nv + v, nvn + v),
+                        (false, false) => F!("{}", nv + v),
+                    }
+                };
+                lines.push(F!("f {} {} {}", corner(t[0]), corner(t[1]), corner(t[2])));
+            }
+            nv += nvert;
+            yes!(uvs.is_some(), nvt += nvert);
+            yes!(has_normals, nvn += nvert);
+        }
+
+        let mut mtl = vec![generator];
+        for (i, material) in self.materials.iter().enumerate() {
+            mtl.push(F!("newmtl Material{i}"));
+            mtl.push("Kd 1 1 1".s());
+            let Some(layer) = material.layers.first() else { continue };
+            yes!(layer.alpha < 1.0, mtl.push(F!("d {}", num(layer.alpha))));
+            let texture = usize::try_from(layer.texture_id).ok().and_then(|t| self.textures.get(t));
+            if let Some(t) = texture.filter(|t| !t.path.is_empty()) {
+                mtl.push(F!("map_Kd {}", t.path.replace('\\', "/")));
+            }
+        }
+
+        let eol = &opts.line_ending;
+        return Ok((lines.join(eol) + eol, mtl.join(eol) + eol));
+    }
+
+    /// Sequence and frame of a pose spec: "SEQUENCE[:FRAME]", the sequence by name or index,
+    /// and FRAME (ms) from its start (clamped to it, 0 by default).
+    pub fn find_frame(&self, spec: &str) -> Result<(&Sequence, i32), MyError> {
+        let (name, offset) = match spec.rsplit_once(':').map(|(n, f)| (n, f.trim().parse::<i32>()))
+        {
+            Some((n, Ok(f))) => (n, f),
+            _ => (spec, 0),
+        };
+        let exact = self.sequences.iter().find(|s| s.name == name);
+        let by_name = exact.or_else(|| self.sequences.iter().find(|s| s.name.eq_icase(name)));
+        let by_index = || name.trim().parse::<usize>().ok().and_then(|i| self.sequences.get(i));
+        let Some(seq) = by_name.or_else(by_index) else {
+            return ERR!("No sequence {name:?} (of {}) to pose at", self.sequences.len());
+        };
+        let frame = (seq.start_frame + offset.max(0)).min(seq.end_frame);
+        return Ok((seq, frame));
+    }
+}
+
+//#endregion
+//#region read_obj
+
+/// Triangles of a geoset being read, with corners welded by their (position, uv, normal) indices.
+#[derive(Debug, Default)]
+struct ObjPart {
+    material: String,
+    corners: HashMap<[usize; 3], i32>, // (v, vt, vn) => vertex, missing indices are usize::MAX
+    order: Vec<[usize; 3]>,
+    faces: Vec<i32>,
+}
+
+/// A material of a *.mtl file.
+#[derive(Debug, SmartDefault)]
+struct ObjMaterial {
+    texture: Option<String>,
+    #[default(1.0)]
+    alpha: f32,
+}
+
+impl MdlxData {
+    /// Read a Wavefront OBJ as a static model: one geoset per object (or group) and material,
+    /// polygons are fanned into triangles, all vertices follow a single root bone.
+    /// Materials and textures come from the *.mtl files.
+    pub fn read_obj(path: &Path) -> Result<Self, MyError> {
+        let text = String::from_utf8_lossy(&fs::read(path)?).into_owned();
+        let (mut v, mut vt, mut vn): (Vec<Vec3>, Vec<Vec2>, Vec<Vec3>) = (vec![], vec![], vec![]);
+        let (mut parts, mut index): (Vec<ObjPart>, HashMap<(String, String), usize>) =
+            (vec![], HashMap::new());
+        let (mut object, mut material, mut current) = ("".s(), "".s(), None);
+        let mut mtls: Vec<(String, ObjMaterial)> = vec![];
+
+        for (n, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let (key, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let rest = rest.trim();
+            let owner = || F!("line {}", n + 1);
+            let floats = |count: usize| -> Result<Vec<f32>, MyError> {
+                let list: Vec<f32> = rest
+                    .split_whitespace()
+                    .take(count)
+                    .map(|s| s.parse().unwrap_or(f32::NAN))
+                    .collect();
+                yes!(
+                    list.len() < count || list.iter().any(|f| f.is_nan()),
+                    return ERR!("{}: bad numbers {rest:?}", owner())
+                );
+                return Ok(list);
+            };
+            match key {
+                "v" => v.push(Vec3::from_slice(&floats(3)?)),
+                "vt" => {
+                    let uv = floats(1)?[0];
+                    let w =
+                        rest.split_whitespace().nth(1).and_then(|s| s.parse().ok()).unwrap_or(0.0);
+                    vt.push(Vec2::new(uv, 1.0 - w)); // V goes down in MDX
+                },
+                "vn" => vn.push(Vec3::from_slice(&floats(3)?)),
+                "o" | "g" => (object, current) = (rest.s(), None),
+                "usemtl" => (material, current) = (rest.s(), None),
+                "mtllib" => {
+                    let file = path.parent().unwrap_or(Path::new("")).join(rest);
+                    match fs::read(&file) {
+                        Ok(bytes) => mtls.extend(parse_mtl(&String::from_utf8_lossy(&bytes))),
+                        Err(e) => wlog!("Failed to read {:?}: {}", file, e),
+                    }
+                },
+                "f" => {
+                    let mut corners = vec![];
+                    for item in rest.split_whitespace() {
+                        let mut ids = item.split('/');
+                        let mut resolve = |count: usize| -> Result<usize, MyError> {
+                            let s = ids.next().unwrap_or("");
+                            yes!(s.is_empty(), return Ok(usize::MAX));
+                            let i: i64 =
+                                s.parse().or_else(|_| ERR!("{}: bad index {s:?}", owner()))?;
+                            let i = yesno!(i < 0, count as i64 + i, i - 1);
+                            yes!(
+                                i < 0 || i >= count as i64,
+                                return ERR!("{}: index {s} out of {count}", owner())
+                            );
+                            return Ok(i as usize);
+                        };
+                        let c = [resolve(v.len())?, resolve(vt.len())?, resolve(vn.len())?];
+                        yes!(
+                            c[0] == usize::MAX,
+                            return ERR!("{}: face without a position", owner())
+                        );
+                        corners.push(c);
+                    }
+                    yes!(corners.len() < 3, continue);
+                    let p = *current.get_or_insert_with(|| {
+                        *index.entry((object.clone(), material.clone())).or_insert_with(|| {
+                            parts
+                                .push(ObjPart { material: material.clone(), ..Default::default() });
+                            parts.len() - 1
+                        })
+                    });
+                    let part = &mut parts[p];
+                    for k in 1..corners.len() - 1 {
+                        for c in [corners[0], corners[k], corners[k + 1]] {
+                            let next = part.order.len() as i32;
+                            let vertex = *part.corners.entry(c).or_insert(next);
+                            yes!(vertex == next, part.order.push(c));
+                            part.faces.push(vertex);
+                        }
+                    }
+                },
+                _ => {}, // s, l, p, ...
+            }
+        }
+
+        let mut this = Self::default();
+        this.version.format_version = FORMAT_VERSION;
+        this.model.name = path.file_stem().map_or("".s(), |s| s.to_string_lossy().into_owned());
+        let mut root = Bone::default();
+        (root.base.name, root.base.flags) = ("Root".s(), NodeFlags::Bone);
+        this.bones.push(root);
+        this.pivot_points.push(PivotPoint { position: Vec3::ZERO });
+
+        let mut materials: HashMap<String, i32> = HashMap::new();
+        for (i, part) in parts.into_iter().enumerate() {
+            let mut g = Geoset::default();
+            g.material_id = match materials.get(&part.material) {
+                Some(&id) => id,
+                None => {
+                    let m = mtls.iter().rev().find(|m| m.0 == part.material).map(|m| &m.1);
+                    let id = this.add_obj_material(m.unwrap_or(&ObjMaterial::default()));
+                    *materials.entry(part.material.clone()).or_insert(id)
+                },
+            };
+            let has_uvs = part.order.iter().all(|c| c[1] != usize::MAX);
+            let has_normals = part.order.iter().all(|c| c[2] != usize::MAX);
+            g.vertices = part.order.iter().map(|c| v[c[0]]).collect();
+            yes!(
+                has_normals,
+                g.normals = part.order.iter().map(|c| vn[c[2]].normalize_or(Vec3::Z)).collect()
+            );
+            g.uvss = vec![match has_uvs {
+                true => part.order.iter().map(|c| vt[c[1]]).collect(),
+                false => vec![Vec2::ZERO; part.order.len()],
+            }];
+            let vtxgrps = vec![0; part.order.len()];
+            for mut g in g
+                .from_triangles(&part.faces, &vtxgrps, &[vec![0]])
+                .map_err(|e| e.within(F!("Geoset[{i}]")))?
+            {
+                no!(has_normals, g.recalc_normals(&NormalOptions::default())?);
+                this.geosets.push(g);
+            }
+        }
+        this.recalc_extents();
+        return Ok(this);
+    }
+
+    fn add_obj_material(&mut self, m: &ObjMaterial) -> i32 {
+        let path = m.texture.as_deref().map_or(WHITE_TEXTURE.s(), |p| p.replace('/', "\\"));
+        let texture_id = match self.textures.iter().position(|t| t.path == path) {
+            Some(i) => i as i32,
+            None => {
+                self.textures.push(Texture { path, ..Default::default() });
+                self.textures.len() as i32 - 1
+            },
+        };
+        let mut layer = Layer::default();
+        (layer.texture_id, layer.alpha) = (texture_id, m.alpha);
+        yes!(m.alpha < 1.0, layer.filter_mode = FilterMode::Blend);
+        let mut material = Material::default();
+        material.layers.push(layer);
+        self.materials.push(material);
+        return self.materials.len() as i32 - 1;
+    }
+}
+
+/// Materials of a *.mtl file, by name.
+fn parse_mtl(text: &str) -> Vec<(String, ObjMaterial)> {
+    let mut list: Vec<(String, ObjMaterial)> = vec![];
+    for line in text.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        let (key, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        if key == "newmtl" {
+            list.push((rest.s(), ObjMaterial::default()));
+            continue;
+        }
+        let Some((_, m)) = list.last_mut() else { continue };
+        let number = rest.split_whitespace().next().and_then(|s| s.parse::<f32>().ok());
+        match key {
+            "d" => m.alpha = number.unwrap_or(1.0).clamp(0.0, 1.0),
+            "Tr" => m.alpha = (1.0 - number.unwrap_or(0.0)).clamp(0.0, 1.0),
+            // options (e.g. "-s 1 1 1") come before the file name, which may contain spaces
+            "map_Kd" if rest.starts_with('-') => {
+                m.texture = rest.split_whitespace().last().map(|s| s.s())
+            },
+            "map_Kd" => m.texture = Some(rest.s()),
+            _ => {},
+        }
+    }
+    return list;
+}
+
+//#endregion
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn geoset(nvert: usize, faces: &[u16], uvs: bool, normals: bool) -> Geoset {
+        let mut g = Geoset::default();
+        g.vertices = (0..nvert).map(|i| Vec3::new(i as f32, 0.0, 0.0)).collect();
+        g.normals = yesno!(normals, vec![Vec3::Z; nvert], vec![]);
+        g.uvss = yesno!(uvs, vec![vec![Vec2::ZERO; nvert]], vec![]);
+        g.face_types = vec![FaceType::Triangles];
+        g.face_vtxcnts = vec![faces.len() as i32];
+        g.face_vertices = faces.to_vec();
+        return g;
+    }
+
+    fn faces(obj: &str) -> Vec<&str> {
+        obj.lines().filter(|l| l.starts_with("f ")).collect()
+    }
+
+    #[test]
+    fn global_indices() {
+        let mut data = MdlxData::default();
+        data.geosets.push(geoset(3, &[0, 1, 2], true, true));
+        data.geosets.push(geoset(4, &[0, 1, 2, 2, 1, 3], false, true));
+        data.geosets.push(geoset(3, &[2, 1, 0], true, false));
+        let opts = MdlWriteOptions::default();
+        let (obj, _) = data.to_obj("a.mtl", &opts, &ExportOptions::default()).unwrap();
+
+        // v: 3 + 4 + 3, vt: 3 + 0 + 3, vn: 3 + 4 + 0
+        assert_eq!(
+            faces(&obj),
+            ["f 1/1/1 2/2/2 3/3/3", "f 4//4 5//5 6//6", "f 6//6 5//5 7//7", "f 10/6 9/5 8/4",]
+        );
+        let count = |prefix: &str| obj.lines().filter(|l| l.starts_with(prefix)).count();
+        assert_eq!((count("v "), count("vt "), count("vn ")), (10, 6, 7));
+    }
+
+    #[test]
+    fn posed_skips_hidden() {
+        let mut data = MdlxData::default();
+        data.geosets.push(geoset(3, &[0, 1, 2], false, false));
+        data.geosets.push(geoset(3, &[0, 1, 2], false, false));
+        data.geoanims.push(GeosetAnim { geoset_id: 0, alpha: 0.0, ..Default::default() });
+        data.sequences.push(Sequence { name: "Stand".s(), end_frame: 1000, ..Default::default() });
+
+        let eopts = ExportOptions { pose: Some("Stand".s()) };
+        let (obj, _) = data.to_obj("a.mtl", &MdlWriteOptions::default(), &eopts).unwrap();
+        assert!(!obj.contains("o Geoset0") && obj.contains("o Geoset1"));
+        assert_eq!(faces(&obj), ["f 1 2 3"]);
+    }
+
+    #[test]
+    fn find_frame() {
+        let mut data = MdlxData::default();
+        for (name, start_frame, end_frame) in [("Stand", 0, 1000), ("Walk", 2000, 3000)] {
+            data.sequences.push(Sequence {
+                name: name.s(),
+                start_frame,
+                end_frame,
+                ..Default::default()
+            });
+        }
+        let frame = |spec: &str| data.find_frame(spec).map(|(s, f)| (s.name.as_str(), f)).ok();
+
+        assert_eq!(frame("Walk"), Some(("Walk", 2000)));
+        assert_eq!(frame("walk:500"), Some(("Walk", 2500))); // ignoring case
+        assert_eq!(frame("1:100"), Some(("Walk", 2100))); // by index
+        assert_eq!(frame("0"), Some(("Stand", 0)));
+        assert_eq!(frame("Walk:5000"), Some(("Walk", 3000))); // clamped to the end
+        assert_eq!(frame("Walk:-10"), Some(("Walk", 2000))); // and to the start
+        assert_eq!(frame("Run"), None);
+        assert_eq!(frame("2"), None);
+    }
+
+    fn read(name: &str, obj: &str, mtl: &str) -> MdlxData {
+        let dir = std::env::temp_dir().join("war3mdlx_obj_test");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(F!("{name}.mtl")), mtl).unwrap();
+        let path = dir.join(F!("{name}.obj"));
+        fs::write(&path, obj.replace("MTL_NAME", &F!("{name}.mtl"))).unwrap();
+        return MdlxData::read_obj(&path).unwrap();
+    }
+
+    #[test]
+    fn read_corners() {
+        let obj = "mtllib MTL_NAME
+            v 0 0 0
+            v 1 0 0
+            v 1 1 0
+            v 0 1 0
+            vn 0 0 2
+            f -4//1 -3//1 -2//1 -1//-1 # a quad, by negative indices
+            f 1//1 2//1 3//1 # welded to the same vertices
+            f 1 2 # not a polygon";
+        let data = read("corners", obj, "");
+        assert_eq!(data.geosets.len(), 1);
+        let g = &data.geosets[0];
+        assert_eq!(g.vertices.len(), 4);
+        assert_eq!(g.face_vertices, [0, 1, 2, 0, 2, 3, 0, 1, 2]);
+        assert_eq!(g.normals, [Vec3::Z; 4]);
+        assert_eq!(g.uvss, [[Vec2::ZERO; 4]]);
+        assert_eq!(data.textures[0].path, WHITE_TEXTURE);
+    }
+
+    #[test]
+    fn read_parts() {
+        let obj = "mtllib MTL_NAME
+            v 0 0 0
+            v 1 0 0
+            v 0 1 0
+            vt 0 0
+            vt 1 0
+            vt 0 0.25
+            o A
+            usemtl red
+            f 1/1 2/2 3/3
+            usemtl glass
+            f 1/1 2/2 3/3
+            o B
+            usemtl red
+            f 1/1 2/2 3/3
+            o A
+            usemtl red
+            f 3/3 2/2 1/1";
+        let mtl = "newmtl red
+            map_Kd -s 1 1 1 -bm 0.5 tex/red.blp
+            newmtl glass
+            d 0.5";
+        let data = read("parts", obj, mtl);
+
+        // one geoset per (object, material), one material per name
+        let parts: Vec<_> =
+            data.geosets.iter().map(|g| (g.material_id, g.face_vertices.len())).collect();
+        assert_eq!(parts, [(0, 6), (1, 3), (0, 3)]);
+        assert_eq!(data.geosets[0].vertices.len(), 3);
+        assert_eq!(data.geosets[0].uvss[0][2], Vec2::new(0.0, 0.75)); // V goes down
+        assert_eq!(data.materials.len(), 2);
+        let paths: Vec<_> = data.textures.iter().map(|t| t.path.as_str()).collect();
+        assert_eq!(paths, ["tex\\red.blp", WHITE_TEXTURE]);
+        let layer = &data.materials[1].layers[0];
+        assert_eq!((layer.texture_id, layer.alpha), (1, 0.5));
+        assert!(matches!(layer.filter_mode, FilterMode::Blend));
+    }
+
+    #[test]
+    fn mtl_maps() {
+        let list = parse_mtl(
+            "map_Kd ignored.blp # before any newmtl
+            newmtl a
+            map_Kd my texture.blp
+            Tr 0.25
+            newmtl b
+            map_Kd -o 0.5 0.5 -clamp on b.blp
+            d 2",
+        );
+        let list: Vec<_> =
+            list.iter().map(|(n, m)| (n.as_str(), m.texture.as_deref(), m.alpha)).collect();
+        assert_eq!(list, [("a", Some("my texture.blp"), 0.75), ("b", Some("b.blp"), 1.0)]);
+    }
+}
@@ -0,0 +1,221 @@
+use crate::*;
+
+//#region pose
+
+/// World transform of a node, with the rotation and scaling it passes on to children.
+#[derive(Debug, Clone, Copy)]
+struct NodePose {
+    matrix: Mat4,
+    rotation: Quat,
+    scaling: Vec3,
+}
+
+impl MdlxData {
+    /// World matrices of all nodes (by object_id) at `frame` of `sequence`,
+    /// global sequences start with it. Billboarded nodes face the default view
+    /// (their +X axis towards the viewer), see `pose_at_view`.
+    pub fn pose_at(&self, sequence: &Sequence, frame: i32) -> HashMap<i32, Mat4> {
+        self.pose_at_view(sequence, frame, Quat::IDENTITY)
+    }
+
+    /// Like `pose_at`, billboarded nodes face a camera rotated by `view` (from the default view).
+    pub fn pose_at_view(&self, sequence: &Sequence, frame: i32, view: Quat) -> HashMap<i32, Mat4> {
+        let nodes: HashMap<i32, &Node> =
+            self.nodes().into_iter().map(|(_, n)| (n.object_id, n)).collect();
+        let mut poses: HashMap<i32, NodePose> = HashMap::new();
+        for &id in nodes.keys() {
+            self.node_pose(id, &nodes, (sequence, frame, view), &mut poses, 0);
+        }
+        return poses.into_iter().map(|(id, p)| (id, p.matrix)).collect();
+    }
+
+    fn node_pose(
+        &self,
+        id: i32,
+        nodes: &HashMap<i32, &Node>,
+        at: (&Sequence, i32, Quat),
+        poses: &mut HashMap<i32, NodePose>,
+        depth: usize,
+    ) -> NodePose {
+        const ROOT: NodePose =
+            NodePose { matrix: Mat4::IDENTITY, rotation: Quat::IDENTITY, scaling: Vec3::ONE };
+        if let Some(p) = poses.get(&id) {
+            return *p;
+        }
+        let Some(node) = nodes.get(&id) else { return ROOT };
+        let (seq, frame, view) = at;
+
+        let pivot = usize::try_from(id)
+            .ok()
+            .and_then(|i| self.pivot_points.get(i))
+            .map_or(Vec3::ZERO, |p| p.position);
+        let time = frame - seq.start_frame;
+        let t = self.sample(&node.translation, frame, seq, time, Vec3::ZERO);
+        let r = to_quat(self.sample(&node.rotation, frame, seq, time, Vec4::W));
+        let s = self.sample(&node.scaling, frame, seq, time, Vec3::ONE);
+
+        let parent = match node.parent_id {
+            p if p == -1 || p == id || depth > nodes.len() => ROOT, // no parent, or a loop
+            p => self.node_pose(p, nodes, at, poses, depth + 1),
+        };
+        let flags = node.flags;
+        let special = NodeFlags::DontInheritT
+            | NodeFlags::DontInheritR
+            | NodeFlags::DontInheritS
+            | NodeFlags::Billboarded
+            | NodeFlags::BillboardedLockX
+            | NodeFlags::BillboardedLockY
+            | NodeFlags::BillboardedLockZ;
+
+        let mut rotation = yesno!(flags.contains(NodeFlags::DontInheritR), r, parent.rotation * r);
+        let scaling = yesno!(flags.contains(NodeFlags::DontInheritS), s, parent.scaling * s);
+        let matrix = if !flags.intersects(special) {
+            parent.matrix
+                * Mat4::from_scale_rotation_translation(s, r, pivot + t)
+                * Mat4::from_translation(-pivot)
+        } else {
+            let origin = match flags.contains(NodeFlags::DontInheritT) {
+                true => pivot + t,
+                false => parent.matrix.transform_point3(pivot + t),
+            };
+            let locks = [
+                (NodeFlags::BillboardedLockX, Vec3::X),
+                (NodeFlags::BillboardedLockY, Vec3::Y),
+                (NodeFlags::BillboardedLockZ, Vec3::Z),
+            ];
+            if flags.contains(NodeFlags::Billboarded) {
+                rotation = view;
+            } else if let Some((_, axis)) = locks.iter().find(|(f, _)| flags.contains(*f)) {
+                rotation = billboard_around(rotation, *axis, view);
+            }
+            Mat4::from_scale_rotation_translation(scaling, rotation, origin)
+                * Mat4::from_translation(-pivot)
+        };
+
+        let pose = NodePose { matrix, rotation, scaling };
+        poses.insert(id, pose);
+        return pose;
+    }
+}
+
+/// Spin `rotation` around its local `axis`,
+/// so that its facing axis (+X, or +Z when locked to X) turns to the view.
+fn billboard_around(rotation: Quat, axis: Vec3, view: Quat) -> Quat {
+    let facing = yesno!(axis == Vec3::X, Vec3::Z, Vec3::X);
+    let a = rotation * axis;
+    let (from, to) = (rotation * facing, view * facing);
+    let (from, to) = (from - a * from.dot(a), to - a * to.dot(a));
+    yes!(from.length_squared() < 1e-8 || to.length_squared() < 1e-8, return rotation);
+    let angle = from.cross(to).dot(a).atan2(from.dot(to));
+    return Quat::from_axis_angle(a.normalize(), angle) * rotation;
+}
+
+//#endregion
+//#region skinning
+
+/// A geoset deformed by a pose.
+#[derive(Debug, Default, Clone)]
+pub struct PosedMesh {
+    pub geoset_id: usize,
+    pub vertices: Vec<Vec3>,
+    pub normals: Vec<Vec3>,
+    /// Alpha of the GeosetAnim (1 without one).
+    pub alpha: f32,
+}
+
+impl MdlxData {
+    /// Geosets posed at `frame` of `sequence` (see `pose_at`),
+    /// including those hidden by GeosetAnim alpha (`alpha <= 0`).
+    pub fn posed_meshes(&self, sequence: &Sequence, frame: i32) -> Vec<PosedMesh> {
+        let pose = self.pose_at(sequence, frame);
+        let mut meshes = vec![];
+        for (i, g) in self.geosets.iter().enumerate() {
+            let alpha = self.geoset_alpha(i, sequence, frame);
+            let (vertices, normals) = g.skin(&pose);
+            meshes.push(PosedMesh { geoset_id: i, vertices, normals, alpha });
+        }
+        return meshes;
+    }
+
+    /// Alpha of the (first) GeosetAnim of a geoset at `frame` of `sequence`, 1 if there is none.
+    pub fn geoset_alpha(&self, geoset_id: usize, sequence: &Sequence, frame: i32) -> f32 {
+        let Some(a) = self.geoanims.iter().find(|a| a.geoset_id == geoset_id as i32) else {
+            return 1.0;
+        };
+        return self.sample(&a.alpha_anim, frame, sequence, frame - sequence.start_frame, a.alpha);
+    }
+}
+
+impl Geoset {
+    /// Vertices and normals transformed by a pose (see `MdlxData::pose_at`).
+    /// Each vertex follows the average of the matrices in its group,
+    /// or its skin weights if there are any.
+    pub fn skin(&self, pose: &HashMap<i32, Mat4>) -> (Vec<Vec3>, Vec<Vec3>) {
+        let matrices = self.vertex_matrices(pose);
+        let vertices = self
+            .vertices
+            .iter()
+            .zip(matrices.iter())
+            .map(|(v, m)| m.map_or(*v, |m| m.transform_point3(*v)));
+        let normals = self.normals.iter().zip(matrices.iter()).map(|(n, m)| match m {
+            None => *n,
+            Some(m) => {
+                let m = Mat3::from_mat4(*m);
+                let m = yesno!(m.determinant().abs() > 1e-12, m.inverse().transpose(), m);
+                (m * *n).normalize_or_zero()
+            },
+        });
+        return (vertices.collect(), normals.collect());
+    }
+
+    /// Blended matrix of each vertex, None if it has no (valid) matrix.
+    fn vertex_matrices(&self, pose: &HashMap<i32, Mat4>) -> Vec<Option<Mat4>> {
+        let mut out = Vec::with_capacity(self.vertices.len());
+        for bones in self.vertex_bones() {
+            let (mut sum, mut total) = (Mat4::ZERO, 0.0);
+            for (id, weight) in bones {
+                let Some(m) = pose.get(&id) else { continue };
+                sum += *m * weight;
+                total += weight;
+            }
+            out.push(yesno!(total > 0.0, Some(sum / total), None));
+        }
+        return out;
+    }
+
+    /// Nodes (object_id, weight) each vertex follows: its skin weights if there are any,
+    /// otherwise the matrices in its group with equal weights.
+    pub fn vertex_bones(&self) -> Vec<Vec<(i32, f32)>> {
+        let nvert = self.vertices.len();
+        let node = |i: usize| self.mtx_indices.get(i).copied();
+
+        if self.skin_weights.len() == nvert * Self::SKIN_WEIGHT_SIZE {
+            let mut out = Vec::with_capacity(nvert);
+            for w in self.skin_weights.chunks(Self::SKIN_WEIGHT_SIZE) {
+                let bones = (0..4).filter(|&k| w[k + 4] > 0);
+                out.push(
+                    bones
+                        .filter_map(|k| node(w[k] as usize).map(|id| (id, w[k + 4] as f32 / 255.0)))
+                        .collect(),
+                );
+            }
+            return out;
+        }
+
+        let mut offsets = vec![0_usize];
+        for n in self.mtxgrpcnts.iter() {
+            offsets.push(offsets[offsets.len() - 1] + (*n).max(0) as usize);
+        }
+        let mut out = Vec::with_capacity(nvert);
+        for i in 0..nvert {
+            let group = self.vtxgrps.get(i).map(|&g| g as usize);
+            let range = group.and_then(|g| offsets.get(g + 1).map(|&end| offsets[g]..end));
+            let ids: Vec<i32> = range.map_or(vec![], |r| r.filter_map(&node).collect());
+            let weight = 1.0 / ids.len().max(1) as f32;
+            out.push(ids.into_iter().map(|id| (id, weight)).collect());
+        }
+        return out;
+    }
+}
+
+//#endregion
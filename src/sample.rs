@@ -0,0 +1,276 @@
+use crate::*;
+
+//#region Interpolate
+
+/// Values of animation tracks which can be interpolated between two keys.
+pub trait Interpolate: TAnimation + Copy {
+    fn linear(a: Self, b: Self, t: f32) -> Self;
+    /// Hermite or Bezier from `a` to `b`, with `a.otan` and `b.itan` as tangents or control points.
+    fn cubic(typ: InterpolationType, a: &KeyFrame<Self>, b: &KeyFrame<Self>, t: f32) -> Self;
+    /// Tangents (in, out) of a key between `prev` and `next`,
+    /// which make Hermite or Bezier interpolation linear.
+    fn linear_tangents(typ: InterpolationType, prev: Self, value: Self, next: Self)
+    -> (Self, Self);
+
+    /// The value as a rotation quaternion, None for other types. See `MdlxData::fix_anims`.
+    fn as_quat(&self) -> Option<&Vec4> {
+        None
+    }
+    fn as_quat_mut(&mut self) -> Option<&mut Vec4> {
+        None
+    }
+}
+
+/// Factors of `a`, `a.otan`, `b.itan` and `b`.
+fn cubic_factors(typ: InterpolationType, t: f32) -> [f32; 4] {
+    let (t2, it) = (t * t, 1.0 - t);
+    match typ {
+        InterpolationType::Bezier => [it * it * it, 3.0 * t * it * it, 3.0 * t2 * it, t2 * t],
+        _ => [t2 * (2.0 * t - 3.0) + 1.0, t2 * (t - 2.0) + t, t2 * (t - 1.0), t2 * (3.0 - 2.0 * t)],
+    }
+}
+
+macro_rules! impl_Interpolate_vecN {
+    ($($ty:ty),+) => {
+        $(impl Interpolate for $ty {
+            fn linear(a: Self, b: Self, t: f32) -> Self {
+                a + (b - a) * t
+            }
+            fn cubic(
+                typ: InterpolationType,
+                a: &KeyFrame<Self>,
+                b: &KeyFrame<Self>,
+                t: f32,
+            ) -> Self {
+                let f = cubic_factors(typ, t);
+                a.value * f[0] + a.otan * f[1] + b.itan * f[2] + b.value * f[3]
+            }
+            fn linear_tangents(
+                typ: InterpolationType,
+                prev: Self,
+                value: Self,
+                next: Self,
+            ) -> (Self, Self) {
+                match typ {
+                    InterpolationType::Bezier => {
+                        (value - (value - prev) / 3.0, value + (next - value) / 3.0)
+                    },
+                    _ => (value - prev, next - value),
+                }
+            }
+        })+
+    };
+}
+impl_Interpolate_vecN!(f32, Vec3);
+
+/// Integer tracks (e.g. texture ids) never interpolate.
+impl Interpolate for i32 {
+    fn linear(a: Self, _: Self, _: f32) -> Self {
+        a
+    }
+    fn cubic(_: InterpolationType, a: &KeyFrame<Self>, _: &KeyFrame<Self>, _: f32) -> Self {
+        a.value
+    }
+    fn linear_tangents(_: InterpolationType, _: Self, value: Self, _: Self) -> (Self, Self) {
+        (value, value)
+    }
+}
+
+/// Rotation quaternions: slerp, and squad for Hermite and Bezier.
+impl Interpolate for Vec4 {
+    fn linear(a: Self, b: Self, t: f32) -> Self {
+        from_quat(to_quat(a).slerp(to_quat(b), t))
+    }
+    fn cubic(_: InterpolationType, a: &KeyFrame<Self>, b: &KeyFrame<Self>, t: f32) -> Self {
+        let outer = to_quat(a.value).slerp(to_quat(b.value), t);
+        let inner = to_quat(a.otan).slerp(to_quat(b.itan), t);
+        from_quat(outer.slerp(inner, 2.0 * t * (1.0 - t)))
+    }
+    fn linear_tangents(_: InterpolationType, _: Self, value: Self, _: Self) -> (Self, Self) {
+        (value, value) // squad reduces to slerp
+    }
+    fn as_quat(&self) -> Option<&Vec4> {
+        Some(self)
+    }
+    fn as_quat_mut(&mut self) -> Option<&mut Vec4> {
+        Some(self)
+    }
+}
+
+/// Normalized quaternion of a rotation value, identity if zero.
+pub fn to_quat(v: Vec4) -> Quat {
+    let q = Quat::from_vec4(v);
+    return yesno!(q.length() > 0.0, q.normalize(), Quat::IDENTITY);
+}
+pub fn from_quat(q: Quat) -> Vec4 {
+    Vec4::from_array(q.to_array())
+}
+
+//#endregion
+//#region sample
+
+impl<T: Interpolate> Animation<T> {
+    /// Value at `frame` of `sequence`, only keys within the sequence count
+    /// and `frame` is clamped to it.
+    /// An inverted sequence (start after end) counts as its swapped interval.
+    /// Tracks of a global sequence are sampled at `global_time` (ms, looping) instead.
+    /// Return None if no key applies, i.e. the static value (or default) should be used.
+    pub fn sample(
+        &self,
+        frame: i32,
+        sequence: &Sequence,
+        global_time: i32,
+        globalseqs: &[GlobalSequence],
+    ) -> Option<T> {
+        let global = usize::try_from(self.global_seq_id).ok().and_then(|i| globalseqs.get(i));
+        let (frame, interval) = match global {
+            Some(g) => {
+                let d = g.duration.min(i32::MAX as u32) as i32;
+                (yesno!(d > 0, global_time.rem_euclid(d), 0), (0, d))
+            },
+            None => {
+                let (start, end) = (sequence.start_frame, sequence.end_frame);
+                let (lo, hi) = (start.min(end), start.max(end));
+                (frame.clamp(lo, hi), (lo, hi))
+            },
+        };
+        return self.sample_within(frame, interval);
+    }
+
+    /// Value at `frame`, only keys within `interval` count.
+    /// Before the first key (after the last) hold its value.
+    pub fn sample_within(&self, frame: i32, interval: (i32, i32)) -> Option<T> {
+        let keys: Vec<&KeyFrame<T>> = self
+            .key_frames
+            .iter()
+            .filter(|k| interval.0 <= k.frame && k.frame <= interval.1)
+            .collect();
+        let (first, last) = (keys.first()?, keys.last()?);
+        yes!(frame <= first.frame, return Some(first.value));
+        yes!(frame >= last.frame, return Some(last.value));
+
+        let i = keys.iter().position(|k| k.frame > frame).unwrap_or(keys.len() - 1).max(1);
+        let (a, b) = (keys[i - 1], keys[i]);
+        let t = (frame - a.frame) as f32 / (b.frame - a.frame).max(1) as f32;
+        return Some(match self.interp_type {
+            InterpolationType::Linear => T::linear(a.value, b.value, t),
+            typ @ (InterpolationType::Hermite | InterpolationType::Bezier) => {
+                T::cubic(typ, a, b, t)
+            },
+            _ => a.value,
+        });
+    }
+}
+
+impl MdlxData {
+    /// Sample an optional track at `frame` of `sequence` (see `Animation::sample`),
+    /// `default` if none applies.
+    pub fn sample<T: Interpolate>(
+        &self,
+        anim: &Option<Animation<T>>,
+        frame: i32,
+        sequence: &Sequence,
+        global_time: i32,
+        default: T,
+    ) -> T {
+        let value =
+            anim.as_ref().and_then(|a| a.sample(frame, sequence, global_time, &self.globalseqs));
+        return value.unwrap_or(default);
+    }
+}
+
+//#endregion
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key<T: TAnimation>(frame: i32, value: T, itan: T, otan: T) -> KeyFrame<T> {
+        KeyFrame { frame, value, itan, otan, has_tans: true }
+    }
+    fn track<T: TAnimation>(
+        interp_type: InterpolationType,
+        key_frames: Vec<KeyFrame<T>>,
+    ) -> Animation<T> {
+        Animation { interp_type, key_frames, ..Default::default() }
+    }
+    fn sequence(start_frame: i32, end_frame: i32) -> Sequence {
+        Sequence { start_frame, end_frame, ..Default::default() }
+    }
+
+    #[test]
+    fn linear_and_hold() {
+        let keys = vec![
+            key(0, Vec3::ZERO, Vec3::ZERO, Vec3::ZERO),
+            key(10, Vec3::X * 10.0, Vec3::ZERO, Vec3::ZERO),
+        ];
+        let anim = track(InterpolationType::Linear, keys.clone());
+        assert_eq!(anim.sample_within(5, (0, 10)), Some(Vec3::X * 5.0));
+        assert_eq!(anim.sample_within(-5, (0, 10)), Some(Vec3::ZERO));
+        assert_eq!(anim.sample_within(15, (0, 10)), Some(Vec3::X * 10.0));
+        assert_eq!(anim.sample_within(5, (20, 30)), None);
+        assert_eq!(
+            track(InterpolationType::DontInterp, keys).sample_within(9, (0, 10)),
+            Some(Vec3::ZERO)
+        );
+    }
+
+    #[test]
+    fn hermite_and_bezier() {
+        // Hermite basis at t = 0.5: [0.5, 0.125, -0.125, 0.5]
+        let hermite =
+            track(InterpolationType::Hermite, vec![key(0, 0.0, 0.0, 2.0), key(10, 1.0, 0.0, 0.0)]);
+        assert_eq!(hermite.sample_within(5, (0, 10)), Some(0.75));
+        // Bezier basis at t = 0.5: [0.125, 0.375, 0.375, 0.125]
+        let bezier =
+            track(InterpolationType::Bezier, vec![key(0, 0.0, 0.0, 1.0), key(10, 0.0, 1.0, 0.0)]);
+        assert_eq!(bezier.sample_within(5, (0, 10)), Some(0.75));
+        // linear tangents keep a cubic track on the line
+        for typ in [InterpolationType::Hermite, InterpolationType::Bezier] {
+            let (_, otan) = f32::linear_tangents(typ, 0.0, 0.0, 4.0);
+            let (itan, _) = f32::linear_tangents(typ, 0.0, 4.0, 8.0);
+            let anim = track(typ, vec![key(0, 0.0, 0.0, otan), key(10, 4.0, itan, 0.0)]);
+            assert!((anim.sample_within(5, (0, 10)).unwrap() - 2.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn squad_rotation() {
+        let (a, b) = (Quat::IDENTITY, Quat::from_rotation_z(std::f32::consts::FRAC_PI_2));
+        let (a, b) = (from_quat(a), from_quat(b));
+        let expect = Quat::from_rotation_z(std::f32::consts::FRAC_PI_4);
+        // tangents equal to the values reduce squad to slerp
+        let anim = track(InterpolationType::Hermite, vec![key(0, a, a, a), key(10, b, b, b)]);
+        assert!(to_quat(anim.sample_within(5, (0, 10)).unwrap()).angle_between(expect) < 1e-4);
+        let anim = track(InterpolationType::Linear, vec![key(0, a, a, a), key(10, b, b, b)]);
+        assert!(to_quat(anim.sample_within(5, (0, 10)).unwrap()).angle_between(expect) < 1e-4);
+        // at the keys squad returns the key values, whatever the tangents
+        let c = from_quat(Quat::from_rotation_x(1.0));
+        let anim = track(InterpolationType::Bezier, vec![key(0, a, c, c), key(10, b, c, c)]);
+        assert!(
+            to_quat(anim.sample_within(0, (0, 10)).unwrap()).angle_between(Quat::IDENTITY) < 1e-4
+        );
+        assert!(to_quat(anim.sample_within(10, (0, 10)).unwrap()).angle_between(to_quat(b)) < 1e-4);
+    }
+
+    #[test]
+    fn sequence_and_global_sequence() {
+        let anim = track(
+            InterpolationType::Linear,
+            vec![key(100, 0.0, 0.0, 0.0), key(200, 10.0, 0.0, 0.0)],
+        );
+        assert_eq!(anim.sample(150, &sequence(100, 200), 0, &[]), Some(5.0));
+        assert_eq!(anim.sample(150, &sequence(200, 100), 0, &[]), Some(5.0)); // inverted
+        assert_eq!(anim.sample(500, &sequence(200, 100), 0, &[]), Some(10.0));
+
+        let globalseqs = [GlobalSequence { duration: 100 }];
+        let anim = Animation {
+            global_seq_id: 0,
+            ..track(
+                InterpolationType::Linear,
+                vec![key(0, 0.0, 0.0, 0.0), key(100, 10.0, 0.0, 0.0)],
+            )
+        };
+        assert_eq!(anim.sample(0, &sequence(1000, 2000), 250, &globalseqs), Some(5.0));
+    }
+}
@@ -7,6 +7,80 @@ pub enum MyError {
     Io(ioError),
     ParseInt(ParseIntError),
     ParseFloat(ParseFloatError),
+    /// `line` is set when the version comes from an mdl file.
+    UnsupportedVersion {
+        version: i32,
+        supported: Vec<i32>,
+        line: Option<u32>,
+    },
+    /// Chunk `id` (0 while reading the id itself) needs `need` bytes at `offset`, only `left` remain.
+    TruncatedChunk {
+        id: u32,
+        offset: u32,
+        need: u32,
+        left: u32,
+    },
+    UnknownAnimationTag {
+        owner: String,
+        tag: u32,
+    },
+    MdlSyntax {
+        line: u32,
+        col: u32,
+        message: String,
+    },
+    /// `owner.field` refers to `index`, but only `count` items exist.
+    BadReference {
+        owner: String,
+        field: String,
+        index: i64,
+        count: usize,
+    },
+    /// `owner.field` is `value`, over what the game engine (or the mdx format) allows.
+    LimitExceeded {
+        owner: String,
+        field: String,
+        value: usize,
+        limit: usize,
+    },
+    /// `source` occurred inside `scope`, e.g. a chunk, an mdl block or a file.
+    Within {
+        scope: String,
+        source: Box<MyError>,
+    },
+}
+
+impl MyError {
+    pub fn within<S: Into<String>>(self, scope: S) -> Self {
+        MyError::Within { scope: scope.into(), source: Box::new(self) }
+    }
+
+    /// The innermost error, without any scopes.
+    pub fn root(&self) -> &MyError {
+        match self {
+            Self::Within { source, .. } => source.root(),
+            _ => self,
+        }
+    }
+
+    /// Scopes from outermost to innermost, e.g. `["Geoset(GEOS)[0th]"]`.
+    pub fn path(&self) -> Vec<&str> {
+        let (mut path, mut e) = (vec![], self);
+        while let Self::Within { scope, source } = e {
+            path.push(scope.as_str());
+            e = source;
+        }
+        return path;
+    }
+
+    /// The mdl line of the innermost error, if known.
+    pub fn line(&self) -> Option<u32> {
+        match self.root() {
+            Self::UnsupportedVersion { line, .. } => *line,
+            Self::MdlSyntax { line, .. } => Some(*line),
+            _ => None,
+        }
+    }
 }
 
 //#region trait: Display
@@ -18,6 +92,45 @@ impl Display for MyError {
             Self::Io(e) => write!(f, "{}", e),
             Self::ParseInt(e) => write!(f, "{}", e),
             Self::ParseFloat(e) => write!(f, "{}", e),
+            Self::UnsupportedVersion { version, supported, line } => {
+                write!(
+                    f,
+                    "Unsupported version {} (must be {})",
+                    version,
+                    supported.to_or_string()
+                )?;
+                match line {
+                    Some(l) => write!(f, " at line {}", l),
+                    None => Ok(()),
+                }
+            },
+            Self::TruncatedChunk { id, offset, need, left } => match id {
+                0 => write!(
+                    f,
+                    "reading chunk id at offset {}: {}B left (need {})",
+                    offset, left, need
+                ),
+                _ => write!(
+                    f,
+                    "reading chunk 0x{:08X}({}) at offset {}: {}B left (need {})",
+                    id,
+                    u32_to_ascii(*id),
+                    offset,
+                    left,
+                    need
+                ),
+            },
+            Self::UnknownAnimationTag { owner, tag } => {
+                write!(f, "Unknown animation in {}: {} (0x{:08X})", owner, u32_to_ascii(*tag), tag)
+            },
+            Self::MdlSyntax { message, .. } => write!(f, "{}", message),
+            Self::BadReference { owner, field, index, count } => {
+                write!(f, "{}: {} {} out of range (count: {})", owner, field, index, count)
+            },
+            Self::LimitExceeded { owner, field, value, limit } => {
+                write!(f, "{}: {} {} exceeds the limit (max: {})", owner, field, value, limit)
+            },
+            Self::Within { scope, source } => write!(f, "{}: {}", scope, source),
         }
     }
 }
@@ -49,6 +162,20 @@ impl From<ParseFloatError> for MyError {
     }
 }
 
+impl From<pest::error::Error<Rule>> for MyError {
+    fn from(e: pest::error::Error<Rule>) -> Self {
+        let (line, col) = match e.line_col {
+            pest::error::LineColLocation::Pos(p) => p,
+            pest::error::LineColLocation::Span(p, _) => p,
+        };
+        MyError::MdlSyntax {
+            line: line as u32,
+            col: col as u32,
+            message: F!("Failed to parse mdl: {}", e),
+        }
+    }
+}
+
 //#endregion
 
 #[macro_export]
@@ -60,3 +187,10 @@ macro_rules! ERR {
         core::result::Result::Err(MyError::String(s))
     }};
 }
+
+#[macro_export]
+macro_rules! SYNTAX_ERR {
+    ($line:expr, $col:expr, $($arg:tt)*) => {{
+        core::result::Result::Err(MyError::MdlSyntax { line: $line, col: $col, message: F!($($arg)*) })
+    }};
+}
@@ -16,6 +16,9 @@ macro_rules! F {
 pub fn fmtx<T: Formatter>(v: &T) -> String {
     T::fmt(v)
 }
+pub fn fmtw<T: Formatter>(v: &T, opts: &MdlWriteOptions) -> String {
+    T::fmt_with(v, opts)
+}
 pub fn fmtxx<T: FormatterXX>(v: &T) -> String {
     T::fmtxx(v)
 }
@@ -66,6 +69,20 @@ pub fn fmt_float(v: &f32, len: u32, precision: u32) -> String {
     }
 }
 
+/// The shortest decimal string that parses back to the identical f32 bits.
+/// NaN and infinities have none, they are written as their bits, e.g. `0x7FC00000`.
+pub fn fmt_float_lossless(v: &f32) -> String {
+    yes!(!v.is_finite(), return F!("0x{:08X}", v.to_bits()));
+    yes!(*v == 0.0, return yesno!(v.is_sign_negative(), "-0.0", "0").s()); // keep the sign of -0
+    let (d, e) = (F!("{v}"), F!("{v:e}"));
+    let s = yesno!(d.len() <= e.len(), d, e);
+    // integers are parsed as i32 (then cast), so keep large ones in exponent form
+    if !s.contains(['.', 'e']) && v.abs() >= 16777216.0 {
+        return F!("{v:e}");
+    }
+    return s;
+}
+
 #[allow(dead_code)]
 pub fn fmt_id4s(v: &u32) -> String {
     u32_to_ascii(*v)
@@ -79,7 +96,10 @@ pub fn u32_to_ascii(n: u32) -> String {
 //#region trait: Formatter
 
 pub trait Formatter {
-    fn fmt(&self) -> String;
+    fn fmt(&self) -> String {
+        self.fmt_with(&MdlWriteOptions::default())
+    }
+    fn fmt_with(&self, opts: &MdlWriteOptions) -> String;
 }
 pub trait FormatterXX {
     fn fmtxx(&self) -> String;
@@ -88,7 +108,7 @@ pub trait FormatterXX {
 macro_rules! impl_Formatter {
     ($($t:ty),*) => {
         $(impl Formatter for $t {
-            fn fmt(&self) -> String { self.to_string() }
+            fn fmt_with(&self, _: &MdlWriteOptions) -> String { self.to_string() }
         })*
     };
 }
@@ -96,12 +116,12 @@ macro_rules! impl_Formatter_array {
     ($($t:ty),*) => {
         $(
             impl Formatter for Vec<$t> {
-                fn fmt(&self) -> String {
-                    F!("{{ {} }}", self.iter().map(|x| Formatter::fmt(x)).collect::<Vec<_>>().join(", "))
+                fn fmt_with(&self, opts: &MdlWriteOptions) -> String {
+                    F!("{{ {} }}", self.iter().map(|x| x.fmt_with(opts)).collect::<Vec<_>>().join(", "))
                 }
             }
             impl Formatter for &[$t] {
-                fn fmt(&self) -> String { Formatter::fmt(&self.to_vec()) }
+                fn fmt_with(&self, opts: &MdlWriteOptions) -> String { self.to_vec().fmt_with(opts) }
             }
         )*
     };
@@ -110,8 +130,8 @@ macro_rules! impl_Formatter_vecN {
     ($($t:ty),*) => {
         $(
             impl Formatter for $t {
-                fn fmt(&self) -> String {
-                    let s = Formatter::fmt(&self.to_array().to_vec());
+                fn fmt_with(&self, opts: &MdlWriteOptions) -> String {
+                    let s = self.to_array().to_vec().fmt_with(opts);
                     F!("{{{}}}", &s[1..s.len()-1])
                 }
             }
@@ -126,24 +146,25 @@ impl_Formatter_array!(Vec2, Vec3, Vec4);
 impl_Formatter_array!(Vec<Vec2>, Vec<Vec3>, Vec<Vec4>);
 
 impl Formatter for f32 {
-    fn fmt(&self) -> String {
-        let p = *precision!() as u32;
+    fn fmt_with(&self, opts: &MdlWriteOptions) -> String {
+        yes!(opts.lossless, return fmt_float_lossless(self));
+        let p = opts.precision as u32;
         return fmt_float(self, p * 2 + 1, p);
     }
 }
 impl Formatter for String {
-    fn fmt(&self) -> String {
+    fn fmt_with(&self, _: &MdlWriteOptions) -> String {
         F!("\"{self}\"")
     }
 }
 impl Formatter for str {
-    fn fmt(&self) -> String {
+    fn fmt_with(&self, _: &MdlWriteOptions) -> String {
         F!("\"{self}\"")
     }
 }
 
 impl<T: stdDebug> Formatter for Option<T> {
-    fn fmt(&self) -> String {
+    fn fmt_with(&self, _: &MdlWriteOptions) -> String {
         match self {
             Some(v) => F!("{:?}", v),
             None => "None".to_string(),
@@ -160,12 +181,59 @@ impl<T: stdDebug> FormatterXX for Option<T> {
 }
 
 pub trait _ExtendFormatter {
-    fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError>;
+    fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError>;
 }
 impl<T: Formatter> _ExtendFormatter for T {
-    fn write_mdl(&self, depth: u8) -> Result<Vec<String>, MyError> {
-        Ok(vec![F!("{}{},", indent!(depth), fmtx(self))])
+    fn write_mdl(&self, depth: u8, opts: &MdlWriteOptions) -> Result<Vec<String>, MyError> {
+        Ok(vec![F!("{}{},", opts.indent(depth), fmtw(self, opts))])
     }
 }
 
 //#endregion
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write `v` losslessly, read it back as an mdl field.
+    fn round_trip(v: f32) -> f32 {
+        let text = F!("Value {},", fmt_float_lossless(&v));
+        let pair = MdlParser::parse(Rule::field, &text).unwrap().next().unwrap();
+        return MdlField::from(pair, "test").unwrap().value.to().unwrap();
+    }
+
+    #[test]
+    fn lossless_round_trip() {
+        let values = [
+            0.0,
+            -0.0,
+            1.0,
+            -1.5,
+            0.1,
+            1.0 / 3.0,
+            16777217.0,
+            -3.4e38,
+            f32::MAX,
+            f32::MIN_POSITIVE,
+            f32::from_bits(1),          // the smallest subnormal
+            f32::from_bits(0x007FFFFF), // the largest subnormal
+            f32::INFINITY,
+            f32::NEG_INFINITY,
+            f32::NAN,
+            f32::from_bits(0xFFC00001), // a negative NaN with payload
+        ];
+        for v in values {
+            let s = fmt_float_lossless(&v);
+            assert_eq!(round_trip(v).to_bits(), v.to_bits(), "{v:e} written as {s}");
+        }
+    }
+
+    #[test]
+    fn lossless_array() {
+        let text = F!("Value {{ {}, 1.5, -0.0 }},", fmt_float_lossless(&f32::NAN));
+        let pair = MdlParser::parse(Rule::field, &text).unwrap().next().unwrap();
+        let v: Vec3 = MdlField::from(pair, "test").unwrap().value.to().unwrap();
+        assert!(v.x.is_nan());
+        assert_eq!((v.y, v.z.to_bits()), (1.5, (-0.0f32).to_bits()));
+    }
+}
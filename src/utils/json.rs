@@ -0,0 +1,366 @@
+use crate::*;
+
+//#region Json
+
+/// Minimal JSON value, object keys keep their insertion order.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum Json {
+    #[default]
+    Null,
+    Bool(bool),
+    Num(f64),
+    Str(String),
+    Arr(Vec<Json>),
+    Obj(Vec<(String, Json)>),
+}
+
+macro_rules! JOBJ {
+    ($( $key:expr => $value:expr ),* $(,)?) => {
+        Json::Obj(vec![$( ($key.to_string(), Json::from($value)) ),*])
+    };
+}
+pub(crate) use JOBJ;
+
+impl Json {
+    /// Add (or replace) a key of an object, ignored by other values.
+    pub fn set<V: Into<Json>>(&mut self, key: &str, value: V) {
+        let Json::Obj(pairs) = self else { return };
+        let value = value.into();
+        match pairs.iter_mut().find(|(k, _)| k == key) {
+            Some(pair) => pair.1 = value,
+            None => pairs.push((key.s(), value)),
+        }
+    }
+}
+
+static NULL: Json = Json::Null;
+
+impl Json {
+    /// Value of a key of an object, Null if missing (or not an object).
+    pub fn get(&self, key: &str) -> &Json {
+        match self {
+            Json::Obj(pairs) => pairs.iter().find(|(k, _)| k == key).map_or(&NULL, |(_, v)| v),
+            _ => &NULL,
+        }
+    }
+    /// Item of an array, Null if missing (or not an array).
+    pub fn at(&self, i: usize) -> &Json {
+        self.arr().get(i).unwrap_or(&NULL)
+    }
+    pub fn is_null(&self) -> bool {
+        matches!(self, Json::Null)
+    }
+    pub fn num(&self) -> Option<f64> {
+        match self {
+            Json::Num(v) => Some(*v),
+            _ => None,
+        }
+    }
+    pub fn usize(&self) -> Option<usize> {
+        self.num().filter(|v| *v >= 0.0 && v.fract() == 0.0).map(|v| v as usize)
+    }
+    pub fn str(&self) -> Option<&str> {
+        match self {
+            Json::Str(s) => Some(s.as_str()),
+            _ => None,
+        }
+    }
+    /// Items of an array, empty if not an array.
+    pub fn arr(&self) -> &[Json] {
+        match self {
+            Json::Arr(list) => list,
+            _ => &[],
+        }
+    }
+    /// Numbers of an array (non-numbers are 0).
+    pub fn nums(&self) -> Vec<f32> {
+        self.arr().iter().map(|v| v.num().unwrap_or_default() as f32).collect()
+    }
+}
+
+impl From<bool> for Json {
+    fn from(v: bool) -> Self {
+        Json::Bool(v)
+    }
+}
+macro_rules! impl_FromJson_num {
+    ($($ty:ty),+) => {
+        $(impl From<$ty> for Json {
+            fn from(v: $ty) -> Self {
+                Json::Num(v as f64)
+            }
+        })+
+    };
+}
+impl_FromJson_num!(f32, f64, i32, u32, usize);
+impl From<&str> for Json {
+    fn from(v: &str) -> Self {
+        Json::Str(v.s())
+    }
+}
+impl From<String> for Json {
+    fn from(v: String) -> Self {
+        Json::Str(v)
+    }
+}
+impl<T: Into<Json>> From<Vec<T>> for Json {
+    fn from(v: Vec<T>) -> Self {
+        Json::Arr(v.into_iter().map(|x| x.into()).collect())
+    }
+}
+impl<T: Into<Json> + Copy, const N: usize> From<[T; N]> for Json {
+    fn from(v: [T; N]) -> Self {
+        Json::Arr(v.iter().map(|x| (*x).into()).collect())
+    }
+}
+
+//#endregion
+//#region parse
+
+impl Json {
+    pub fn parse(s: &str) -> Result<Json, MyError> {
+        let mut p = JsonParser { s: s.as_bytes(), pos: 0 };
+        let v = p.value()?;
+        p.skip_ws();
+        yes!(p.pos < p.s.len(), return p.error("unexpected trailing characters"));
+        return Ok(v);
+    }
+}
+
+struct JsonParser<'a> {
+    s: &'a [u8],
+    pos: usize,
+}
+
+impl JsonParser<'_> {
+    fn error<T>(&self, what: &str) -> Result<T, MyError> {
+        ERR!("Invalid json at offset {}: {}", self.pos, what)
+    }
+    fn skip_ws(&mut self) {
+        while self.pos < self.s.len() && self.s[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_ws();
+        self.s.get(self.pos).copied()
+    }
+    fn expect(&mut self, c: u8) -> Result<(), MyError> {
+        yes!(self.peek() != Some(c), return self.error(&F!("expect '{}'", c as char)));
+        self.pos += 1;
+        return Ok(());
+    }
+    fn literal(&mut self, word: &str, value: Json) -> Result<Json, MyError> {
+        yes!(
+            !self.s[self.pos..].starts_with(word.as_bytes()),
+            return self.error(&F!("expect {word}"))
+        );
+        self.pos += word.len();
+        return Ok(value);
+    }
+
+    fn value(&mut self) -> Result<Json, MyError> {
+        match self.peek() {
+            Some(b'{') => {
+                self.pos += 1;
+                let mut pairs = vec![];
+                if self.peek() == Some(b'}') {
+                    self.pos += 1;
+                    return Ok(Json::Obj(pairs));
+                }
+                loop {
+                    yes!(self.peek() != Some(b'"'), return self.error("expect a key"));
+                    let key = self.string()?;
+                    self.expect(b':')?;
+                    pairs.push((key, self.value()?));
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b'}') => break,
+                        _ => return self.error("expect ',' or '}'"),
+                    }
+                }
+                self.pos += 1;
+                Ok(Json::Obj(pairs))
+            },
+            Some(b'[') => {
+                self.pos += 1;
+                let mut list = vec![];
+                if self.peek() == Some(b']') {
+                    self.pos += 1;
+                    return Ok(Json::Arr(list));
+                }
+                loop {
+                    list.push(self.value()?);
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b']') => break,
+                        _ => return self.error("expect ',' or ']'"),
+                    }
+                }
+                self.pos += 1;
+                Ok(Json::Arr(list))
+            },
+            Some(b'"') => Ok(Json::Str(self.string()?)),
+            Some(b't') => self.literal("true", Json::Bool(true)),
+            Some(b'f') => self.literal("false", Json::Bool(false)),
+            Some(b'n') => self.literal("null", Json::Null),
+            Some(b'-' | b'0'..=b'9') => {
+                let start = self.pos;
+                while self.pos < self.s.len()
+                    && matches!(self.s[self.pos], b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9')
+                {
+                    self.pos += 1;
+                }
+                let text = String::from_utf8_lossy(&self.s[start..self.pos]);
+                text.parse().map(Json::Num).or_else(|_| self.error("bad number"))
+            },
+            _ => self.error("expect a value"),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, MyError> {
+        self.pos += 1; // "
+        let mut bytes: Vec<u8> = vec![];
+        loop {
+            let Some(&c) = self.s.get(self.pos) else { return self.error("unterminated string") };
+            self.pos += 1;
+            match c {
+                b'"' => break,
+                b'\\' => {
+                    let Some(&e) = self.s.get(self.pos) else {
+                        return self.error("unterminated string");
+                    };
+                    self.pos += 1;
+                    match e {
+                        b'n' => bytes.push(b'\n'),
+                        b'r' => bytes.push(b'\r'),
+                        b't' => bytes.push(b'\t'),
+                        b'b' => bytes.push(8),
+                        b'f' => bytes.push(12),
+                        b'u' => {
+                            let mut code = self.hex4()?;
+                            if (0xD800..0xDC00).contains(&code)
+                                && self.s[self.pos..].starts_with(b"\\u")
+                            {
+                                self.pos += 2;
+                                code = 0x10000
+                                    + ((code - 0xD800) << 10)
+                                    + (self.hex4()?.wrapping_sub(0xDC00) & 0x3FF);
+                            }
+                            let c = char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER);
+                            bytes.extend(c.encode_utf8(&mut [0; 4]).as_bytes());
+                        },
+                        other => bytes.push(other), // " \ /
+                    }
+                },
+                c => bytes.push(c),
+            }
+        }
+        return Ok(String::from_utf8_lossy(&bytes).into_owned());
+    }
+
+    fn hex4(&mut self) -> Result<u32, MyError> {
+        let hex = self.s.get(self.pos..self.pos + 4).and_then(|h| std::str::from_utf8(h).ok());
+        let Some(code) = hex.and_then(|h| u32::from_str_radix(h, 16).ok()) else {
+            return self.error("bad \\u escape");
+        };
+        self.pos += 4;
+        return Ok(code);
+    }
+}
+
+//#endregion
+//#region trait: Display
+
+impl Display for Json {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(v) => write!(f, "{v}"),
+            Json::Num(v) if !v.is_finite() => write!(f, "null"),
+            Json::Num(v) if v.fract() == 0.0 && v.abs() < 1e15 => write!(f, "{}", *v as i64),
+            Json::Num(v) => write!(f, "{}", *v as f32), // values come from f32s
+            Json::Str(s) => write_json_str(f, s),
+            Json::Arr(list) => {
+                write!(f, "[")?;
+                for (i, v) in list.iter().enumerate() {
+                    yes!(i > 0, write!(f, ",")?);
+                    write!(f, "{v}")?;
+                }
+                write!(f, "]")
+            },
+            Json::Obj(pairs) => {
+                write!(f, "{{")?;
+                for (i, (k, v)) in pairs.iter().enumerate() {
+                    yes!(i > 0, write!(f, ",")?);
+                    write_json_str(f, k)?;
+                    write!(f, ":{v}")?;
+                }
+                write!(f, "}}")
+            },
+        }
+    }
+}
+
+fn write_json_str(f: &mut std::fmt::Formatter<'_>, s: &str) -> std::fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    write!(f, "\"")
+}
+
+//#endregion
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_escapes() {
+        let v = Json::parse(r#""a\"b\\c\/d\n\t\u00e9\ud83d\ude00""#).unwrap();
+        assert_eq!(v.str(), Some("a\"b\\c/d\n\t\u{e9}\u{1f600}"));
+        assert!(Json::parse(r#""\u12""#).is_err());
+        assert!(Json::parse(r#""abc"#).is_err());
+    }
+
+    #[test]
+    fn parse_numbers() {
+        let v = Json::parse("[0, -1, 2.5, 1e3, -1.25E-2]").unwrap();
+        assert_eq!(v.nums(), vec![0.0, -1.0, 2.5, 1000.0, -0.0125]);
+        assert_eq!(v.at(1).usize(), None);
+        assert_eq!(v.at(3).usize(), Some(1000));
+        assert!(Json::parse("1.2.3").is_err());
+        assert!(Json::parse("-").is_err());
+    }
+
+    #[test]
+    fn parse_nesting() {
+        let text = r#" { "a": [1, { "b": [] }, null], "c": { "d": true, "e": {} } } "#;
+        let v = Json::parse(text).unwrap();
+        assert_eq!(v.get("a").arr().len(), 3);
+        assert_eq!(v.get("a").at(1).get("b"), &Json::Arr(vec![]));
+        assert!(v.get("a").at(2).is_null());
+        assert_eq!(v.get("c").get("d"), &Json::Bool(true));
+        assert_eq!(v.get("c").get("e"), &Json::Obj(vec![]));
+        assert!(v.get("missing").is_null());
+        assert!(Json::parse(r#"{ "a": [1, 2 }"#).is_err());
+        assert!(Json::parse("[1] 2").is_err());
+    }
+
+    #[test]
+    fn write_and_parse() {
+        let v = JOBJ! { "s" => "q\"\n\u{1}", "n" => vec![1.5_f32, -2.0], "o" => JOBJ! {} };
+        let text = v.to_string();
+        assert_eq!(text, r#"{"s":"q\"\n\u0001","n":[1.5,-2],"o":{}}"#);
+        assert_eq!(Json::parse(&text).unwrap(), v);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::*;
+use std::sync::atomic::{AtomicU8, Ordering};
 
 //#region level
 
@@ -13,9 +14,25 @@ pub enum LogLevel {
     Error,
 }
 
+static LOG_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Info as u8);
+
+pub fn log_level() -> LogLevel {
+    match LOG_LEVEL.load(Ordering::Relaxed) {
+        0 => LogLevel::Verbose3,
+        1 => LogLevel::Verbose2,
+        2 => LogLevel::Verbose,
+        3 => LogLevel::Info,
+        4 => LogLevel::Warn,
+        _ => LogLevel::Error,
+    }
+}
+pub fn set_log_level(level: LogLevel) {
+    LOG_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
 #[macro_export]
 macro_rules! check_log_level {
-    (::$item:ident) => {{ *log_level() <= crate::logging::LogLevel::$item }};
+    (::$item:ident) => {{ log_level() <= crate::logging::LogLevel::$item }};
 }
 
 //#endregion
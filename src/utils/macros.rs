@@ -121,3 +121,16 @@ macro_rules! EXIT1 {
 }
 
 //#endregion
+//#region REPAIR
+
+/// Log a repair made in salvage mode, see [`MdlxData::repairs`].
+macro_rules! REPAIR {
+    ($repairs:expr, $($arg:tt)*) => {{
+        let msg = F!($($arg)*);
+        vlog!("Repaired: {msg}");
+        $repairs.push(msg);
+    }};
+}
+pub(crate) use REPAIR;
+
+//#endregion
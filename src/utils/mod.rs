@@ -1,6 +1,7 @@
 pub mod debug;
 pub mod error;
 pub mod format;
+pub mod json;
 pub mod logging;
 pub mod macros;
 pub mod time;
@@ -10,6 +11,7 @@ pub mod types;
 pub use debug::*;
 pub use error::*;
 pub use format::*;
+pub use json::*;
 pub use logging::*;
 #[allow(unused_imports)]
 pub use macros::*;
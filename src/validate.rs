@@ -0,0 +1,495 @@
+use crate::*;
+
+//#region Diagnostic
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub path: String,      // e.g. "Material[1].Layer[0]", "Bone[3].Rotation"
+    pub line: Option<u32>, // of the outermost object, only when read from mdl
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut stdFormatter<'_>) -> stdResult {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{severity}: {}", self.path)?;
+        if let Some(line) = self.line {
+            write!(f, " (line {line})")?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+//#endregion
+//#region validate
+
+impl MdlxData {
+    /// Check cross references (indices, ids) and counts, which are not checked when reading.
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut v = Validator { data: self, diags: vec![] };
+        v.check_sequences();
+        v.check_materials();
+        v.check_geosets();
+        v.check_geoanims();
+        v.check_nodes();
+        v.check_anims();
+        return v.diags;
+    }
+}
+
+struct Validator<'a> {
+    data: &'a MdlxData,
+    diags: Vec<Diagnostic>,
+}
+
+impl<'a> Validator<'a> {
+    fn push(&mut self, severity: Severity, path: &str, message: String) {
+        let key = path.split('.').next().unwrap_or(path);
+        let line = self.data.mdl_lines.get(key).copied();
+        self.diags.push(Diagnostic { severity, path: path.s(), line, message });
+    }
+    fn error(&mut self, path: &str, message: String) {
+        self.push(Severity::Error, path, message);
+    }
+    fn warn(&mut self, path: &str, message: String) {
+        self.push(Severity::Warning, path, message);
+    }
+
+    /// index must be in 0..count, or -1 if optional.
+    fn check_index(
+        &mut self,
+        path: &str,
+        field: &str,
+        index: i32,
+        count: usize,
+        what: &str,
+        optional: bool,
+    ) {
+        yes!(optional && index == -1, return);
+        if index < 0 || index as usize >= count {
+            self.error(path, F!("{field} {index} is out of range ({count} {what})"));
+        }
+    }
+
+    fn check_sequences(&mut self) {
+        for (i, a) in self.data.sequences.iter().enumerate() {
+            if a.start_frame > a.end_frame {
+                let msg = F!("Interval [{}, {}] is reversed", a.start_frame, a.end_frame);
+                self.warn(&F!("Sequence[{i}]"), msg);
+            }
+        }
+    }
+
+    fn check_materials(&mut self) {
+        let (ntex, ntexanim) = (self.data.textures.len(), self.data.texanims.len());
+        for (i, m) in self.data.materials.iter().enumerate() {
+            yes!(m.layers.is_empty(), self.warn(&F!("Material[{i}]"), F!("No layers")));
+            for (j, a) in m.layers.iter().enumerate() {
+                let path = F!("Material[{i}].Layer[{j}]");
+                self.check_index(&path, "TextureID", a.texture_id, ntex, "textures", false);
+                self.check_index(
+                    &path,
+                    "TVertexAnimId",
+                    a.texture_anim_id,
+                    ntexanim,
+                    "texture anims",
+                    true,
+                );
+                let mut keys = a.texid_anim.iter().flat_map(|anim| anim.key_frames.iter());
+                if let Some(k) = keys.find(|k| k.value < 0 || k.value as usize >= ntex) {
+                    let msg = F!(
+                        "TextureID {} at frame {} is out of range ({ntex} textures)",
+                        k.value,
+                        k.frame
+                    );
+                    self.error(&F!("{path}.TextureID"), msg);
+                }
+            }
+        }
+    }
+
+    fn check_geosets(&mut self) {
+        let nmat = self.data.materials.len();
+        let ids: HashSet<i32> = self.data.nodes().iter().map(|(_, n)| n.object_id).collect();
+        for (i, g) in self.data.geosets.iter().enumerate() {
+            let path = F!("Geoset[{i}]");
+            let nvtx = g.vertices.len();
+            self.check_index(&path, "MaterialID", g.material_id, nmat, "materials", false);
+
+            if let Some(v) = g.face_vertices.iter().find(|&&v| v as usize >= nvtx) {
+                self.error(&path, F!("Face vertex {v} is out of range ({nvtx} vertices)"));
+            }
+            let nface: i32 = g.face_vtxcnts.iter().sum();
+            if nface as usize != g.face_vertices.len() {
+                self.error(
+                    &path,
+                    F!(
+                        "Face vertex count {} != sum of face group counts {nface}",
+                        g.face_vertices.len()
+                    ),
+                );
+            }
+            if !g.normals.is_empty() && g.normals.len() != nvtx {
+                self.error(&path, F!("Normal count {} != vertex count {nvtx}", g.normals.len()));
+            }
+            for (j, uvs) in g.uvss.iter().enumerate() {
+                if uvs.len() != nvtx {
+                    self.error(
+                        &path,
+                        F!("TVertices[{j}] count {} != vertex count {nvtx}", uvs.len()),
+                    );
+                }
+            }
+
+            let nmtx = g.mtxgrpcnts.len();
+            if g.skin_weights.is_empty() && g.vtxgrps.len() != nvtx {
+                self.error(
+                    &path,
+                    F!("VertexGroup count {} != vertex count {nvtx}", g.vtxgrps.len()),
+                );
+            }
+            if let Some(v) = g.vtxgrps.iter().find(|&&v| v as usize >= nmtx) {
+                self.error(&path, F!("VertexGroup {v} is out of range ({nmtx} matrix groups)"));
+            }
+            let nidx: i32 = g.mtxgrpcnts.iter().sum();
+            if nidx as usize != g.mtx_indices.len() {
+                self.error(
+                    &path,
+                    F!(
+                        "Matrix index count {} != sum of matrix group counts {nidx}",
+                        g.mtx_indices.len()
+                    ),
+                );
+            }
+            if let Some(v) = g.mtx_indices.iter().find(|v| !ids.contains(v)) {
+                self.error(&path, F!("Matrix index {v} is not an existing node ObjectId"));
+            }
+        }
+    }
+
+    fn check_geoanims(&mut self) {
+        let ngeo = self.data.geosets.len();
+        let mut used: HashSet<i32> = HashSet::new();
+        for (i, a) in self.data.geoanims.iter().enumerate() {
+            let path = F!("GeosetAnim[{i}]");
+            self.check_index(&path, "GeosetId", a.geoset_id, ngeo, "geosets", false);
+            if !used.insert(a.geoset_id) {
+                self.warn(
+                    &path,
+                    F!("GeosetId {} is already animated by another GeosetAnim", a.geoset_id),
+                );
+            }
+        }
+    }
+
+    fn check_nodes(&mut self) {
+        let nodes = self.data.nodes();
+        let mut ids: HashMap<i32, &str> = HashMap::new();
+        for (path, n) in nodes.iter() {
+            if let Some(other) = ids.insert(n.object_id, path) {
+                self.error(path, F!("ObjectId {} is already used by {other}", n.object_id));
+            }
+        }
+        for (path, n) in nodes.iter() {
+            if n.parent_id == n.object_id {
+                self.error(path, F!("Parent {} is the node itself", n.parent_id));
+            } else if n.parent_id != -1 && !ids.contains_key(&n.parent_id) {
+                self.error(path, F!("Parent {} is not an existing node ObjectId", n.parent_id));
+            }
+        }
+        if let Some(max) = nodes.iter().map(|(_, n)| n.object_id).max() {
+            yes!(
+                max as usize >= nodes.len(),
+                self.warn(
+                    "Model",
+                    F!("ObjectIds are not contiguous (max {max}, {} nodes)", nodes.len())
+                )
+            );
+        }
+
+        let npivot = self.data.pivot_points.len();
+        if npivot != nodes.len() {
+            self.warn("PivotPoint", F!("Pivot point count {npivot} != node count {}", nodes.len()));
+        }
+
+        let (ngeo, ngeoanim, nmat, ntex) = (
+            self.data.geosets.len(),
+            self.data.geoanims.len(),
+            self.data.materials.len(),
+            self.data.textures.len(),
+        );
+        for (i, a) in self.data.bones.iter().enumerate() {
+            let path = F!("Bone[{i}]");
+            self.check_index(&path, "GeosetId", a.geoset_id, ngeo, "geosets", true);
+            self.check_index(&path, "GeosetAnimId", a.geoanim_id, ngeoanim, "geoset anims", true);
+        }
+        for (i, a) in self.data.ribbon_emitters.iter().enumerate() {
+            self.check_index(
+                &F!("RibbonEmitter[{i}]"),
+                "MaterialID",
+                a.material_id,
+                nmat,
+                "materials",
+                false,
+            );
+        }
+        for (i, a) in self.data.particle_emitters2.iter().enumerate() {
+            self.check_index(
+                &F!("ParticleEmitter2[{i}]"),
+                "TextureID",
+                a.texture_id,
+                ntex,
+                "textures",
+                false,
+            );
+        }
+    }
+
+    fn check_anims(&mut self) {
+        let mut checker = AnimChecker { data: self.data, path: String::new(), issues: vec![] };
+        self.data.visit_anims(&mut checker);
+        for (severity, path, message) in checker.issues {
+            self.push(severity, &path, message);
+        }
+    }
+}
+
+/// Check keys and global sequence of each animation, see Validator::check_anims().
+struct AnimChecker<'a> {
+    data: &'a MdlxData,
+    path: String,                            // of the current object
+    issues: Vec<(Severity, String, String)>, // (severity, path, message)
+}
+
+impl AnimVisitor for AnimChecker<'_> {
+    fn enter(&mut self, path: &str) {
+        self.path = path.s();
+    }
+
+    fn visit<T: Interpolate>(&mut self, name: &str, anim: &Animation<T>) {
+        let path = F!("{}.{name}", self.path);
+        let mut issue = |severity: Severity, message: String| {
+            self.issues.push((severity, path.clone(), message))
+        };
+
+        let frames: Vec<i32> = anim.key_frames.iter().map(|k| k.frame).collect();
+        if let Some(w) = frames.windows(2).find(|w| w[1] < w[0]) {
+            issue(Severity::Error, F!("Keys are not sorted (frame {} after {})", w[1], w[0]));
+        }
+        let ndup = frames.windows(2).filter(|w| w[1] == w[0]).count();
+        if let Some(w) = frames.windows(2).find(|w| w[1] == w[0]) {
+            issue(Severity::Warning, F!("{ndup} duplicate keys (first at frame {})", w[0]));
+        }
+
+        let (id, nglobal) = (anim.global_seq_id, self.data.globalseqs.len());
+        if id >= 0 && (id as usize) < nglobal {
+            let duration = self.data.globalseqs[id as usize].duration as i64;
+            let outside: Vec<&i32> =
+                frames.iter().filter(|&&f| f < 0 || f as i64 > duration).collect();
+            if let Some(f) = outside.first() {
+                let n = outside.len();
+                issue(
+                    Severity::Warning,
+                    F!(
+                        "{n} keys outside GlobalSequence[{id}] (0 ~ {duration}), first at frame {f}"
+                    ),
+                );
+            }
+        } else if id != -1 {
+            issue(
+                Severity::Error,
+                F!("GlobalSeqId {id} is out of range ({nglobal} global sequences)"),
+            );
+        } else if !self.data.sequences.is_empty() && !frames.is_empty() {
+            let seqs = &self.data.sequences;
+            let inside = |f: i32| seqs.iter().any(|s| s.start_frame <= f && f <= s.end_frame);
+            let outside: Vec<&i32> = frames.iter().filter(|&&f| !inside(f)).collect();
+            if let Some(f) = outside.first() {
+                issue(
+                    Severity::Warning,
+                    F!("{} keys outside every sequence, first at frame {f}", outside.len()),
+                );
+            }
+
+            let mut missing: Vec<String> = vec![];
+            for s in seqs.iter() {
+                let keys: Vec<&i32> =
+                    frames.iter().filter(|&&f| s.start_frame <= f && f <= s.end_frame).collect();
+                yes!(keys.is_empty(), continue);
+                yes!(!keys.contains(&&s.start_frame), missing.push(F!("{:?} start", s.name)));
+                yes!(!keys.contains(&&s.end_frame), missing.push(F!("{:?} end", s.name)));
+            }
+            if !missing.is_empty() {
+                issue(
+                    Severity::Warning,
+                    F!("No keys at sequence boundaries: {}", missing.join(", ")),
+                );
+            }
+        }
+
+        if name == "Rotation" {
+            let bad =
+                |q: &T| q.as_quat().is_some_and(|q| (q.length() - 1.0).abs() > Self::QUAT_EPSILON);
+            let keys: Vec<&KeyFrame<T>> = anim
+                .key_frames
+                .iter()
+                .filter(|k| bad(&k.value) || (k.has_tans && (bad(&k.itan) || bad(&k.otan))))
+                .collect();
+            if let Some(k) = keys.first() {
+                let n = keys.len();
+                issue(
+                    Severity::Warning,
+                    F!("{n} rotation keys are not normalized, first at frame {}", k.frame),
+                );
+            }
+        }
+    }
+}
+
+impl AnimChecker<'_> {
+    const QUAT_EPSILON: f32 = 1e-3;
+}
+
+//#endregion
+//#region fix_anims
+
+impl MdlxData {
+    /// Sort keys, remove duplicate keys and normalize rotations of all animations.
+    /// Return what were fixed, e.g. "Bone[0].Rotation: sorted keys".
+    pub fn fix_anims(&mut self) -> Vec<String> {
+        let mut fixer = AnimFixer::default();
+        self.visit_anims_mut(&mut fixer);
+        return fixer.fixes;
+    }
+}
+
+#[derive(Default)]
+struct AnimFixer {
+    path: String, // of the current object
+    fixes: Vec<String>,
+}
+
+impl AnimVisitorMut for AnimFixer {
+    fn enter(&mut self, path: &str) {
+        self.path = path.s();
+    }
+
+    fn visit_mut<T: Interpolate>(&mut self, name: &str, anim: &mut Animation<T>) {
+        let mut fixes: Vec<String> = vec![];
+        yes!(anim.sort_keys(), fixes.push(F!("sorted keys")));
+        let n = anim.dedup_keys();
+        yes!(n > 0, fixes.push(F!("removed {n} duplicate keys")));
+
+        if name == "Rotation" {
+            let mut n = 0;
+            for k in anim.key_frames.iter_mut() {
+                let mut fixed = Self::normalize(k.value.as_quat_mut());
+                if k.has_tans {
+                    // squad takes the tangents as quaternions too
+                    fixed |= Self::normalize(k.itan.as_quat_mut());
+                    fixed |= Self::normalize(k.otan.as_quat_mut());
+                }
+                yes!(fixed, n += 1);
+            }
+            yes!(n > 0, fixes.push(F!("normalized {n} rotation keys")));
+        }
+
+        if !fixes.is_empty() {
+            self.fixes.push(F!("{}.{name}: {}", self.path, fixes.join(", ")));
+        }
+    }
+}
+
+impl AnimFixer {
+    /// Return true if `q` was changed.
+    fn normalize(q: Option<&mut Vec4>) -> bool {
+        let Some(q) = q else { return false };
+        let len = q.length();
+        yes!((len - 1.0).abs() <= AnimChecker::QUAT_EPSILON || len == 0.0, return false);
+        *q /= len;
+        return true;
+    }
+}
+
+//#endregion
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model() -> MdlxData {
+        let mut data = MdlxData::default();
+        data.textures.push(Texture::default());
+        let mut material = Material::default();
+        material.layers.push(Layer { texture_id: 1, ..Default::default() });
+        data.materials.push(material);
+
+        let mut g = Geoset::default();
+        g.vertices = vec![Vec3::ZERO; 3];
+        (g.face_vtxcnts, g.face_vertices) = (vec![3], vec![0, 1, 3]);
+        (g.vtxgrps, g.mtxgrpcnts, g.mtx_indices) = (vec![0; 3], vec![1], vec![0]);
+        data.geosets.push(g);
+        data.geoanims.push(GeosetAnim::default());
+        data.geoanims.push(GeosetAnim::default());
+
+        for parent_id in [-1, 1] {
+            let mut bone = Bone::default();
+            (bone.base.object_id, bone.base.parent_id) = (data.bones.len() as i32, parent_id);
+            data.bones.push(bone);
+            data.pivot_points.push(PivotPoint::default());
+        }
+        data.mdl_lines.insert("Bone[1]".s(), 42);
+
+        let key = |frame, value| KeyFrame { frame, value, ..Default::default() };
+        data.bones[0].base.rotation = Some(Animation {
+            key_frames: vec![key(100, Vec4::W), key(0, Vec4::W * 2.0), key(0, Vec4::W)],
+            ..Default::default()
+        });
+        data.sequences.push(Sequence { end_frame: 100, ..Default::default() });
+        return data;
+    }
+
+    fn diags(data: &MdlxData) -> Vec<String> {
+        data.validate().iter().map(|d| d.to_string()).collect()
+    }
+
+    #[test]
+    fn validate() {
+        assert_eq!(
+            diags(&model()),
+            [
+                "error: Material[0].Layer[0]: TextureID 1 is out of range (1 textures)",
+                "error: Geoset[0]: Face vertex 3 is out of range (3 vertices)",
+                "warning: GeosetAnim[1]: GeosetId 0 is already animated by another GeosetAnim",
+                "error: Bone[1] (line 42): Parent 1 is the node itself",
+                "error: Bone[0].Rotation: Keys are not sorted (frame 0 after 100)",
+                "warning: Bone[0].Rotation: 1 duplicate keys (first at frame 0)",
+                "warning: Bone[0].Rotation: 1 rotation keys are not normalized, first at frame 0",
+            ]
+        );
+    }
+
+    #[test]
+    fn fix_anims() {
+        let mut data = model();
+        assert_eq!(
+            data.fix_anims(),
+            ["Bone[0].Rotation: sorted keys, removed 1 duplicate keys, normalized 1 rotation keys"]
+        );
+        let anim = data.bones[0].base.rotation.as_ref().unwrap();
+        let keys: Vec<_> = anim.key_frames.iter().map(|k| (k.frame, k.value)).collect();
+        assert_eq!(keys, [(0, Vec4::W), (100, Vec4::W)]);
+        assert!(diags(&data).iter().all(|d| !d.contains("Rotation")));
+        assert!(data.fix_anims().is_empty());
+    }
+}
@@ -0,0 +1,211 @@
+use crate::*;
+
+//#region VerifyResult
+
+#[derive(Debug)]
+pub enum VerifyResult {
+    Lossless,
+    /// `offset`: the first differing byte of the files.
+    /// `diffs`: chunks which differ, none if only their order does.
+    Lossy {
+        offset: u32,
+        diffs: Vec<ChunkDiff>,
+    },
+}
+
+/// The first difference of a chunk after MDX -> MDL -> MDX.
+#[derive(Debug)]
+pub struct ChunkDiff {
+    pub id: u32,
+    pub nth: usize,                        // nth chunk of this id
+    pub offset: Option<u32>, // first differing offset in chunk body, none if missing on one side
+    pub field: Option<u32>,  // the nearest tag (see MdlxMagic::TAGS) before offset
+    pub sizes: (Option<u32>, Option<u32>), // body sizes of (original, round-trip)
+}
+
+impl Display for ChunkDiff {
+    fn fmt(&self, f: &mut stdFormatter<'_>) -> stdResult {
+        write!(f, "{}[{}]: ", u32_to_ascii(self.id), self.nth)?;
+        match (self.sizes, self.offset) {
+            ((Some(_), None), _) => write!(f, "missing after round-trip"),
+            ((None, _), _) => write!(f, "extra after round-trip"),
+            ((Some(a), Some(b)), Some(offset)) => {
+                write!(f, "differs at offset {offset} (0x{offset:X})")?;
+                if let Some(tag) = self.field {
+                    write!(f, " in {}", u32_to_ascii(tag))?;
+                }
+                yes!(a != b, write!(f, ", size {a} -> {b}")?);
+                Ok(())
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+impl VerifyResult {
+    /// Compare two mdx files chunk by chunk (nth chunk of the same id).
+    /// Any difference is lossy, even if all chunks are equal but in another order.
+    pub fn compare(original: &Vec<u8>, output: &Vec<u8>) -> Result<Self, MyError> {
+        yes!(original == output, return Ok(Self::Lossless));
+        let n = original.len().min(output.len());
+        let offset = (0..n).find(|&k| original[k] != output[k]).unwrap_or(n) as u32;
+
+        let (chunks1, chunks2) = (Self::split(original)?, Self::split(output)?);
+        let mut used = vec![false; chunks2.len()];
+        let mut diffs: Vec<ChunkDiff> = vec![];
+        for (i, a) in chunks1.iter().enumerate() {
+            let nth = chunks1[..i].iter().filter(|c| c.id == a.id).count();
+            let found = chunks2.iter().enumerate().filter(|(_, c)| c.id == a.id).nth(nth);
+            let Some((j, b)) = found else {
+                diffs.push(ChunkDiff {
+                    id: a.id,
+                    nth,
+                    offset: None,
+                    field: None,
+                    sizes: (Some(a.size), None),
+                });
+                continue;
+            };
+            used[j] = true;
+            yes!(a.body == b.body, continue);
+
+            let n = a.body.len().min(b.body.len());
+            let offset = (0..n).find(|&k| a.body[k] != b.body[k]).unwrap_or(n);
+            let field = Self::tag_before(&a.body, offset);
+            let (offset, sizes) = (Some(offset as u32), (Some(a.size), Some(b.size)));
+            diffs.push(ChunkDiff { id: a.id, nth, offset, field, sizes });
+        }
+        for (j, b) in chunks2.iter().enumerate() {
+            if !used[j] {
+                let nth = chunks2[..j].iter().filter(|c| c.id == b.id).count();
+                diffs.push(ChunkDiff {
+                    id: b.id,
+                    nth,
+                    offset: None,
+                    field: None,
+                    sizes: (None, Some(b.size)),
+                });
+            }
+        }
+
+        return Ok(Self::Lossy { offset, diffs });
+    }
+
+    fn split(input: &Vec<u8>) -> Result<Vec<MdxChunk>, MyError> {
+        let mut cur = Cursor::new(input);
+        let mut chunks = vec![];
+        _ = cur.read_be::<u32>()?; // magic
+        while !cur.eol() {
+            chunks.push(MdxChunk::read_mdx(&mut cur)?);
+        }
+        return Ok(chunks);
+    }
+
+    fn tag_before(body: &[u8], offset: usize) -> Option<u32> {
+        yes!(body.len() < 4, return None);
+        let end = offset.min(body.len() - 4);
+        let tag_at = |i: usize| u32::from_be_bytes(body[i..i + 4].try_into().unwrap());
+        (0..=end).rev().map(tag_at).find(|t| MdlxMagic::TAGS.contains(t))
+    }
+}
+
+//#endregion
+
+impl MdlxData {
+    /// MDX -> MDL (in memory) -> MDX, then compare with the original.
+    /// Decimal numbers are always written losslessly, whatever `wopts.precision` is.
+    pub fn verify_mdx(
+        input: &Vec<u8>,
+        ropts: &MdlReadOptions,
+        wopts: &MdlWriteOptions,
+    ) -> Result<VerifyResult, MyError> {
+        let wopts = MdlWriteOptions { lossless: true, ..wopts.clone() };
+        let mdl = Self::read_mdx(input)?.to_mdl_string(&wopts)?;
+        let output =
+            Self::read_mdl(&mdl, ropts).map_err(|e| e.within("Re-reading mdl"))?.to_mdx_bytes()?;
+        return VerifyResult::compare(input, &output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mdx(chunks: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+        let mut out = b"MDLX".to_vec();
+        for (id, body) in chunks {
+            out.extend_from_slice(*id);
+            out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+            out.extend_from_slice(body);
+        }
+        return out;
+    }
+
+    #[test]
+    fn lossless() {
+        let a = mdx(&[(b"VERS", &[1, 2, 3, 4]), (b"SEQS", &[5, 6])]);
+        assert!(matches!(VerifyResult::compare(&a, &a.clone()).unwrap(), VerifyResult::Lossless));
+    }
+
+    #[test]
+    fn reordered_chunks() {
+        let a = mdx(&[(b"VERS", &[1, 2, 3, 4]), (b"SEQS", &[5, 6])]);
+        let b = mdx(&[(b"SEQS", &[5, 6]), (b"VERS", &[1, 2, 3, 4])]);
+        match VerifyResult::compare(&a, &b).unwrap() {
+            VerifyResult::Lossy { offset, diffs } => {
+                assert_eq!(offset, 4);
+                assert!(diffs.is_empty());
+            },
+            VerifyResult::Lossless => panic!("reordered chunks verified as lossless"),
+        }
+    }
+
+    #[test]
+    fn changed_chunk() {
+        let a = mdx(&[(b"VERS", &[1, 2, 3, 4]), (b"SEQS", &[5, 6])]);
+        let b = mdx(&[(b"VERS", &[1, 2, 3, 4]), (b"SEQS", &[5, 7])]);
+        match VerifyResult::compare(&a, &b).unwrap() {
+            VerifyResult::Lossy { offset, diffs } => {
+                assert_eq!(offset, 4 + 12 + 8 + 1);
+                assert_eq!(diffs.len(), 1);
+                assert_eq!((diffs[0].id, diffs[0].offset), (MdlxMagic::SEQS, Some(1)));
+            },
+            VerifyResult::Lossless => panic!("changed chunk verified as lossless"),
+        }
+    }
+
+    #[test]
+    fn verify_is_lossless() {
+        let mut data = MdlxData::default();
+        data.version.format_version = 800;
+        data.pivot_points.push(PivotPoint { position: Vec3::new(0.1234567, -1.0e-7, 12345.678) });
+        let input = data.to_mdx_bytes().unwrap();
+
+        let wopts = MdlWriteOptions { precision: 2, ..Default::default() };
+        let ret = MdlxData::verify_mdx(&input, &MdlReadOptions::default(), &wopts).unwrap();
+        assert!(matches!(ret, VerifyResult::Lossless), "{ret:?}");
+    }
+
+    #[test]
+    fn verify_unknown_chunk_and_track() {
+        let mut data = MdlxData::default();
+        data.version.format_version = 800;
+        let chunk = MdxChunk::from_raw(u32::from_be_bytes(*b"ZZZZ"), vec![1, 2, 3]);
+        data.unknown_chunks.push(UnknownChunk { after: MdlxMagic::VERS, chunk });
+
+        // 1 key, DontInterp, no global sequence, frame 10 => 1.0; before the known track
+        let body = [1u32, 0, u32::MAX, 10, 1.0f32.to_bits()].map(u32::to_le_bytes).concat();
+        let track = MdxChunk::from_raw(u32::from_be_bytes(*b"KZZZ"), body);
+        let key = KeyFrame { frame: 0, value: Vec3::ONE, ..Default::default() };
+        data.texanims.push(TextureAnim {
+            translation: Some(Animation { key_frames: vec![key], ..Default::default() }),
+            unknown_tracks: vec![UnknownTrack { after: 0, track }],
+            ..Default::default()
+        });
+        let input = data.to_mdx_bytes().unwrap();
+
+        let (ropts, wopts) = (MdlReadOptions::default(), MdlWriteOptions::default());
+        let ret = MdlxData::verify_mdx(&input, &ropts, &wopts).unwrap();
+        assert!(matches!(ret, VerifyResult::Lossless), "{ret:?}");
+    }
+}
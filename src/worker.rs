@@ -15,6 +15,8 @@ struct Job {
 #[derive(Debug)]
 enum JobResult {
     Ok,
+    Lossy, // verify only
+    Warn,  // lint only
     Err,
 }
 
@@ -37,13 +39,34 @@ lazy_static! {
     static ref STOP: StopSignal = StopSignal { signal: Arc::new(AtomicBool::new(false)) };
 }
 
+//#endregion
+//#region WorkerOptions
+
+/// What to do with each job, see `Args::worker_options()`.
+#[derive(Debug, Default, Clone)]
+pub struct WorkerOptions {
+    pub read: MdlReadOptions,
+    pub write: MdlWriteOptions,
+    pub export: ExportOptions,
+    pub normals: Option<NormalOptions>, // recompute normals before writing
+    pub add_bvh: Option<PathBuf>,       // add the motion of a BVH file as a new sequence
+    pub fix: bool,                      // fix animations before writing
+    pub recalc_extents: bool,
+    pub verify: bool, // verify mdx round trips instead of converting
+    pub lint: bool,   // validate instead of converting
+    pub stop_on_error: bool,
+}
+
 //#endregion
 
 #[derive(Default)]
 pub struct Worker {
+    opts: Arc<WorkerOptions>,
     start: i128,
     total: i32,
     ok: i32,
+    lossy: i32,
+    warn: i32,
     skip: i32,
     fail: i32,
     workers: Vec<thread::JoinHandle<()>>,
@@ -52,11 +75,12 @@ pub struct Worker {
 }
 
 impl Worker {
-    pub fn init() -> Self {
+    pub fn init(opts: WorkerOptions) -> Self {
         let (jobtx, jobrx) = channel::<Job>();
         let (restx, resrx) = channel::<JobResult>();
         let jobrx = Arc::new(Mutex::new(jobrx)); // to share it across threads
         let mut this = Build! { start: timestamp_ms(), jobtx: Some(jobtx), resrx: Some(resrx) };
+        this.opts = Arc::new(opts);
         let stop_signal = STOP.clone();
 
         let old_hook = panic::take_hook();
@@ -70,7 +94,8 @@ impl Worker {
             let jobrx = Arc::clone(&jobrx);
             let restx = restx.clone();
             let stop = stop_signal.clone();
-            let handle = thread::spawn(move || Self::thread(id, jobrx, restx, stop));
+            let opts = Arc::clone(&this.opts);
+            let handle = thread::spawn(move || Self::thread(id, jobrx, restx, stop, opts));
             this.workers.push(handle);
         }
 
@@ -89,8 +114,14 @@ impl Worker {
         }
     }
 
-    fn thread(_id: usize, rx: Arc<Mutex<Receiver<Job>>>, tx: Sender<JobResult>, stop: StopSignal) {
-        let stop_on_error = *stop_on_error!();
+    fn thread(
+        _id: usize,
+        rx: Arc<Mutex<Receiver<Job>>>,
+        tx: Sender<JobResult>,
+        stop: StopSignal,
+        opts: Arc<WorkerOptions>,
+    ) {
+        let (ropts, wopts, eopts) = (&opts.read, &opts.write, &opts.export);
         let mut dead = false;
         while !(dead || stop.get()) {
             // *Q: why using let instead of directly assigning?
@@ -102,11 +133,31 @@ impl Worker {
             dead = match job {
                 Err(_) => true, // caused by drop(sender)
                 Ok(job) => {
-                    match MdlxData::read(&job.input).and_then(|mut a| a.write(&job.output)) {
-                        Ok(_) => tx.send(JobResult::Ok).is_err(),
+                    let ret = match (opts.verify, opts.lint) {
+                        (true, _) => Self::verify(&job.input, ropts, wopts),
+                        (_, true) => Self::lint(&job.input, ropts),
+                        _ => MdlxData::read(&job.input, ropts).and_then(|mut a| {
+                            Self::report_repairs(&job.input, &a.repairs);
+                            if let Some(bvh) = &opts.add_bvh {
+                                a.import_bvh(bvh).map_err(|e| {
+                                    e.within(F!("Failed to add motion to {:?}", job.input))
+                                })?;
+                            }
+                            yes!(opts.fix, Self::report_fixes(&job.input, &a.fix_anims()));
+                            if let Some(nopts) = &opts.normals {
+                                a.recalc_normals(nopts).map_err(|e| {
+                                    e.within(F!("Failed to recompute normals of {:?}", job.input))
+                                })?;
+                            }
+                            yes!(opts.recalc_extents, a.recalc_extents());
+                            a.write(&job.output, wopts, eopts).and(Ok(JobResult::Ok))
+                        }),
+                    };
+                    match ret {
+                        Ok(res) => tx.send(res).is_err(),
                         Err(e) => {
                             elog!("{}", e);
-                            yes!(stop_on_error, stop.set());
+                            yes!(opts.stop_on_error, stop.set());
                             tx.send(JobResult::Err).is_err()
                         },
                     }
@@ -115,15 +166,81 @@ impl Worker {
         }
     }
 
+    fn verify(
+        input: &Path,
+        ropts: &MdlReadOptions,
+        wopts: &MdlWriteOptions,
+    ) -> Result<JobResult, MyError> {
+        let bytes = fs::read(input)?;
+        let ret = MdlxData::verify_mdx(&bytes, ropts, wopts);
+        match ret.map_err(|e| e.within(F!("Failed to verify file {:?}", input)))? {
+            VerifyResult::Lossless => {
+                log!("Lossless: {}", input.fmtx());
+                return Ok(JobResult::Ok);
+            },
+            VerifyResult::Lossy { offset, diffs } => {
+                let mut report = F!("Lossy: {} (differs from offset {offset})", input.fmtx());
+                yes!(diffs.is_empty(), report += "\n\tchunks in another order");
+                for d in diffs {
+                    report += &F!("\n\t{d}");
+                }
+                wlog!("{report}");
+                return Ok(JobResult::Lossy);
+            },
+        }
+    }
+
+    fn lint(input: &Path, ropts: &MdlReadOptions) -> Result<JobResult, MyError> {
+        let data = MdlxData::read(input, ropts)?;
+        Self::report_repairs(input, &data.repairs);
+        let diags = data.validate();
+        if diags.is_empty() {
+            log!("OK: {}", input.fmtx());
+            return Ok(JobResult::Ok);
+        }
+
+        let errors = diags.iter().any(|d| d.severity == Severity::Error);
+        let mut report = F!("{}: {}", yesno!(errors, "Error", "Warning"), input.fmtx());
+        for d in diags {
+            report += &F!("\n\t{d}");
+        }
+        match errors {
+            true => elog!("{report}"),
+            false => wlog!("{report}"),
+        }
+        return Ok(yesno!(errors, JobResult::Err, JobResult::Warn));
+    }
+
+    fn report_repairs(input: &Path, repairs: &Vec<String>) {
+        yes!(repairs.is_empty(), return);
+        let mut report = F!("Salvaged {} with {} repairs:", input.fmtx(), repairs.len());
+        for r in repairs {
+            report += &F!("\n\t{r}");
+        }
+        wlog!("{report}");
+    }
+
+    fn report_fixes(input: &Path, fixes: &Vec<String>) {
+        yes!(fixes.is_empty(), return);
+        let mut report = F!("Fixed {} animations of {}:", fixes.len(), input.fmtx());
+        for f in fixes {
+            report += &F!("\n\t{f}");
+        }
+        log!("{report}");
+    }
+
     fn handle(&mut self) {
-        let stop_on_error = *stop_on_error!();
+        let stop_on_error = self.opts.stop_on_error;
         if let Some(rx) = &self.resrx {
             while let Ok(result) = rx.recv() {
-                if let JobResult::Ok = result {
-                    self.ok += 1;
-                } else {
-                    self.fail += 1;
-                    yes!(stop_on_error, break);
+                match result {
+                    JobResult::Ok => self.ok += 1,
+                    JobResult::Lossy => self.lossy += 1,
+                    JobResult::Warn => self.warn += 1,
+                    JobResult::Err => {
+                        self.fail += 1;
+                        yes!(stop_on_error, break);
+                    },
                 }
             }
         }
@@ -141,6 +258,19 @@ impl Worker {
 
         let time = timestamp_ms() - self.start;
         let (ok, skip, error) = (self.ok, self.skip, self.fail);
+        if self.opts.verify {
+            let lossy = self.lossy;
+            print!("Verified {} files: {ok} lossless, {lossy} lossy, {error} failed", self.total);
+            println!(", cost {}.{:03}s.", time / 1000, time % 1000);
+            return Ok(());
+        }
+        if self.opts.lint {
+            let (total, warn) = (self.total, self.warn);
+            print!("Linted {total} files: {ok} ok, {warn} with warnings, {error} with errors");
+            println!(", cost {}.{:03}s.", time / 1000, time % 1000);
+            yes!(error > 0, return ERR!("Lint failed: {error} files with errors"));
+            return Ok(());
+        }
         print!("Converted {ok} files");
         yes!(skip > 0, print!(", {skip} skipped"));
         yes!(error > 0, print!(", {error} errors"));